@@ -0,0 +1,51 @@
+
+use std::time::{Duration, Instant};
+
+use matheval::{Expr, Number, NumberContext, F64Context, Context};
+
+const FORMULA: &str = "3x^2 - 2x y + sin(x) * max(y, 1) / (1 + x^2)";
+const ITERATIONS: i64 = 100_000;
+
+fn time<F: FnMut(i64)>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        f(i);
+    }
+    return start.elapsed();
+}
+
+fn report(name: &str, interpreted: Duration, compiled: Duration) {
+    println!(
+        "{:<8} eval_in: {:>10.2?}  compiled: {:>10.2?}  speedup: {:.1}x",
+        name, interpreted, compiled, interpreted.as_secs_f64() / compiled.as_secs_f64()
+    );
+}
+
+fn main() {
+    let expr = Expr::parse(FORMULA).expect("Failed to parse formula");
+    let mut context = NumberContext::new();
+    let compiled = expr.compile(&context, &["x", "y"]).expect("Failed to compile formula");
+
+    let interpreted = time(|i| {
+        context.set_variable("x", Number::Float(i as f64 * 1e-3));
+        context.set_variable("y", Number::Float(2.5));
+        expr.eval_in::<Number, _>(&context).unwrap();
+    });
+    let fast = time(|i| {
+        compiled.eval(&[Number::Float(i as f64 * 1e-3), Number::Float(2.5)]).unwrap();
+    });
+    report("Number", interpreted, fast);
+
+    let mut context = F64Context::new();
+    let compiled = expr.compile(&context, &["x", "y"]).expect("Failed to compile formula");
+
+    let interpreted = time(|i| {
+        context.set_variable("x", i as f64 * 1e-3);
+        context.set_variable("y", 2.5);
+        expr.eval_in::<f64, _>(&context).unwrap();
+    });
+    let fast = time(|i| {
+        compiled.eval(&[i as f64 * 1e-3, 2.5]).unwrap();
+    });
+    report("f64", interpreted, fast);
+}
@@ -0,0 +1,273 @@
+
+use std::ops::*;
+use std::str::FromStr;
+use std::cmp::Ordering;
+use num::traits::Pow;
+
+use crate::{Number, Value, EvalError, EvalErrorKind};
+
+use super::ComplexContext;
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct Complex {
+    re: Number,
+    im: Number,
+}
+
+impl Complex {
+    pub fn new(re: Number, im: Number) -> Complex {
+        Complex { re, im }
+    }
+
+    pub fn real(re: Number) -> Complex {
+        Complex { re, im: Number::zero() }
+    }
+
+    pub fn i() -> Complex {
+        Complex { re: Number::zero(), im: Number::one() }
+    }
+
+    pub fn re(&self) -> &Number {
+        &self.re
+    }
+
+    pub fn im(&self) -> &Number {
+        &self.im
+    }
+
+    pub fn is_real(&self) -> bool {
+        self.im.is_zero()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.re.is_zero() && self.im.is_zero()
+    }
+
+    pub(super) fn from_f64s(re: f64, im: f64) -> Complex {
+        Complex { re: Number::Float(re), im: Number::Float(im) }
+    }
+
+    pub(super) fn to_f64s(&self) -> (f64, f64) {
+        (self.re.to_f64(), self.im.to_f64())
+    }
+
+    pub fn nan_to_err(self) -> Result<Complex, EvalError> {
+        if self.re.to_f64().is_nan() || self.im.to_f64().is_nan() {
+            return Err(EvalError::new(EvalErrorKind::MathError, "NaN"));
+        } else {
+            return Ok(self);
+        }
+    }
+
+    fn powi(self, exp: i32) -> Result<Complex, EvalError> {
+        let mut base = self;
+        let mut n = exp.unsigned_abs();
+        let mut result = Complex::real(Number::one());
+        while n > 0 {
+            if n % 2 == 1 {
+                result = (result * base.clone())?;
+            }
+            n /= 2;
+            if n > 0 {
+                base = (base.clone() * base)?;
+            }
+        }
+        if exp < 0 {
+            return Complex::real(Number::one()) / result;
+        } else {
+            return Ok(result);
+        }
+    }
+}
+
+fn imaginary_string(im: &Number) -> String {
+    if im == &Number::one() {
+        "i".to_owned()
+    } else if let Number::Symbolic(_) = im {
+        let s = im.to_string();
+        if s.contains(" + ") || s.contains(" - ") {
+            format!("({}) i", s)
+        } else {
+            format!("{} i", s)
+        }
+    } else if im.is_rational() && !im.is_integer() {
+        format!("{} i", im.to_string())
+    } else {
+        format!("{}i", im.to_string())
+    }
+}
+
+impl ToString for Complex {
+    fn to_string(&self) -> String {
+        if self.im.is_zero() {
+            self.re.to_string()
+        } else if self.re.is_zero() {
+            if self.im.is_negative() {
+                format!("-{}", imaginary_string(&self.im.abs()))
+            } else {
+                imaginary_string(&self.im)
+            }
+        } else if self.im.is_negative() {
+            format!("{} - {}", self.re.to_string(), imaginary_string(&self.im.abs()))
+        } else {
+            format!("{} + {}", self.re.to_string(), imaginary_string(&self.im))
+        }
+    }
+}
+
+impl FromStr for Complex {
+    type Err = EvalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Complex::real(Number::from_str(s)?))
+    }
+}
+
+impl PartialOrd for Complex {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.is_real() && other.is_real() {
+            self.re.partial_cmp(&other.re)
+        } else if self == other {
+            Some(Ordering::Equal)
+        } else {
+            None
+        }
+    }
+}
+
+impl Neg for Complex {
+    type Output = Result<Complex, EvalError>;
+
+    fn neg(self) -> Self::Output {
+        Ok(Complex::new((-self.re)?, (-self.im)?))
+    }
+}
+
+impl Add for Complex {
+    type Output = Result<Complex, EvalError>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Ok(Complex::new((self.re + rhs.re)?, (self.im + rhs.im)?))
+    }
+}
+
+impl Sub for Complex {
+    type Output = Result<Complex, EvalError>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Ok(Complex::new((self.re - rhs.re)?, (self.im - rhs.im)?))
+    }
+}
+
+impl Mul for Complex {
+    type Output = Result<Complex, EvalError>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let re = ((self.re.clone() * rhs.re.clone())? - (self.im.clone() * rhs.im.clone())?)?;
+        let im = ((self.re * rhs.im)? + (self.im * rhs.re)?)?;
+        return Ok(Complex::new(re, im));
+    }
+}
+
+impl Div for Complex {
+    type Output = Result<Complex, EvalError>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.is_real() {
+            return Ok(Complex::new((self.re / rhs.re.clone())?, (self.im / rhs.re)?));
+        }
+        let den = ((rhs.re.clone() * rhs.re.clone())? + (rhs.im.clone() * rhs.im.clone())?)?;
+        let re = ((self.re.clone() * rhs.re.clone())? + (self.im.clone() * rhs.im.clone())?)?;
+        let im = ((self.im * rhs.re)? - (self.re * rhs.im)?)?;
+        return Ok(Complex::new((re / den.clone())?, (im / den)?));
+    }
+}
+
+impl Pow<Complex> for Complex {
+    type Output = Result<Complex, EvalError>;
+
+    fn pow(self, rhs: Complex) -> Self::Output {
+        if self.is_zero() && rhs.is_zero() {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Zero to the power of zero"));
+        } else if rhs.is_real() {
+            if self.is_real() {
+                let res = Pow::pow(self.re.clone(), rhs.re.clone())?;
+                if !res.to_f64().is_nan() {
+                    return Ok(Complex::real(res));
+                }
+            } else if rhs.re.is_integer() {
+                if let Some(exp) = rhs.re.to_rational().and_then(|r| num::ToPrimitive::to_i32(&r)) {
+                    return self.powi(exp);
+                }
+            }
+        }
+        if self.is_zero() {
+            if rhs.re.is_positive() {
+                return Ok(Complex::real(Number::zero()));
+            } else {
+                return Err(EvalError::new(EvalErrorKind::MathError, "Division by zero"));
+            }
+        }
+        return Ok(self.exp_ln(&rhs));
+    }
+}
+
+impl Value for Complex {
+    type DefaultContext = ComplexContext;
+
+    fn default_context() -> Self::DefaultContext {
+        ComplexContext::new()
+    }
+
+    fn parse_literal(literal: &str) -> Result<Self, EvalError> {
+        Complex::from_str(literal)
+    }
+
+    fn add(self, other: Self) -> Result<Self, EvalError> {
+        self + other
+    }
+
+    fn sub(self, other: Self) -> Result<Self, EvalError> {
+        self - other
+    }
+
+    fn mul(self, other: Self) -> Result<Self, EvalError> {
+        self * other
+    }
+
+    fn div(self, other: Self) -> Result<Self, EvalError> {
+        self / other
+    }
+
+    fn pow(self, other: Self) -> Result<Self, EvalError> {
+        Pow::pow(self, other)
+    }
+
+    fn neg(self) -> Result<Self, EvalError> {
+        -self
+    }
+
+    fn from_bool(value: bool) -> Self {
+        Complex::real(Number::from_bool(value))
+    }
+
+    fn to_bool(&self) -> Result<bool, EvalError> {
+        return Ok(!self.is_zero());
+    }
+
+    fn compare(&self, other: &Self) -> Result<Option<Ordering>, EvalError> {
+        if self.is_real() && other.is_real() {
+            return Ok(self.partial_cmp(other));
+        } else {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Complex numbers cannot be ordered"));
+        }
+    }
+
+    fn equal(self, other: Self) -> Result<Self, EvalError> {
+        return Ok(Self::from_bool(self == other));
+    }
+
+    fn not_equal(self, other: Self) -> Result<Self, EvalError> {
+        return Ok(Self::from_bool(self != other));
+    }
+}
@@ -0,0 +1,137 @@
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::Context;
+use crate::ContextFn;
+use crate::FunctionDefinition;
+use crate::DEFAULT_RECURSION_LIMIT;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use crate::Number;
+
+use super::Complex;
+
+pub struct ComplexContext {
+    vars: HashMap<String, Complex>,
+    funcs: HashMap<String, Rc<ContextFn<Complex>>>,
+    defs: HashMap<String, Rc<FunctionDefinition>>,
+    recursion_limit: usize,
+}
+
+fn check_length(args: &[Complex], min: usize, max: usize) -> Result<&[Complex], EvalError> {
+    if args.len() < min {
+        return Err(EvalError::new(EvalErrorKind::ArgumentMismatch, "Too few arguments to function"));
+    } else if args.len() > max {
+        return Err(EvalError::new(EvalErrorKind::ArgumentMismatch, "Too many arguments to function"));
+    } else {
+        return Ok(args);
+    }
+}
+
+fn check_real(args: &[Complex]) -> Result<Vec<Number>, EvalError> {
+    if args.iter().all(Complex::is_real) {
+        return Ok(args.iter().map(|c| c.re().clone()).collect());
+    } else {
+        return Err(EvalError::new(EvalErrorKind::MathError, "Function is only defined for real arguments"));
+    }
+}
+
+fn extremum(args: &[Complex], name: &str, order: Ordering) -> Result<Complex, EvalError> {
+    let args = check_real(check_length(args, 1, usize::MAX)?)?;
+    let mut m = 0;
+    for i in 1..args.len() {
+        if let Some(o) = args[i].partial_cmp(&args[m]) {
+            if o == order {
+                m = i;
+            }
+        } else {
+            return Err(EvalError::new(
+                EvalErrorKind::NotSupported, &format!("Values in {} function are not comparable", name)
+            ));
+        }
+    }
+    return Ok(Complex::real(args[m].clone()));
+}
+
+impl ComplexContext {
+    pub fn new() -> ComplexContext {
+        let mut res = ComplexContext {
+            vars: HashMap::new(), funcs: HashMap::new(), defs: HashMap::new(), recursion_limit: DEFAULT_RECURSION_LIMIT
+        };
+        res.set_variable("pi", Complex::real(Number::pi()));
+        res.set_variable("e", Complex::real(Number::e()));
+        res.set_variable("i", Complex::i());
+        res.set_function("floor", Box::new(|v| check_length(v, 1, 1)?[0].floor().nan_to_err()));
+        res.set_function("ceil", Box::new(|v| check_length(v, 1, 1)?[0].ceil().nan_to_err()));
+        res.set_function("round", Box::new(|v| check_length(v, 1, 1)?[0].round().nan_to_err()));
+        res.set_function("trunc", Box::new(|v| check_length(v, 1, 1)?[0].trunc().nan_to_err()));
+        res.set_function("fract", Box::new(|v| check_length(v, 1, 1)?[0].fract().nan_to_err()));
+        res.set_function("abs", Box::new(|v| check_length(v, 1, 1)?[0].abs().nan_to_err()));
+        res.set_function("sign", Box::new(|v| check_length(v, 1, 1)?[0].sign().nan_to_err()));
+        res.set_function("sqrt", Box::new(|v| check_length(v, 1, 1)?[0].sqrt().nan_to_err()));
+        res.set_function("ln", Box::new(|v| check_length(v, 1, 1)?[0].ln().nan_to_err()));
+        res.set_function("log", Box::new(|v| check_length(v, 1, 1)?[0].log().nan_to_err()));
+        res.set_function("cbrt", Box::new(|v| check_length(v, 1, 1)?[0].cbrt().nan_to_err()));
+        res.set_function("sin", Box::new(|v| check_length(v, 1, 1)?[0].sin().nan_to_err()));
+        res.set_function("cos", Box::new(|v| check_length(v, 1, 1)?[0].cos().nan_to_err()));
+        res.set_function("tan", Box::new(|v| check_length(v, 1, 1)?[0].tan().nan_to_err()));
+        res.set_function("asin", Box::new(|v| check_length(v, 1, 1)?[0].asin().nan_to_err()));
+        res.set_function("acos", Box::new(|v| check_length(v, 1, 1)?[0].acos().nan_to_err()));
+        res.set_function("atan", Box::new(|v| check_length(v, 1, 1)?[0].atan().nan_to_err()));
+        res.set_function("atan2", Box::new(|v| {
+            let v = check_real(check_length(v, 2, 2)?)?;
+            Complex::real(v[0].atan2(&v[1])).nan_to_err()
+        }));
+        res.set_function("sinh", Box::new(|v| check_length(v, 1, 1)?[0].sinh().nan_to_err()));
+        res.set_function("cosh", Box::new(|v| check_length(v, 1, 1)?[0].cosh().nan_to_err()));
+        res.set_function("tanh", Box::new(|v| check_length(v, 1, 1)?[0].tanh().nan_to_err()));
+        res.set_function("asinh", Box::new(|v| check_length(v, 1, 1)?[0].asinh().nan_to_err()));
+        res.set_function("acosh", Box::new(|v| check_length(v, 1, 1)?[0].acosh().nan_to_err()));
+        res.set_function("atanh", Box::new(|v| check_length(v, 1, 1)?[0].atanh().nan_to_err()));
+        res.set_function("min", Box::new(|v| extremum(v, "min", Ordering::Less)));
+        res.set_function("max", Box::new(|v| extremum(v, "max", Ordering::Greater)));
+        res.set_function("re", Box::new(|v| Ok(Complex::real(check_length(v, 1, 1)?[0].re().clone()))));
+        res.set_function("im", Box::new(|v| Ok(Complex::real(check_length(v, 1, 1)?[0].im().clone()))));
+        res.set_function("arg", Box::new(|v| Complex::real(check_length(v, 1, 1)?[0].arg()).nan_to_err()));
+        res.set_function("conj", Box::new(|v| check_length(v, 1, 1)?[0].conj()));
+        return res;
+    }
+
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.recursion_limit = limit;
+    }
+}
+
+impl Context<Complex> for ComplexContext {
+    fn set_variable(&mut self, name: &str, value: Complex) {
+        self.vars.insert(name.to_owned(), value);
+    }
+
+    fn set_function(&mut self, name: &str, value: Box<ContextFn<Complex>>) {
+        self.defs.remove(name);
+        self.funcs.insert(name.to_owned(), Rc::from(value));
+    }
+
+    fn get_variable(&self, name: &str) -> Option<Complex> {
+        return self.vars.get(name).cloned();
+    }
+
+    fn get_function(&self, name: &str) -> Option<Rc<ContextFn<Complex>>> {
+        return self.funcs.get(name).cloned();
+    }
+
+    fn set_definition(&mut self, name: &str, definition: Rc<FunctionDefinition>) {
+        self.funcs.remove(name);
+        self.defs.insert(name.to_owned(), definition);
+    }
+
+    fn get_definition(&self, name: &str) -> Option<Rc<FunctionDefinition>> {
+        return self.defs.get(name).cloned();
+    }
+
+    fn recursion_limit(&self) -> usize {
+        self.recursion_limit
+    }
+}
@@ -0,0 +1,242 @@
+
+use std::f64::consts;
+
+use crate::{Number, EvalError};
+
+use super::Complex;
+
+type C64 = (f64, f64);
+
+fn c_add(a: C64, b: C64) -> C64 {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+fn c_sub(a: C64, b: C64) -> C64 {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn c_mul(a: C64, b: C64) -> C64 {
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+fn c_div(a: C64, b: C64) -> C64 {
+    let den = b.0 * b.0 + b.1 * b.1;
+    ((a.0 * b.0 + a.1 * b.1) / den, (a.1 * b.0 - a.0 * b.1) / den)
+}
+
+fn c_exp(a: C64) -> C64 {
+    let r = a.0.exp();
+    (r * a.1.cos(), r * a.1.sin())
+}
+
+fn c_ln(a: C64) -> C64 {
+    (a.0.hypot(a.1).ln(), a.1.atan2(a.0))
+}
+
+fn c_sqrt(a: C64) -> C64 {
+    let r = a.0.hypot(a.1);
+    let im = ((r - a.0) / 2.0).sqrt();
+    ((((r + a.0) / 2.0).sqrt()), if a.1 < 0.0 { -im } else { im })
+}
+
+fn c_sin(a: C64) -> C64 {
+    (a.0.sin() * a.1.cosh(), a.0.cos() * a.1.sinh())
+}
+
+fn c_cos(a: C64) -> C64 {
+    (a.0.cos() * a.1.cosh(), -a.0.sin() * a.1.sinh())
+}
+
+fn c_sinh(a: C64) -> C64 {
+    (a.0.sinh() * a.1.cos(), a.0.cosh() * a.1.sin())
+}
+
+fn c_cosh(a: C64) -> C64 {
+    (a.0.cosh() * a.1.cos(), a.0.sinh() * a.1.sin())
+}
+
+const ONE: C64 = (1.0, 0.0);
+const I: C64 = (0.0, 1.0);
+
+fn c_asin(z: C64) -> C64 {
+    let w = c_ln(c_add(c_mul(I, z), c_sqrt(c_sub(ONE, c_mul(z, z)))));
+    (w.1, -w.0)
+}
+
+fn c_atan(z: C64) -> C64 {
+    let w = c_sub(c_ln(c_sub(ONE, c_mul(I, z))), c_ln(c_add(ONE, c_mul(I, z))));
+    c_mul((0.0, 0.5), w)
+}
+
+fn c_asinh(z: C64) -> C64 {
+    c_ln(c_add(z, c_sqrt(c_add(c_mul(z, z), ONE))))
+}
+
+fn c_acosh(z: C64) -> C64 {
+    c_ln(c_add(z, c_mul(c_sqrt(c_add(z, ONE)), c_sqrt(c_sub(z, ONE)))))
+}
+
+fn c_atanh(z: C64) -> C64 {
+    let w = c_sub(c_ln(c_add(ONE, z)), c_ln(c_sub(ONE, z)));
+    (w.0 / 2.0, w.1 / 2.0)
+}
+
+impl Complex {
+    fn real_or(&self, real: fn(&Number) -> Number, complex: fn(C64) -> C64) -> Complex {
+        if self.is_real() {
+            let res = real(self.re());
+            if !res.to_f64().is_nan() {
+                return Complex::real(res);
+            }
+        }
+        let (re, im) = complex(self.to_f64s());
+        return Complex::from_f64s(re, im);
+    }
+
+    fn map_parts(&self, f: fn(&Number) -> Number) -> Complex {
+        Complex::new(f(self.re()), f(self.im()))
+    }
+
+    pub(super) fn exp_ln(&self, exp: &Complex) -> Complex {
+        let (re, im) = c_exp(c_mul(exp.to_f64s(), c_ln(self.to_f64s())));
+        return Complex::from_f64s(re, im);
+    }
+
+    pub fn conj(&self) -> Result<Complex, EvalError> {
+        Ok(Complex::new(self.re().clone(), (-self.im().clone())?))
+    }
+
+    pub fn arg(&self) -> Number {
+        if self.is_real() && !self.re().is_negative() {
+            Number::zero()
+        } else {
+            Number::Float(self.im().to_f64().atan2(self.re().to_f64()))
+        }
+    }
+
+    pub fn floor(&self) -> Complex {
+        self.map_parts(Number::floor)
+    }
+
+    pub fn ceil(&self) -> Complex {
+        self.map_parts(Number::ceil)
+    }
+
+    pub fn round(&self) -> Complex {
+        self.map_parts(Number::round)
+    }
+
+    pub fn trunc(&self) -> Complex {
+        self.map_parts(Number::trunc)
+    }
+
+    pub fn fract(&self) -> Complex {
+        self.map_parts(Number::fract)
+    }
+
+    pub fn abs(&self) -> Complex {
+        if self.is_real() {
+            Complex::real(self.re().abs())
+        } else if self.re().is_zero() {
+            Complex::real(self.im().abs())
+        } else {
+            let (re, im) = (self.re().clone(), self.im().clone());
+            match (re.clone() * re, im.clone() * im) {
+                (Ok(a), Ok(b)) => match a + b {
+                    Ok(sum) => Complex::real(sum.sqrt()),
+                    Err(_) => Complex::real(Number::Float(f64::NAN)),
+                },
+                _ => Complex::real(Number::Float(f64::NAN)),
+            }
+        }
+    }
+
+    pub fn sign(&self) -> Complex {
+        if self.is_real() {
+            Complex::real(self.re().sign())
+        } else {
+            (self.clone() / self.abs()).unwrap_or_else(|_| Complex::from_f64s(f64::NAN, f64::NAN))
+        }
+    }
+
+    pub fn sqrt(&self) -> Complex {
+        if self.is_real() && self.re().is_negative() {
+            Complex::new(Number::zero(), self.re().abs().sqrt())
+        } else {
+            self.real_or(Number::sqrt, c_sqrt)
+        }
+    }
+
+    pub fn cbrt(&self) -> Complex {
+        if self.is_real() {
+            Complex::real(self.re().cbrt())
+        } else {
+            self.exp_ln(&Complex::real(Number::from_i64s(1, 3)))
+        }
+    }
+
+    pub fn ln(&self) -> Complex {
+        if self.is_real() && self.re().is_negative() {
+            Complex::new(self.re().abs().ln(), Number::pi())
+        } else {
+            self.real_or(Number::ln, c_ln)
+        }
+    }
+
+    pub fn log(&self) -> Complex {
+        if self.is_real() && !self.re().is_negative() {
+            Complex::real(self.re().log())
+        } else {
+            let (re, im) = c_div(self.ln().to_f64s(), (consts::LN_10, 0.0));
+            Complex::from_f64s(re, im)
+        }
+    }
+
+    pub fn sin(&self) -> Complex {
+        self.real_or(Number::sin, c_sin)
+    }
+
+    pub fn cos(&self) -> Complex {
+        self.real_or(Number::cos, c_cos)
+    }
+
+    pub fn tan(&self) -> Complex {
+        self.real_or(Number::tan, |z| c_div(c_sin(z), c_cos(z)))
+    }
+
+    pub fn asin(&self) -> Complex {
+        self.real_or(Number::asin, c_asin)
+    }
+
+    pub fn acos(&self) -> Complex {
+        self.real_or(Number::acos, |z| c_sub((consts::FRAC_PI_2, 0.0), c_asin(z)))
+    }
+
+    pub fn atan(&self) -> Complex {
+        self.real_or(Number::atan, c_atan)
+    }
+
+    pub fn sinh(&self) -> Complex {
+        self.real_or(Number::sinh, c_sinh)
+    }
+
+    pub fn cosh(&self) -> Complex {
+        self.real_or(Number::cosh, c_cosh)
+    }
+
+    pub fn tanh(&self) -> Complex {
+        self.real_or(Number::tanh, |z| c_div(c_sinh(z), c_cosh(z)))
+    }
+
+    pub fn asinh(&self) -> Complex {
+        self.real_or(Number::asinh, c_asinh)
+    }
+
+    pub fn acosh(&self) -> Complex {
+        self.real_or(Number::acosh, c_acosh)
+    }
+
+    pub fn atanh(&self) -> Complex {
+        self.real_or(Number::atanh, c_atanh)
+    }
+}
@@ -0,0 +1,7 @@
+
+mod complex;
+mod context;
+mod functions;
+
+pub use complex::Complex;
+pub use context::ComplexContext;
@@ -0,0 +1,220 @@
+
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use super::Expr;
+use super::Value;
+use super::Context;
+use super::EvalError;
+use super::EvalErrorKind;
+use super::forms;
+use super::function;
+
+type Node<V> = Box<dyn Fn(&[V]) -> Result<V, EvalError>>;
+
+pub struct CompiledExpr<V: Value> {
+    expr: Expr,
+    params: Vec<String>,
+    root: Node<V>,
+}
+
+impl<V: Value> CompiledExpr<V> {
+    pub fn params(&self) -> &[String] {
+        &self.params
+    }
+
+    pub fn eval(&self, args: &[V]) -> Result<V, EvalError> {
+        if args.len() != self.params.len() {
+            return Err(EvalError::new(
+                EvalErrorKind::ArgumentMismatch,
+                &format!("Expected {} arguments but got {}", self.params.len(), args.len())
+            ));
+        } else {
+            return (self.root)(args).map_err(|e| e.located(&self.expr));
+        }
+    }
+}
+
+// Errors keep the location of the innermost node that failed, as in `evaluate`
+fn unary<V, F>(expr: &Expr, o: Node<V>, op: F) -> Node<V>
+where V: Value + 'static, F: Fn(V) -> Result<V, EvalError> + 'static
+{
+    let expr = expr.clone();
+    Box::new(move |args| op(o(args)?).map_err(|e| e.located(&expr)))
+}
+
+fn binary<V, F>(expr: &Expr, l: Node<V>, r: Node<V>, op: F) -> Node<V>
+where V: Value + 'static, F: Fn(V, V) -> Result<V, EvalError> + 'static
+{
+    let expr = expr.clone();
+    Box::new(move |args| op(l(args)?, r(args)?).map_err(|e| e.located(&expr)))
+}
+
+fn compile_function<V: Value + 'static>(
+    expr: &Expr, name: &str, args: &[Expr], cnxt: &dyn Context<V>, params: &[String]
+) -> Result<Node<V>, EvalError> {
+    // Like everything else, definitions are bound when compiling
+    let function = match cnxt.get_definition(name) {
+        Some(def) => Some(Rc::from(function::to_function(name, &def.params, &def.body, cnxt))),
+        None => cnxt.get_function(name),
+    };
+    if let Some(f) = function {
+        let nodes = args.iter()
+            .map(|a| compile_node(a, cnxt, params))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (expr, name) = (expr.clone(), name.to_owned());
+        // Arguments are collected into a buffer owned by the node so that evaluation does not allocate
+        let scratch = RefCell::new(Vec::with_capacity(nodes.len()));
+        return Ok(Box::new(move |args| {
+            with_scratch(&scratch, |argn| {
+                for n in &nodes {
+                    argn.push(n(args)?);
+                }
+                f(argn).map_err(|e| e.located(&expr).called_from(&name))
+            })
+        }));
+    } else if forms::is_form(name) {
+        let (var, from, to, body) = forms::split_form(name, args)?;
+        let from = compile_node(from, cnxt, params)?;
+        let to = compile_node(to, cnxt, params)?;
+        let mut inner = params.to_vec();
+        inner.push(var.to_owned());
+        let body = compile_node(body, cnxt, &inner)?;
+        let (expr, name) = (expr.clone(), name.to_owned());
+        let scratch = RefCell::new(Vec::with_capacity(inner.len()));
+        return Ok(Box::new(move |args| {
+            let at = |value: V| {
+                with_scratch(&scratch, |argn| {
+                    argn.extend_from_slice(args);
+                    argn.push(value);
+                    body(argn)
+                })
+            };
+            forms::apply_form(&name, from(args)?, to(args)?, &at)
+                .map_err(|e| e.located(&expr).called_from(&name))
+        }));
+    } else {
+        return Err(EvalError::unknown(EvalErrorKind::UnknownFunction, name));
+    }
+}
+
+// A node that is re-entered, e.g. through a context function calling back into the expression, finds its buffer in
+// use and falls back to a fresh one
+fn with_scratch<V, R>(scratch: &RefCell<Vec<V>>, f: impl FnOnce(&mut Vec<V>) -> R) -> R {
+    match scratch.try_borrow_mut() {
+        Ok(mut argn) => {
+            argn.clear();
+            f(&mut argn)
+        },
+        Err(_) => f(&mut Vec::new()),
+    }
+}
+
+fn compile_node<V: Value + 'static>(
+    expr: &Expr, cnxt: &dyn Context<V>, params: &[String]
+) -> Result<Node<V>, EvalError> {
+    compile_expr(expr, cnxt, params).map_err(|e| e.located(expr))
+}
+
+fn compile_expr<V: Value + 'static>(
+    expr: &Expr, cnxt: &dyn Context<V>, params: &[String]
+) -> Result<Node<V>, EvalError> {
+    let node: Node<V> = match expr {
+        Expr::Literal(s) => {
+            let value = V::parse_literal(s)?;
+            Box::new(move |_| Ok(value.clone()))
+        },
+        Expr::Variable(name) => {
+            if let Some(i) = params.iter().rposition(|p| p == name) {
+                Box::new(move |args| Ok(args[i].clone()))
+            } else if let Some(value) = cnxt.get_variable(name) {
+                Box::new(move |_| Ok(value.clone()))
+            } else {
+                return Err(EvalError::unknown(EvalErrorKind::UnknownVariable, name));
+            }
+        },
+        Expr::Neg(o) => unary(expr, compile_node(o, cnxt, params)?, V::neg),
+        Expr::Not(o) => unary(expr, compile_node(o, cnxt, params)?, V::not),
+        Expr::BitNot(o) => unary(expr, compile_node(o, cnxt, params)?, V::bit_not),
+        Expr::Add(l, r) => binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::add),
+        Expr::Sub(l, r) => binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::sub),
+        Expr::Mul(l, r) => binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::mul),
+        Expr::Div(l, r) => binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::div),
+        Expr::Pow(l, r) => {
+            let (l, r) = (compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?);
+            match cnxt.power() {
+                Some(f) => binary(expr, l, r, move |base, exponent| f(&[base, exponent])),
+                None => binary(expr, l, r, V::pow),
+            }
+        },
+        Expr::Mod(l, r) => binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::modulo),
+        Expr::FloorDiv(l, r) => binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::floor_div),
+        Expr::BitAnd(l, r) => binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::bit_and),
+        Expr::BitOr(l, r) => binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::bit_or),
+        Expr::BitXor(l, r) => binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::bit_xor),
+        Expr::ShiftLeft(l, r) => binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::shift_left),
+        Expr::ShiftRight(l, r) => {
+            binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::shift_right)
+        },
+        Expr::PlusMinus(l, r) => {
+            binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::plus_minus)
+        },
+        Expr::Less(l, r) => binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::less),
+        Expr::LessEqual(l, r) => {
+            binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::less_equal)
+        },
+        Expr::Equal(l, r) => binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::equal),
+        Expr::NotEqual(l, r) => {
+            binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::not_equal)
+        },
+        Expr::GreaterEqual(l, r) => {
+            binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::greater_equal)
+        },
+        Expr::Greater(l, r) => {
+            binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::greater)
+        },
+        Expr::Convert(l, r) => {
+            binary(expr, compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?, V::convert)
+        },
+        Expr::And(l, r) => {
+            let (l, r) = (compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?);
+            let expr = expr.clone();
+            Box::new(move |args| {
+                let and = || Ok(V::from_bool(l(args)?.to_bool()? && r(args)?.to_bool()?));
+                and().map_err(|e: EvalError| e.located(&expr))
+            })
+        },
+        Expr::Or(l, r) => {
+            let (l, r) = (compile_node(l, cnxt, params)?, compile_node(r, cnxt, params)?);
+            let expr = expr.clone();
+            Box::new(move |args| {
+                let or = || Ok(V::from_bool(l(args)?.to_bool()? || r(args)?.to_bool()?));
+                or().map_err(|e: EvalError| e.located(&expr))
+            })
+        },
+        Expr::If(c, t, o) => {
+            let c = compile_node(c, cnxt, params)?;
+            let t = compile_node(t, cnxt, params)?;
+            let o = compile_node(o, cnxt, params)?;
+            let expr = expr.clone();
+            Box::new(move |args| {
+                let cond = c(args)?.to_bool().map_err(|e| e.located(&expr))?;
+                if cond { t(args) } else { o(args) }
+            })
+        },
+        Expr::Function(name, args) => compile_function(expr, name, args, cnxt, params)?,
+        Expr::Assign(..) | Expr::Define(..) => {
+            return Err(EvalError::new(EvalErrorKind::NotSupported, "Definitions cannot be compiled"));
+        },
+    };
+    return Ok(node);
+}
+
+pub fn compile<V: Value + 'static>(
+    expr: &Expr, cnxt: &dyn Context<V>, params: &[&str]
+) -> Result<CompiledExpr<V>, EvalError> {
+    let params: Vec<String> = params.iter().map(|p| p.to_string()).collect();
+    let root = compile_node(expr, cnxt, &params)?;
+    return Ok(CompiledExpr { expr: expr.clone(), params, root });
+}
@@ -0,0 +1,185 @@
+
+use super::Expr;
+use super::EvalError;
+use super::EvalErrorKind;
+
+fn literal(value: &str) -> Expr {
+    Expr::Literal(value.to_owned())
+}
+
+fn function(name: &str, args: Vec<Expr>) -> Expr {
+    Expr::Function(name.to_owned(), args)
+}
+
+fn add(l: Expr, r: Expr) -> Expr {
+    Expr::Add(Box::new(l), Box::new(r))
+}
+
+fn sub(l: Expr, r: Expr) -> Expr {
+    Expr::Sub(Box::new(l), Box::new(r))
+}
+
+fn mul(l: Expr, r: Expr) -> Expr {
+    Expr::Mul(Box::new(l), Box::new(r))
+}
+
+fn div(l: Expr, r: Expr) -> Expr {
+    Expr::Div(Box::new(l), Box::new(r))
+}
+
+fn pow(l: Expr, r: Expr) -> Expr {
+    Expr::Pow(Box::new(l), Box::new(r))
+}
+
+fn depends_on(expr: &Expr, var: &str) -> bool {
+    match expr {
+        Expr::Literal(_) => false,
+        Expr::Variable(name) => name == var,
+        Expr::Function(name, args) => match (name.as_str(), args.as_slice()) {
+            ("sum" | "prod" | "integrate" | "solve", [Expr::Variable(bound), from, to, body]) => {
+                depends_on(from, var) || depends_on(to, var) || (bound != var && depends_on(body, var))
+            },
+            _ => args.iter().any(|a| depends_on(a, var)),
+        },
+        Expr::Neg(o) | Expr::Not(o) | Expr::BitNot(o) | Expr::Assign(_, o) => depends_on(o, var),
+        Expr::Add(l, r) | Expr::Sub(l, r) | Expr::Mul(l, r) | Expr::Div(l, r) | Expr::Pow(l, r)
+        | Expr::PlusMinus(l, r) | Expr::Less(l, r) | Expr::LessEqual(l, r) | Expr::Equal(l, r)
+        | Expr::NotEqual(l, r) | Expr::GreaterEqual(l, r) | Expr::Greater(l, r) | Expr::And(l, r) | Expr::Or(l, r)
+        | Expr::Mod(l, r) | Expr::FloorDiv(l, r) | Expr::BitAnd(l, r) | Expr::BitOr(l, r) | Expr::BitXor(l, r)
+        | Expr::ShiftLeft(l, r) | Expr::ShiftRight(l, r) | Expr::Convert(l, r) => depends_on(l, var) || depends_on(r, var),
+        Expr::If(c, t, o) => depends_on(c, var) || depends_on(t, var) || depends_on(o, var),
+        Expr::Define(_, params, body) => !params.iter().any(|p| p == var) && depends_on(body, var),
+    }
+}
+
+fn extremum_derivative(name: &str, args: &[Expr], var: &str) -> Result<Expr, EvalError> {
+    if args.len() == 1 {
+        return derivative(&args[0], var);
+    } else {
+        let rest = function(name, args[1..].to_vec());
+        let cond = if name == "min" {
+            Expr::LessEqual(Box::new(args[0].clone()), Box::new(rest.clone()))
+        } else {
+            Expr::GreaterEqual(Box::new(args[0].clone()), Box::new(rest.clone()))
+        };
+        return Ok(Expr::If(
+            Box::new(cond),
+            Box::new(derivative(&args[0], var)?),
+            Box::new(extremum_derivative(name, &args[1..], var)?),
+        ));
+    }
+}
+
+fn function_derivative(name: &str, args: &[Expr], var: &str) -> Result<Expr, EvalError> {
+    match (name, args) {
+        ("min" | "max", [_, ..]) => extremum_derivative(name, args, var),
+        ("sum" | "integrate", [Expr::Variable(bound), from, to, body])
+            if bound != var && !depends_on(from, var) && !depends_on(to, var) => {
+            Ok(function(name, vec![args[0].clone(), from.clone(), to.clone(), derivative(body, var)?]))
+        },
+        ("atan2", [y, x]) => Ok(div(
+            sub(mul(x.clone(), derivative(y, var)?), mul(y.clone(), derivative(x, var)?)),
+            add(pow(x.clone(), literal("2")), pow(y.clone(), literal("2")))
+        )),
+        (_, [u]) => {
+            let outer = match name {
+                "floor" | "ceil" | "round" | "trunc" | "sign" => literal("0"),
+                "fract" => literal("1"),
+                "abs" => function("sign", vec![u.clone()]),
+                "sqrt" => div(literal("1"), mul(literal("2"), function("sqrt", vec![u.clone()]))),
+                "cbrt" => div(literal("1"), mul(literal("3"), pow(function("cbrt", vec![u.clone()]), literal("2")))),
+                "ln" => div(literal("1"), u.clone()),
+                "log" => div(literal("1"), mul(u.clone(), function("ln", vec![literal("10")]))),
+                "sin" => function("cos", vec![u.clone()]),
+                "cos" => Expr::Neg(Box::new(function("sin", vec![u.clone()]))),
+                "tan" => div(literal("1"), pow(function("cos", vec![u.clone()]), literal("2"))),
+                "asin" => div(literal("1"), function("sqrt", vec![sub(literal("1"), pow(u.clone(), literal("2")))])),
+                "acos" => Expr::Neg(Box::new(
+                    div(literal("1"), function("sqrt", vec![sub(literal("1"), pow(u.clone(), literal("2")))]))
+                )),
+                "atan" => div(literal("1"), add(literal("1"), pow(u.clone(), literal("2")))),
+                "sinh" => function("cosh", vec![u.clone()]),
+                "cosh" => function("sinh", vec![u.clone()]),
+                "tanh" => div(literal("1"), pow(function("cosh", vec![u.clone()]), literal("2"))),
+                "asinh" => div(literal("1"), function("sqrt", vec![add(pow(u.clone(), literal("2")), literal("1"))])),
+                "acosh" => div(literal("1"), function("sqrt", vec![sub(pow(u.clone(), literal("2")), literal("1"))])),
+                "atanh" => div(literal("1"), sub(literal("1"), pow(u.clone(), literal("2")))),
+                _ => return Err(EvalError::unknown(EvalErrorKind::UnknownFunction, name)),
+            };
+            return Ok(mul(outer, derivative(u, var)?));
+        },
+        _ => Err(EvalError::unknown(EvalErrorKind::UnknownFunction, name)),
+    }
+}
+
+pub fn derivative(expr: &Expr, var: &str) -> Result<Expr, EvalError> {
+    if !depends_on(expr, var) {
+        return Ok(literal("0"));
+    }
+    match expr {
+        Expr::Literal(_) => Ok(literal("0")),
+        Expr::Variable(_) => Ok(literal("1")),
+        Expr::Neg(o) => Ok(Expr::Neg(Box::new(derivative(o, var)?))),
+        Expr::Add(l, r) => Ok(add(derivative(l, var)?, derivative(r, var)?)),
+        Expr::Sub(l, r) => Ok(sub(derivative(l, var)?, derivative(r, var)?)),
+        Expr::Mul(l, r) => {
+            if !depends_on(l, var) {
+                Ok(mul((**l).clone(), derivative(r, var)?))
+            } else if !depends_on(r, var) {
+                Ok(mul(derivative(l, var)?, (**r).clone()))
+            } else {
+                Ok(add(
+                    mul(derivative(l, var)?, (**r).clone()),
+                    mul((**l).clone(), derivative(r, var)?)
+                ))
+            }
+        },
+        Expr::Div(l, r) => Ok(div(
+            sub(mul(derivative(l, var)?, (**r).clone()), mul((**l).clone(), derivative(r, var)?)),
+            pow((**r).clone(), literal("2"))
+        )),
+        Expr::Pow(l, r) => {
+            if !depends_on(r, var) {
+                match r.as_ref() {
+                    Expr::Literal(k) if k == "0" => Ok(literal("0")),
+                    Expr::Literal(k) if k == "1" => derivative(l, var),
+                    _ => Ok(mul(
+                        mul((**r).clone(), pow((**l).clone(), sub((**r).clone(), literal("1")))),
+                        derivative(l, var)?
+                    )),
+                }
+            } else if **l == literal("0") {
+                // 0^x is zero wherever it is defined
+                Ok(mul(literal("0"), expr.clone()))
+            } else if !depends_on(l, var) {
+                Ok(mul(mul(expr.clone(), function("ln", vec![(**l).clone()])), derivative(r, var)?))
+            } else {
+                Ok(mul(expr.clone(), add(
+                    mul(derivative(r, var)?, function("ln", vec![(**l).clone()])),
+                    div(mul((**r).clone(), derivative(l, var)?), (**l).clone())
+                )))
+            }
+        },
+        Expr::Mod(l, r) => Ok(sub(
+            derivative(l, var)?,
+            mul(Expr::FloorDiv(l.clone(), r.clone()), derivative(r, var)?)
+        )),
+        Expr::Less(..) | Expr::LessEqual(..) | Expr::Equal(..) | Expr::NotEqual(..)
+        | Expr::GreaterEqual(..) | Expr::Greater(..) | Expr::And(..) | Expr::Or(..) | Expr::Not(..)
+        | Expr::FloorDiv(..) => {
+            Ok(literal("0"))
+        },
+        Expr::If(c, t, o) => Ok(Expr::If(c.clone(), Box::new(derivative(t, var)?), Box::new(derivative(o, var)?))),
+        Expr::Function(name, args) => function_derivative(name, args, var),
+        Expr::PlusMinus(..) => {
+            Err(EvalError::new(EvalErrorKind::NotSupported, "Cannot differentiate an uncertain value"))
+        },
+        Expr::BitAnd(..) | Expr::BitOr(..) | Expr::BitXor(..) | Expr::ShiftLeft(..) | Expr::ShiftRight(..)
+        | Expr::BitNot(..) => {
+            Err(EvalError::new(EvalErrorKind::NotSupported, "Cannot differentiate a bitwise operation"))
+        },
+        Expr::Assign(..) | Expr::Define(..) | Expr::Convert(..) => {
+            Err(EvalError::new(EvalErrorKind::NotSupported, "Cannot differentiate a statement"))
+        },
+    }
+}
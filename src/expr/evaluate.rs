@@ -1,72 +1,230 @@
 
+use std::fmt;
+use std::error::Error;
+use std::rc::Rc;
+
 use super::Expr;
 use super::Value;
 use super::Context;
+use super::function;
+use super::function::FunctionDefinition;
+use super::forms;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EvalErrorKind {
+    MathError,
+    UnitError,
+    InvalidLiteral,
+    NotSupported,
+    ArgumentMismatch,
+    UnknownVariable,
+    UnknownFunction,
+    RecursionLimit,
+}
+
+#[derive(Clone, Debug)]
+struct ErrorDetails {
+    kind: EvalErrorKind,
+    message: String,
+    identifier: Option<String>,
+    expr: Option<Expr>,
+    calls: Vec<String>,
+}
+
+// The details are boxed so that results carrying an error stay small
+#[derive(Clone, Debug)]
+pub struct EvalError {
+    details: Box<ErrorDetails>,
+}
+
+impl EvalError {
+    pub fn new(kind: EvalErrorKind, msg: &str) -> EvalError {
+        EvalError {
+            details: Box::new(ErrorDetails {
+                kind, message: msg.to_owned(), identifier: None, expr: None, calls: Vec::new()
+            }),
+        }
+    }
+
+    pub(super) fn unknown(kind: EvalErrorKind, name: &str) -> EvalError {
+        let mut error = EvalError::new(kind, name);
+        error.details.identifier = Some(name.to_owned());
+        return error;
+    }
+
+    pub(super) fn located(mut self, expr: &Expr) -> EvalError {
+        if self.details.expr.is_none() {
+            self.details.expr = Some(expr.clone());
+        }
+        return self;
+    }
+
+    pub(super) fn called_from(mut self, name: &str) -> EvalError {
+        self.details.calls.push(name.to_owned());
+        return self;
+    }
+
+    pub fn kind(&self) -> EvalErrorKind {
+        self.details.kind
+    }
+
+    pub fn message(&self) -> &str {
+        &self.details.message
+    }
 
-#[derive(Debug)]
-pub enum EvalError {
-    MathError(String),
-    UnitError(String),
-    InvalidLiteral(String),
-    NotSupported(String),
-    ArgumentMismatch(String),
-    UnknownVariable(String),
-    UnknownFunction(String),
+    pub fn identifier(&self) -> Option<&str> {
+        self.details.identifier.as_deref()
+    }
+
+    pub fn expr(&self) -> Option<&Expr> {
+        self.details.expr.as_ref()
+    }
+
+    pub fn calls(&self) -> &[String] {
+        &self.details.calls
+    }
 }
 
-impl ToString for EvalError {
-    fn to_string(&self) -> String {
-        match self {
-            EvalError::MathError(s) => format!("Math error: {}", s),
-            EvalError::UnitError(s) => format!("Unit error: {}", s),
-            EvalError::InvalidLiteral(s) => format!("Invalid literal: {}", s),
-            EvalError::NotSupported(s) => format!("Not supported: {}", s),
-            EvalError::ArgumentMismatch(s) => format!("Argument mismatch: {}", s),
-            EvalError::UnknownVariable(s) => format!("Unknown variable: {}", s),
-            EvalError::UnknownFunction(s) => format!("Unknown function: {}", s),
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = self.message();
+        match self.kind() {
+            EvalErrorKind::MathError => write!(f, "Math error: {}", message)?,
+            EvalErrorKind::UnitError => write!(f, "Unit error: {}", message)?,
+            EvalErrorKind::InvalidLiteral => write!(f, "Invalid literal: {}", message)?,
+            EvalErrorKind::NotSupported => write!(f, "Not supported: {}", message)?,
+            EvalErrorKind::ArgumentMismatch => write!(f, "Argument mismatch: {}", message)?,
+            EvalErrorKind::UnknownVariable => write!(f, "Unknown variable: {}", message)?,
+            EvalErrorKind::UnknownFunction => write!(f, "Unknown function: {}", message)?,
+            EvalErrorKind::RecursionLimit => write!(f, "Recursion error: {}", message)?,
+        }
+        if let Some(expr) = self.expr() {
+            write!(f, " in '{}'", expr.to_string())?;
         }
+        return Ok(());
+    }
+}
+
+impl Error for EvalError {}
+
+// Operators are evaluated out of line to keep the stack frame of the recursive evaluate small
+fn unary<V: Value, C: Context<V>>(o: &Expr, cnxt: &C, op: fn(V) -> Result<V, EvalError>) -> Result<V, EvalError> {
+    op(evaluate::<V, C>(o, cnxt)?)
+}
+
+fn binary<V: Value, C: Context<V>>(
+    l: &Expr, r: &Expr, cnxt: &C, op: fn(V, V) -> Result<V, EvalError>
+) -> Result<V, EvalError> {
+    op(evaluate::<V, C>(l, cnxt)?, evaluate::<V, C>(r, cnxt)?)
+}
+
+fn call_definition<V: Value, C: Context<V>>(
+    function: &FunctionDefinition, args: &[Expr], cnxt: &C
+) -> Result<V, EvalError> {
+    let mut argn = Vec::with_capacity(args.len());
+    for a in args {
+        argn.push(evaluate::<V, C>(a, cnxt)?);
+    }
+    function::call(function, &argn, cnxt)
+}
+
+fn power<V: Value, C: Context<V>>(l: &Expr, r: &Expr, cnxt: &C) -> Result<V, EvalError> {
+    let (base, exponent) = (evaluate::<V, C>(l, cnxt)?, evaluate::<V, C>(r, cnxt)?);
+    match cnxt.power() {
+        Some(f) => f(&[base, exponent]),
+        None => base.pow(exponent),
     }
 }
 
 pub fn evaluate<V: Value, C: Context<V>>(expr: &Expr, cnxt: &C) -> Result<V, EvalError> {
     match expr {
-        Expr::Literal(s) => s.parse(),
-        Expr::Add(l, r) => {
-            evaluate::<V, C>(l, cnxt)?.add(evaluate::<V, C>(r, cnxt)?)
-        },
-        Expr::Sub(l, r) => {
-            evaluate::<V, C>(l, cnxt)?.sub(evaluate::<V, C>(r, cnxt)?)
-        },
-        Expr::Mul(l, r) => {
-            evaluate::<V, C>(l, cnxt)?.mul(evaluate::<V, C>(r, cnxt)?)
-        },
-        Expr::Div(l, r) => {
-            evaluate::<V, C>(l, cnxt)?.div(evaluate::<V, C>(r, cnxt)?)
+        Expr::Literal(s) => V::parse_literal(s),
+        Expr::Add(l, r) => binary(l, r, cnxt, V::add),
+        Expr::Sub(l, r) => binary(l, r, cnxt, V::sub),
+        Expr::Mul(l, r) => binary(l, r, cnxt, V::mul),
+        Expr::Div(l, r) => binary(l, r, cnxt, V::div),
+        Expr::Neg(o) => unary(o, cnxt, V::neg),
+        Expr::Pow(l, r) => power(l, r, cnxt),
+        Expr::Mod(l, r) => binary(l, r, cnxt, V::modulo),
+        Expr::FloorDiv(l, r) => binary(l, r, cnxt, V::floor_div),
+        Expr::BitAnd(l, r) => binary(l, r, cnxt, V::bit_and),
+        Expr::BitOr(l, r) => binary(l, r, cnxt, V::bit_or),
+        Expr::BitXor(l, r) => binary(l, r, cnxt, V::bit_xor),
+        Expr::ShiftLeft(l, r) => binary(l, r, cnxt, V::shift_left),
+        Expr::ShiftRight(l, r) => binary(l, r, cnxt, V::shift_right),
+        Expr::PlusMinus(l, r) => binary(l, r, cnxt, V::plus_minus),
+        Expr::Less(l, r) => binary(l, r, cnxt, V::less),
+        Expr::LessEqual(l, r) => binary(l, r, cnxt, V::less_equal),
+        Expr::Equal(l, r) => binary(l, r, cnxt, V::equal),
+        Expr::NotEqual(l, r) => binary(l, r, cnxt, V::not_equal),
+        Expr::GreaterEqual(l, r) => binary(l, r, cnxt, V::greater_equal),
+        Expr::Greater(l, r) => binary(l, r, cnxt, V::greater),
+        Expr::And(l, r) => {
+            if evaluate::<V, C>(l, cnxt)?.to_bool()? {
+                Ok(V::from_bool(evaluate::<V, C>(r, cnxt)?.to_bool()?))
+            } else {
+                Ok(V::from_bool(false))
+            }
         },
-        Expr::Neg(o) => {
-            evaluate::<V, C>(o, cnxt)?.neg()
+        Expr::Or(l, r) => {
+            if evaluate::<V, C>(l, cnxt)?.to_bool()? {
+                Ok(V::from_bool(true))
+            } else {
+                Ok(V::from_bool(evaluate::<V, C>(r, cnxt)?.to_bool()?))
+            }
         },
-        Expr::Pow(l, r) => {
-            evaluate::<V, C>(l, cnxt)?.pow(evaluate::<V, C>(r, cnxt)?)
+        Expr::If(c, t, o) => {
+            if evaluate::<V, C>(c, cnxt)?.to_bool()? {
+                evaluate::<V, C>(t, cnxt)
+            } else {
+                evaluate::<V, C>(o, cnxt)
+            }
         },
+        Expr::Not(o) => unary(o, cnxt, V::not),
+        Expr::BitNot(o) => unary(o, cnxt, V::bit_not),
         Expr::Function(name, args) => {
-            let mut argn = Vec::new();
-            for a in args {
-                argn.push(evaluate::<V, C>(a, cnxt)?);
-            }
-            if let Some(f) = cnxt.get_function(name) {
-                f(argn)
+            if let Some(def) = cnxt.get_definition(name) {
+                call_definition(&def, args, cnxt).map_err(|e| e.located(expr).called_from(name))
+            } else if let Some(f) = cnxt.get_function(name) {
+                let mut argn = Vec::new();
+                for a in args {
+                    argn.push(evaluate::<V, C>(a, cnxt)?);
+                }
+                f(&argn).map_err(|e| e.located(expr).called_from(name))
+            } else if let Some(res) = forms::evaluate_form(name, args, cnxt) {
+                res.map_err(|e| e.located(expr).called_from(name))
             } else {
-                Err(EvalError::UnknownFunction("Function not found".to_owned()))
+                Err(EvalError::unknown(EvalErrorKind::UnknownFunction, name))
             }
         },
         Expr::Variable(name) => {
             if let Some(v) = cnxt.get_variable(name) {
                 Ok(v)
             } else {
-                Err(EvalError::UnknownVariable("Variable not found".to_owned()))
+                Err(EvalError::unknown(EvalErrorKind::UnknownVariable, name))
             }
         },
-    }
+        Expr::Convert(v, t) => binary(v, t, cnxt, V::convert),
+        Expr::Assign(..) | Expr::Define(..) => {
+            Err(EvalError::new(EvalErrorKind::NotSupported, "Definitions require a mutable context"))
+        },
+    }.map_err(|e| e.located(expr))
 }
 
+pub fn execute<V: Value + 'static, C: Context<V>>(expr: &Expr, cnxt: &mut C) -> Result<Option<V>, EvalError> {
+    match expr {
+        Expr::Assign(name, value) => {
+            let value = evaluate::<V, C>(value, cnxt)?;
+            cnxt.set_variable(name, value.clone());
+            return Ok(Some(value));
+        },
+        Expr::Define(name, params, body) => {
+            let definition = FunctionDefinition { params: params.clone(), body: body.as_ref().clone() };
+            cnxt.set_definition(name, Rc::new(definition));
+            return Ok(None);
+        },
+        _ => {
+            return Ok(Some(evaluate::<V, C>(expr, cnxt)?));
+        },
+    }
+}
@@ -7,12 +7,34 @@ pub fn format(expr: &Expr) -> String {
 
 fn expr_prec(expr: &Expr) -> usize {
     match expr {
-        Expr::Add(..) => 10,
-        Expr::Sub(..) => 10,
+        Expr::Assign(..) => 0,
+        Expr::Define(..) => 0,
+        Expr::Convert(..) => 0,
+        Expr::If(..) => 1,
+        Expr::Or(..) => 2,
+        Expr::And(..) => 4,
+        Expr::Less(..) => 6,
+        Expr::LessEqual(..) => 6,
+        Expr::Equal(..) => 6,
+        Expr::NotEqual(..) => 6,
+        Expr::GreaterEqual(..) => 6,
+        Expr::Greater(..) => 6,
+        Expr::BitOr(..) => 7,
+        Expr::BitXor(..) => 8,
+        Expr::BitAnd(..) => 9,
+        Expr::ShiftLeft(..) => 10,
+        Expr::ShiftRight(..) => 10,
+        Expr::Add(..) => 12,
+        Expr::Sub(..) => 12,
         Expr::Mul(..) => 20,
         Expr::Div(..) => 20,
+        Expr::Mod(..) => 20,
+        Expr::FloorDiv(..) => 20,
+        Expr::PlusMinus(..) => 30,
         Expr::Pow(..) => 40,
         Expr::Neg(..) => 50,
+        Expr::Not(..) => 50,
+        Expr::BitNot(..) => 50,
         Expr::Function(..) => 50,
         Expr::Variable(..) => 50,
         Expr::Literal(..) => 50,
@@ -46,10 +68,105 @@ fn format_prec(expr: &Expr, parent: usize) -> String {
             res.push_str(" / ");
             res.push_str(&format_prec(r, prec + 1));
         },
+        Expr::Mod(l, r) => {
+            res.push_str(&format_prec(l, prec));
+            res.push_str(" % ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
+        Expr::FloorDiv(l, r) => {
+            res.push_str(&format_prec(l, prec));
+            res.push_str(" // ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
+        Expr::BitAnd(l, r) => {
+            res.push_str(&format_prec(l, prec));
+            res.push_str(" & ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
+        Expr::BitOr(l, r) => {
+            res.push_str(&format_prec(l, prec));
+            res.push_str(" | ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
+        Expr::BitXor(l, r) => {
+            res.push_str(&format_prec(l, prec));
+            res.push_str(" xor ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
+        Expr::ShiftLeft(l, r) => {
+            res.push_str(&format_prec(l, prec));
+            res.push_str(" << ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
+        Expr::ShiftRight(l, r) => {
+            res.push_str(&format_prec(l, prec));
+            res.push_str(" >> ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
         Expr::Neg(o) => {
             res.push_str("-");
             res.push_str(&format_prec(o, prec));
         },
+        Expr::If(c, t, o) => {
+            res.push_str(&format_prec(c, prec + 1));
+            res.push_str(" ? ");
+            res.push_str(&format_prec(t, 0));
+            res.push_str(" : ");
+            res.push_str(&format_prec(o, prec));
+        },
+        Expr::Not(o) => {
+            res.push_str("!");
+            res.push_str(&format_prec(o, prec));
+        },
+        Expr::BitNot(o) => {
+            res.push_str("~");
+            res.push_str(&format_prec(o, prec));
+        },
+        Expr::Less(l, r) => {
+            res.push_str(&format_prec(l, prec));
+            res.push_str(" < ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
+        Expr::LessEqual(l, r) => {
+            res.push_str(&format_prec(l, prec));
+            res.push_str(" <= ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
+        Expr::Equal(l, r) => {
+            res.push_str(&format_prec(l, prec));
+            res.push_str(" == ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
+        Expr::NotEqual(l, r) => {
+            res.push_str(&format_prec(l, prec));
+            res.push_str(" != ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
+        Expr::GreaterEqual(l, r) => {
+            res.push_str(&format_prec(l, prec));
+            res.push_str(" >= ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
+        Expr::Greater(l, r) => {
+            res.push_str(&format_prec(l, prec));
+            res.push_str(" > ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
+        Expr::And(l, r) => {
+            res.push_str(&format_prec(l, prec));
+            res.push_str(" && ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
+        Expr::Or(l, r) => {
+            res.push_str(&format_prec(l, prec));
+            res.push_str(" || ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
+        Expr::PlusMinus(l, r) => {
+            res.push_str(&format_prec(l, prec + 1));
+            res.push_str(" ± ");
+            res.push_str(&format_prec(r, prec + 1));
+        },
         Expr::Pow(l, r) => {
             res.push_str(&format_prec(l, prec + 1));
             res.push_str("^");
@@ -69,6 +186,23 @@ fn format_prec(expr: &Expr, parent: usize) -> String {
         Expr::Variable(name) => {
             res.push_str(name);
         },
+        Expr::Assign(name, value) => {
+            res.push_str(name);
+            res.push_str(" = ");
+            res.push_str(&format_prec(value, prec));
+        },
+        Expr::Convert(value, target) => {
+            res.push_str(&format_prec(value, prec + 1));
+            res.push_str(" to ");
+            res.push_str(&format_prec(target, prec + 1));
+        },
+        Expr::Define(name, params, body) => {
+            res.push_str(name);
+            res.push('(');
+            res.push_str(&params.join(", "));
+            res.push_str(") = ");
+            res.push_str(&format_prec(body, prec));
+        },
         Expr::Literal(s) => {
             res.push_str(s);
         },
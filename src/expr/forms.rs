@@ -0,0 +1,205 @@
+
+use std::rc::Rc;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use super::Expr;
+use super::Value;
+use super::Context;
+use super::ContextFn;
+use super::EvalError;
+use super::EvalErrorKind;
+use super::evaluate::evaluate;
+use super::function::FunctionDefinition;
+
+const INTEGRATION_STEPS: i64 = 256;
+const SOLVE_ITERATIONS: usize = 64;
+const MAX_FOLD_ITERATIONS: usize = 1_000_000;
+
+struct BoundContext<'a, V: Value> {
+    parent: &'a dyn Context<V>,
+    vars: HashMap<String, V>,
+    funcs: HashMap<String, Rc<ContextFn<V>>>,
+}
+
+impl<'a, V: Value> Context<V> for BoundContext<'a, V> {
+    fn set_variable(&mut self, name: &str, value: V) {
+        self.vars.insert(name.to_owned(), value);
+    }
+
+    fn set_function(&mut self, name: &str, value: Box<ContextFn<V>>) {
+        self.funcs.insert(name.to_owned(), Rc::from(value));
+    }
+
+    fn get_variable(&self, name: &str) -> Option<V> {
+        self.vars.get(name).cloned().or_else(|| self.parent.get_variable(name))
+    }
+
+    fn get_function(&self, name: &str) -> Option<Rc<ContextFn<V>>> {
+        self.funcs.get(name).cloned().or_else(|| self.parent.get_function(name))
+    }
+
+    fn recursion_limit(&self) -> usize {
+        self.parent.recursion_limit()
+    }
+
+    fn power(&self) -> Option<Rc<ContextFn<V>>> {
+        self.parent.power()
+    }
+
+    fn get_definition(&self, name: &str) -> Option<Rc<FunctionDefinition>> {
+        self.parent.get_definition(name)
+    }
+
+    fn globals(&self) -> Option<&dyn Context<V>> {
+        Some(self.parent.globals().unwrap_or(self.parent))
+    }
+
+    fn call_depth(&self) -> usize {
+        self.parent.call_depth()
+    }
+}
+
+struct Form<'a, V: Value> {
+    body: &'a dyn Fn(V) -> Result<V, EvalError>,
+    from: V,
+    to: V,
+}
+
+impl<'a, V: Value> Form<'a, V> {
+    fn at(&self, value: V) -> Result<V, EvalError> {
+        (self.body)(value)
+    }
+}
+
+pub fn split_form<'a>(name: &str, args: &'a [Expr]) -> Result<(&'a str, &'a Expr, &'a Expr, &'a Expr), EvalError> {
+    if args.len() != 4 {
+        return Err(EvalError::new(
+            EvalErrorKind::ArgumentMismatch, &format!("Function {} expects exactly four arguments", name)
+        ));
+    } else if let Expr::Variable(var) = &args[0] {
+        return Ok((var, &args[1], &args[2], &args[3]));
+    } else {
+        return Err(EvalError::new(
+            EvalErrorKind::ArgumentMismatch, &format!("Function {} expects a variable as the first argument", name)
+        ));
+    }
+}
+
+fn constant<V: Value>(value: i64) -> Result<V, EvalError> {
+    V::parse_literal(&value.to_string())
+}
+
+fn ordering<V: Value>(a: &V, b: &V) -> Result<Ordering, EvalError> {
+    a.compare(b)?.ok_or_else(|| EvalError::new(EvalErrorKind::MathError, "Values are not comparable"))
+}
+
+fn fold<V, F>(name: &str, form: Form<V>, empty: i64, op: F) -> Result<V, EvalError>
+where V: Value, F: Fn(V, V) -> Result<V, EvalError>
+{
+    let too_many = || EvalError::new(
+        EvalErrorKind::MathError, &format!("Function {} exceeds the limit of {} iterations", name, MAX_FOLD_ITERATIONS)
+    );
+    let length = form.to.clone().sub(form.from.clone())?;
+    if ordering(&length, &constant(MAX_FOLD_ITERATIONS as i64)?)? == Ordering::Greater {
+        return Err(too_many());
+    }
+    let mut result: Option<V> = None;
+    let mut i = form.from.clone();
+    let mut iterations = 0;
+    while ordering(&i, &form.to)? != Ordering::Greater {
+        iterations += 1;
+        if iterations > MAX_FOLD_ITERATIONS {
+            return Err(too_many());
+        }
+        let value = form.at(i.clone())?;
+        result = Some(match result {
+            Some(acc) => op(acc, value)?,
+            None => value,
+        });
+        let next = i.clone().add(constant(1)?)?;
+        if ordering(&next, &i)? != Ordering::Greater {
+            return Err(EvalError::new(
+                EvalErrorKind::MathError, &format!("The counter of {} stopped increasing at {}", name, i.to_string())
+            ));
+        }
+        i = next;
+    }
+    return result.map_or_else(|| constant(empty), Ok);
+}
+
+fn integrate<V: Value>(form: Form<V>) -> Result<V, EvalError> {
+    let step = form.to.clone().sub(form.from.clone())?.div(constant(INTEGRATION_STEPS)?)?;
+    let mut sum = form.at(form.from.clone())?.add(form.at(form.to.clone())?)?;
+    for i in 1..INTEGRATION_STEPS {
+        let x = form.from.clone().add(step.clone().mul(constant(i)?)?)?;
+        let weight = if i % 2 == 1 { 4 } else { 2 };
+        sum = sum.add(form.at(x)?.mul(constant(weight)?)?)?;
+    }
+    return sum.mul(step)?.div(constant(3)?);
+}
+
+fn solve<V: Value>(form: Form<V>) -> Result<V, EvalError> {
+    let mut low = form.from.clone();
+    let mut high = form.to.clone();
+    let f_low = form.at(low.clone())?;
+    let f_high = form.at(high.clone())?;
+    let zero = f_low.clone().sub(f_low.clone())?;
+    let sign = ordering(&f_low, &zero)?;
+    if sign == Ordering::Equal {
+        return Ok(low);
+    } else if ordering(&f_high, &zero)? == Ordering::Equal {
+        return Ok(high);
+    } else if ordering(&f_high, &zero)? == sign {
+        return Err(EvalError::new(EvalErrorKind::MathError, "No sign change in the interval given to solve"));
+    }
+    for _ in 0..SOLVE_ITERATIONS {
+        let mid = low.clone().add(high.clone())?.div(constant(2)?)?;
+        if ordering(&mid, &low)? == Ordering::Equal || ordering(&mid, &high)? == Ordering::Equal {
+            return Ok(mid);
+        }
+        let mid_sign = ordering(&form.at(mid.clone())?, &zero)?;
+        if mid_sign == Ordering::Equal {
+            return Ok(mid);
+        } else if mid_sign == sign {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    return low.add(high)?.div(constant(2)?);
+}
+
+pub fn is_form(name: &str) -> bool {
+    matches!(name, "sum" | "prod" | "integrate" | "solve")
+}
+
+pub fn apply_form<V: Value>(
+    name: &str, from: V, to: V, body: &dyn Fn(V) -> Result<V, EvalError>
+) -> Result<V, EvalError> {
+    let form = Form { body, from, to };
+    match name {
+        "sum" => fold(name, form, 0, |a, b| a.add(b)),
+        "prod" => fold(name, form, 1, |a, b| a.mul(b)),
+        "integrate" => integrate(form),
+        _ => solve(form),
+    }
+}
+
+pub fn evaluate_form<V: Value, C: Context<V>>(name: &str, args: &[Expr], cnxt: &C) -> Option<Result<V, EvalError>> {
+    if !is_form(name) {
+        return None;
+    }
+    let result = split_form(name, args).and_then(|(var, from, to, body)| {
+        let from = evaluate::<V, C>(from, cnxt)?;
+        let to = evaluate::<V, C>(to, cnxt)?;
+        let at = |value: V| {
+            let mut vars = HashMap::new();
+            vars.insert(var.to_owned(), value);
+            let scope = BoundContext { parent: cnxt, vars, funcs: HashMap::new() };
+            evaluate::<V, BoundContext<V>>(body, &scope)
+        };
+        apply_form(name, from, to, &at)
+    });
+    return Some(result);
+}
@@ -0,0 +1,187 @@
+use std::rc::Rc;
+use std::collections::HashMap;
+
+use super::Expr;
+use super::Value;
+use super::Context;
+use super::ContextFn;
+use super::EvalError;
+use super::EvalErrorKind;
+use super::evaluate::evaluate;
+
+pub struct FunctionDefinition {
+    pub params: Vec<String>,
+    pub body: Expr,
+}
+
+// The scope of a single call, which resolves everything but the parameters in the global context
+struct FunctionScope<'a, V: Value> {
+    globals: &'a dyn Context<V>,
+    vars: HashMap<String, V>,
+    funcs: HashMap<String, Rc<ContextFn<V>>>,
+    depth: usize,
+}
+
+impl<'a, V: Value> Context<V> for FunctionScope<'a, V> {
+    fn set_variable(&mut self, name: &str, value: V) {
+        self.vars.insert(name.to_owned(), value);
+    }
+
+    fn set_function(&mut self, name: &str, value: Box<ContextFn<V>>) {
+        self.funcs.insert(name.to_owned(), Rc::from(value));
+    }
+
+    fn get_variable(&self, name: &str) -> Option<V> {
+        self.vars.get(name).cloned().or_else(|| self.globals.get_variable(name))
+    }
+
+    fn get_function(&self, name: &str) -> Option<Rc<ContextFn<V>>> {
+        self.funcs.get(name).cloned().or_else(|| self.globals.get_function(name))
+    }
+
+    fn get_definition(&self, name: &str) -> Option<Rc<FunctionDefinition>> {
+        self.globals.get_definition(name)
+    }
+
+    fn recursion_limit(&self) -> usize {
+        self.globals.recursion_limit()
+    }
+
+    fn power(&self) -> Option<Rc<ContextFn<V>>> {
+        self.globals.power()
+    }
+
+    fn globals(&self) -> Option<&dyn Context<V>> {
+        Some(self.globals)
+    }
+
+    fn call_depth(&self) -> usize {
+        self.depth
+    }
+}
+
+// The names a function body refers to, copied from a context
+struct Snapshot<V: Value> {
+    vars: HashMap<String, V>,
+    funcs: HashMap<String, Rc<ContextFn<V>>>,
+    defs: HashMap<String, Rc<FunctionDefinition>>,
+    limit: usize,
+    power: Option<Rc<ContextFn<V>>>,
+}
+
+impl<V: Value> Context<V> for Snapshot<V> {
+    fn set_variable(&mut self, name: &str, value: V) {
+        self.vars.insert(name.to_owned(), value);
+    }
+
+    fn set_function(&mut self, name: &str, value: Box<ContextFn<V>>) {
+        self.defs.remove(name);
+        self.funcs.insert(name.to_owned(), Rc::from(value));
+    }
+
+    fn get_variable(&self, name: &str) -> Option<V> {
+        self.vars.get(name).cloned()
+    }
+
+    fn get_function(&self, name: &str) -> Option<Rc<ContextFn<V>>> {
+        self.funcs.get(name).cloned()
+    }
+
+    fn get_definition(&self, name: &str) -> Option<Rc<FunctionDefinition>> {
+        self.defs.get(name).cloned()
+    }
+
+    fn recursion_limit(&self) -> usize {
+        self.limit
+    }
+
+    fn power(&self) -> Option<Rc<ContextFn<V>>> {
+        self.power.clone()
+    }
+}
+
+pub fn call<V: Value>(function: &FunctionDefinition, args: &[V], cnxt: &dyn Context<V>) -> Result<V, EvalError> {
+    let depth = cnxt.call_depth() + 1;
+    if args.len() < function.params.len() {
+        return Err(EvalError::new(EvalErrorKind::ArgumentMismatch, "Too few arguments to function"));
+    } else if args.len() > function.params.len() {
+        return Err(EvalError::new(EvalErrorKind::ArgumentMismatch, "Too many arguments to function"));
+    } else if depth > cnxt.recursion_limit() {
+        return Err(EvalError::new(
+            EvalErrorKind::RecursionLimit, &format!("Maximum call depth of {} exceeded", cnxt.recursion_limit())
+        ));
+    }
+    let scope = FunctionScope {
+        globals: cnxt.globals().unwrap_or(cnxt),
+        vars: function.params.iter().cloned().zip(args.iter().cloned()).collect(),
+        funcs: HashMap::new(),
+        depth,
+    };
+    return evaluate::<V, FunctionScope<V>>(&function.body, &scope);
+}
+
+fn free_names(expr: &Expr, vars: &mut Vec<String>, funcs: &mut Vec<String>) {
+    match expr {
+        Expr::Literal(_) => {},
+        Expr::Variable(name) => vars.push(name.clone()),
+        Expr::Function(name, args) => {
+            funcs.push(name.clone());
+            for a in args {
+                free_names(a, vars, funcs);
+            }
+        },
+        Expr::Neg(o) | Expr::Not(o) | Expr::BitNot(o) | Expr::Assign(_, o) => free_names(o, vars, funcs),
+        Expr::Add(l, r) | Expr::Sub(l, r) | Expr::Mul(l, r) | Expr::Div(l, r) | Expr::Pow(l, r)
+        | Expr::PlusMinus(l, r) | Expr::Less(l, r) | Expr::LessEqual(l, r) | Expr::Equal(l, r)
+        | Expr::NotEqual(l, r) | Expr::GreaterEqual(l, r) | Expr::Greater(l, r) | Expr::And(l, r) | Expr::Or(l, r)
+        | Expr::Mod(l, r) | Expr::FloorDiv(l, r) | Expr::BitAnd(l, r) | Expr::BitOr(l, r) | Expr::BitXor(l, r)
+        | Expr::ShiftLeft(l, r) | Expr::ShiftRight(l, r) | Expr::Convert(l, r) => {
+            free_names(l, vars, funcs);
+            free_names(r, vars, funcs);
+        },
+        Expr::If(c, t, o) => {
+            free_names(c, vars, funcs);
+            free_names(t, vars, funcs);
+            free_names(o, vars, funcs);
+        },
+        Expr::Define(..) => {},
+    }
+}
+
+// Copies everything the function refers to, following the definitions it calls
+fn snapshot<V: Value, C: Context<V> + ?Sized>(name: &str, function: Rc<FunctionDefinition>, cnxt: &C) -> Snapshot<V> {
+    let mut res = Snapshot {
+        vars: HashMap::new(), funcs: HashMap::new(), defs: HashMap::new(),
+        limit: cnxt.recursion_limit(), power: cnxt.power(),
+    };
+    let mut pending = vec![(name.to_owned(), function)];
+    while let Some((name, function)) = pending.pop() {
+        let mut var_names = Vec::new();
+        let mut func_names = Vec::new();
+        free_names(&function.body, &mut var_names, &mut func_names);
+        res.defs.insert(name, function);
+        for var in var_names {
+            if let Some(v) = cnxt.get_variable(&var) {
+                res.vars.insert(var, v);
+            }
+        }
+        for func in func_names {
+            if res.defs.contains_key(&func) || res.funcs.contains_key(&func) {
+                continue;
+            } else if let Some(def) = cnxt.get_definition(&func) {
+                pending.push((func, def));
+            } else if let Some(f) = cnxt.get_function(&func) {
+                res.funcs.insert(func, f);
+            }
+        }
+    }
+    return res;
+}
+
+pub fn to_function<V: Value + 'static, C: Context<V> + ?Sized>(
+    name: &str, params: &[String], body: &Expr, cnxt: &C
+) -> Box<ContextFn<V>> {
+    let function = Rc::new(FunctionDefinition { params: params.to_vec(), body: body.clone() });
+    let globals = snapshot(name, function.clone(), cnxt);
+    return Box::new(move |args| call(&function, args, &globals));
+}
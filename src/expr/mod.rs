@@ -4,19 +4,32 @@ mod evaluate;
 mod parser;
 mod format;
 mod expr;
+mod function;
+mod forms;
+mod derivative;
+mod simplify;
+mod compile;
 
 pub use expr::Expr;
 pub use value::Value;
 pub use value::Context;
 pub use value::ContextFn;
+pub use value::DEFAULT_RECURSION_LIMIT;
 pub use evaluate::EvalError;
+pub use evaluate::EvalErrorKind;
 pub use parser::ParseError;
+pub use compile::CompiledExpr;
+pub use function::FunctionDefinition;
 
 impl Expr {
     pub fn parse(source: &str) -> Result<Expr, ParseError> {
         parser::parse(source)
     }
 
+    pub fn parse_program(source: &str) -> Result<Vec<Expr>, ParseError> {
+        parser::parse_program(source)
+    }
+
     pub fn eval<V: Value>(&self) -> Result<V, EvalError> {
         self.eval_in::<V, V::DefaultContext>(&V::default_context())
     }
@@ -24,6 +37,38 @@ impl Expr {
     pub fn eval_in<V: Value, C: Context<V>>(&self, c: &C) -> Result<V, EvalError> {
         evaluate::evaluate::<V, C>(self, c)
     }
+
+    pub fn execute_in<V: Value + 'static, C: Context<V>>(&self, c: &mut C) -> Result<Option<V>, EvalError> {
+        evaluate::execute::<V, C>(self, c)
+    }
+
+    pub fn execute_program_in<V: Value + 'static, C: Context<V>>(
+        program: &[Expr], c: &mut C
+    ) -> Result<Option<V>, EvalError> {
+        let mut result = None;
+        for statement in program {
+            result = statement.execute_in(c)?;
+        }
+        return Ok(result);
+    }
+
+    pub fn derivative(&self, var: &str) -> Result<Expr, EvalError> {
+        derivative::derivative(self, var)
+    }
+
+    pub fn simplify(&self) -> Expr {
+        simplify::simplify(self)
+    }
+
+    pub fn compile<V: Value + 'static>(&self, c: &dyn Context<V>, params: &[&str]) -> Result<CompiledExpr<V>, EvalError> {
+        compile::compile(self, c, params)
+    }
+
+    pub fn to_function<V: Value + 'static, C: Context<V>>(
+        &self, name: &str, params: &[String], c: &C
+    ) -> Box<ContextFn<V>> {
+        function::to_function(name, params, self, c)
+    }
 }
 
 impl ToString for Expr {
@@ -1,4 +1,8 @@
 
+use std::fmt;
+use std::error::Error;
+use std::ops::Range;
+
 use super::Expr;
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -6,7 +10,7 @@ enum TokenKind {
     Unknown,
     Identifier,
     Literal,
-    Operator(char),
+    Operator(&'static str),
     Separator(char),
     OpenBracket(char),
     CloseBracket(char),
@@ -16,13 +20,53 @@ enum TokenKind {
 struct Token {
     kind: TokenKind,
     position: usize,
+    end: usize,
     source: Option<String>,
 }
 
+impl Token {
+    fn describe(&self) -> String {
+        match self.kind {
+            TokenKind::Unknown => format!("unknown character '{}'", self.source.as_deref().unwrap_or("")),
+            TokenKind::Identifier => format!("identifier '{}'", self.source.as_deref().unwrap_or("")),
+            TokenKind::Literal => format!("literal '{}'", self.source.as_deref().unwrap_or("")),
+            TokenKind::Operator(op) => format!("'{}'", op),
+            TokenKind::Separator('\n') => "new line".to_owned(),
+            TokenKind::Separator(c)
+            | TokenKind::OpenBracket(c)
+            | TokenKind::CloseBracket(c) => format!("'{}'", c),
+        }
+    }
+}
+
+const OPERATORS: [&str; 26] = [
+    "<=", ">=", "==", "!=", "&&", "||", "+-", "<<", ">>", "//", "±", "+", "-", "*", "/", "%", "^", "<", ">", "&", "|",
+    "~", "!", "?", ":", "=",
+];
+
+// '+-' only stands for '±' with whitespace on both sides, so that '1+-2' keeps its meaning
+fn spaced(source: &[char], pos: usize, len: usize) -> bool {
+    pos > 0 && source[pos - 1].is_whitespace() && source.get(pos + len).map_or(false, |c| c.is_whitespace())
+}
+
+fn closing_bracket(open: char) -> char {
+    match open {
+        '[' => ']',
+        '{' => '}',
+        _ => ')',
+    }
+}
+
 #[derive(Debug)]
 struct ExprTokenizer {
     position: usize,
     source: Vec<char>,
+    offsets: Vec<usize>,
+    last_end: usize,
+    newlines: bool,
+    depth: usize,
+    // The spans of the arguments of the last function call parsed
+    call_args: Vec<Range<usize>>,
     next: Option<Token>
 }
 
@@ -30,33 +74,52 @@ impl Iterator for ExprTokenizer {
     type Item = Token;
     
     fn next(&mut self) -> Option<Token> {
-        if let Some(t) = std::mem::replace(&mut self.next, None) {
-            self.next = None;
-            return Some(t);
+        let next = if let Some(t) = std::mem::replace(&mut self.next, None) {
+            Some(t)
         } else {
-            return self.find_next();
+            self.find_next()
+        };
+        if let Some(t) = &next {
+            self.last_end = t.end;
         }
+        return next;
     }
 }
 
 impl ExprTokenizer {
     fn on(s: &str) -> ExprTokenizer {
+        let mut offsets = s.char_indices().map(|(i, _)| i).collect::<Vec<_>>();
+        offsets.push(s.len());
         return ExprTokenizer {
-            position: 0, source: s.chars().collect::<Vec<_>>(), next: None
+            position: 0, source: s.chars().collect::<Vec<_>>(), offsets, last_end: 0,
+            newlines: false, depth: 0, call_args: Vec::new(), next: None
         };
     }
 
     fn peek_kind(&mut self) -> Option<TokenKind> {
+        return self.peek().and_then(|x| Some(x.kind));
+    }
+
+    fn peek(&mut self) -> Option<&Token> {
         if self.next == None {
             self.next = self.find_next();
         }
-        return self.next.as_ref().and_then(|x| Some(x.kind));
+        return self.next.as_ref();
+    }
+
+    fn peek_keyword(&mut self, keyword: &str) -> bool {
+        if let Some(Token { kind: TokenKind::Identifier, source: Some(name), .. }) = self.peek() {
+            return name == keyword;
+        } else {
+            return false;
+        }
     }
 
     fn find_next(&mut self) -> Option<Token> {
         let source = &self.source;
         let pos = &mut self.position;
-        while *pos < source.len() && source[*pos].is_whitespace() {
+        let newlines = self.newlines && self.depth == 0;
+        while *pos < source.len() && source[*pos].is_whitespace() && !(newlines && source[*pos] == '\n') {
             *pos += 1;
         }
         if *pos == source.len() {
@@ -98,7 +161,7 @@ impl ExprTokenizer {
                     }
                 }
                 return Some(Token {
-                    kind: TokenKind::Literal, position: start,
+                    kind: TokenKind::Literal, position: start, end: *pos,
                     source: Some(source[start..*pos].iter().collect::<String>())
                 });
             } else if source[*pos].is_alphabetic() || source[*pos] == '_' {
@@ -107,86 +170,347 @@ impl ExprTokenizer {
                     *pos += 1;
                 }
                 return Some(Token {
-                    kind: TokenKind::Identifier, position: start,
+                    kind: TokenKind::Identifier, position: start, end: *pos,
                     source: Some(source[start..*pos].iter().collect::<String>())
                 });
             } else if let '(' | '[' | '{' =  source[*pos] {
                 let c = source[*pos];
                 *pos += 1;
-                return Some(Token { kind: TokenKind::OpenBracket(c), position: *pos - 1, source: None });
+                self.depth += 1;
+                return Some(Token { kind: TokenKind::OpenBracket(c), position: *pos - 1, end: *pos, source: None });
             } else if let ')' | ']' | '}' =  source[*pos] {
                 let c = source[*pos];
                 *pos += 1;
-                return Some(Token { kind: TokenKind::CloseBracket(c), position: *pos - 1, source: None });
-            } else if let '+' | '-' | '*' | '/' | '^' =  source[*pos] {
-                let c = source[*pos];
-                *pos += 1;
-                return Some(Token { kind: TokenKind::Operator(c), position: *pos - 1, source: None });
-            } else if let ',' | ';' =  source[*pos] {
+                self.depth = self.depth.saturating_sub(1);
+                return Some(Token { kind: TokenKind::CloseBracket(c), position: *pos - 1, end: *pos, source: None });
+            } else if let Some(op) = OPERATORS.iter().find(|op| {
+                op.chars().enumerate().all(|(i, c)| source.get(*pos + i) == Some(&c))
+                    && (**op != "+-" || spaced(source, *pos, 2))
+            }) {
+                let start = *pos;
+                *pos += op.chars().count();
+                return Some(Token { kind: TokenKind::Operator(op), position: start, end: *pos, source: None });
+            } else if let ',' | ';' | '\n' =  source[*pos] {
                 let c = source[*pos];
                 *pos += 1;
-                return Some(Token { kind: TokenKind::Separator(c), position: *pos - 1, source: None });
+                return Some(Token { kind: TokenKind::Separator(c), position: *pos - 1, end: *pos, source: None });
             } else {
                 *pos += 1;
-                return Some(Token { kind: TokenKind::Unknown, position: *pos - 1, source: None });
+                return Some(Token {
+                    kind: TokenKind::Unknown, position: *pos - 1, end: *pos,
+                    source: Some(source[*pos - 1].to_string())
+                });
             }
         }
     }
     
-    fn empty(&self) -> Token {
-        let position;
+    fn end_position(&self) -> usize {
         if let Some(Token { position: pos , .. }) = self.next {
-            position = pos;
+            return pos;
         } else {
-            position = self.position;
+            return self.last_end;
         }
-        return Token { kind: TokenKind::Unknown, position, source: None };
+    }
+
+    fn error(&self, found: Option<&Token>, msg: &str, expected: &[&str]) -> ParseError {
+        let (start, end) = found.map(|t| (t.position, t.end))
+            .unwrap_or((self.end_position(), self.end_position()));
+        return ParseError {
+            message: msg.to_owned(),
+            span: start..end,
+            bytes: self.offsets[start]..self.offsets[end],
+            expected: expected.iter().map(|e| e.to_string()).collect(),
+            found: found.map(|t| t.describe()).unwrap_or("end of input".to_owned()),
+            related: None,
+        };
+    }
+
+    fn error_at(&self, span: Range<usize>, msg: &str) -> ParseError {
+        return ParseError {
+            message: msg.to_owned(),
+            span: span.clone(),
+            bytes: self.offsets[span.start]..self.offsets[span.end],
+            expected: Vec::new(),
+            found: format!("'{}'", self.source[span].iter().collect::<String>()),
+            related: None,
+        };
     }
 }
 
 #[derive(Debug)]
 pub struct ParseError {
     message: String,
-    position: usize,
+    span: Range<usize>,
+    bytes: Range<usize>,
+    expected: Vec<String>,
+    found: String,
+    related: Option<Range<usize>>,
 }
 
 impl ParseError {
-    fn from(tok: &Token, msg: &str) -> ParseError {
-        return ParseError { message: msg.to_owned(), position: tok.position };
+    fn with_related(mut self, tok: &Token) -> ParseError {
+        self.related = Some(tok.position..tok.end);
+        return self;
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn position(&self) -> usize {
+        self.span.start
+    }
+
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    pub fn byte_span(&self) -> Range<usize> {
+        self.bytes.clone()
+    }
+
+    pub fn expected(&self) -> &[String] {
+        &self.expected
+    }
+
+    pub fn found(&self) -> &str {
+        &self.found
+    }
+
+    pub fn related_span(&self) -> Option<Range<usize>> {
+        self.related.clone()
+    }
+
+    pub fn render(&self, source: &str) -> String {
+        let mut res = self.to_string();
+        let mut marks = vec![self.span.clone()];
+        if let Some(related) = &self.related {
+            marks.push(related.clone());
+        }
+        marks.sort_by_key(|span| span.start);
+        let mut line_start = 0;
+        for line in source.split('\n') {
+            let line_end = line_start + line.chars().count();
+            let mut underline = String::new();
+            for span in &marks {
+                if span.start >= line_start && span.start <= line_end {
+                    let start = span.start - line_start;
+                    let length = span.end.min(line_end).saturating_sub(span.start).max(1);
+                    while underline.chars().count() < start {
+                        underline.push(' ');
+                    }
+                    underline.truncate(start);
+                    underline.push('^');
+                    underline.push_str(&"~".repeat(length - 1));
+                }
+            }
+            if !underline.is_empty() {
+                res.push_str(&format!("\n{}\n{}", line.trim_end(), underline));
+            }
+            line_start = line_end + 1;
+        }
+        return res;
     }
 }
 
-impl ToString for ParseError {
-    fn to_string(&self) -> String {
-        format!("Error at position {}: {}", self.position, self.message)
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Error at position {}: {}", self.span.start, self.message)?;
+        if !self.expected.is_empty() {
+            write!(f, " (expected {}, found {})", self.expected.join(" or "), self.found)?;
+        }
+        return Ok(());
     }
 }
 
+impl Error for ParseError {}
+
 pub fn parse(s: &str) -> Result<Expr, ParseError> {
     let mut tokens = ExprTokenizer::on(s);
     return parse_root(&mut tokens);
 }
 
+pub fn parse_program(s: &str) -> Result<Vec<Expr>, ParseError> {
+    let mut tokens = ExprTokenizer::on(s);
+    tokens.newlines = true;
+    let mut statements = Vec::new();
+    loop {
+        while let Some(TokenKind::Separator(';' | '\n')) = tokens.peek_kind() {
+            tokens.next();
+        }
+        if tokens.peek_kind() == None {
+            return Ok(statements);
+        }
+        statements.push(parse_statement(&mut tokens)?);
+        match tokens.next() {
+            None | Some(Token { kind: TokenKind::Separator(';' | '\n'), .. }) => {},
+            Some(t) => {
+                if let TokenKind::CloseBracket(_) = t.kind {
+                    return Err(tokens.error(Some(&t), "Unmatched closing bracket", &["';'", "new line", "end of input"]));
+                } else {
+                    return Err(tokens.error(
+                        Some(&t), "Expected the end of the statement", &["operator", "';'", "new line", "end of input"]
+                    ));
+                }
+            },
+        }
+    }
+}
+
 fn parse_root(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
-    let expr = parse_expr(tokens)?;
+    let expr = parse_statement(tokens)?;
     if let Some(t) = tokens.next() {
-        return Err(ParseError::from(&t, "Expected the end of input"));
+        if let TokenKind::CloseBracket(_) = t.kind {
+            return Err(tokens.error(Some(&t), "Unmatched closing bracket", &["end of input"]));
+        } else {
+            return Err(tokens.error(Some(&t), "Expected the end of input", &["operator", "end of input"]));
+        }
     } else {
         return Ok(expr);
     }
 }
 
+fn parse_statement(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
+    let target = parse_expr(tokens)?;
+    if tokens.peek_keyword("to") {
+        tokens.next();
+        return Ok(Expr::Convert(Box::new(target), Box::new(parse_expr(tokens)?)));
+    } else if let Some(TokenKind::Operator("=")) = tokens.peek_kind() {
+        let assign = tokens.next().unwrap();
+        match target {
+            Expr::Variable(name) => {
+                return Ok(Expr::Assign(name, Box::new(parse_expr(tokens)?)));
+            },
+            Expr::Function(name, args) => {
+                let mut params = Vec::new();
+                for (i, arg) in args.into_iter().enumerate() {
+                    match arg {
+                        Expr::Variable(param) if !params.contains(&param) => params.push(param),
+                        _ => {
+                            let msg = "Function parameters must be distinct variable names";
+                            match tokens.call_args.get(i) {
+                                Some(span) => return Err(tokens.error_at(span.clone(), msg)),
+                                None => return Err(tokens.error(Some(&assign), msg, &[])),
+                            }
+                        },
+                    }
+                }
+                return Ok(Expr::Define(name, params, Box::new(parse_expr(tokens)?)));
+            },
+            _ => {
+                return Err(tokens.error(Some(&assign), "Expected a variable or function to assign to", &[]));
+            },
+        }
+    } else {
+        return Ok(target);
+    }
+}
+
 fn parse_expr(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
-    parse_sum(tokens)
+    parse_conditional(tokens)
+}
+
+fn parse_conditional(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
+    let cond = parse_or(tokens)?;
+    if let Some(TokenKind::Operator("?")) = tokens.peek_kind() {
+        tokens.next();
+        let then = parse_expr(tokens)?;
+        let colon = tokens.next();
+        if let Some(Token { kind: TokenKind::Operator(":"), .. }) = colon {
+            let otherwise = parse_conditional(tokens)?;
+            return Ok(Expr::If(Box::new(cond), Box::new(then), Box::new(otherwise)));
+        } else {
+            return Err(tokens.error(colon.as_ref(), "Expected ':' in conditional expression", &["':'"]));
+        }
+    } else {
+        return Ok(cond);
+    }
+}
+
+fn parse_or(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
+    let mut or = parse_and(tokens)?;
+    while let Some(TokenKind::Operator("||")) = tokens.peek_kind() {
+        tokens.next();
+        or = Expr::Or(Box::new(or), Box::new(parse_and(tokens)?));
+    }
+    return Ok(or);
+}
+
+fn parse_and(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
+    let mut and = parse_comparison(tokens)?;
+    while let Some(TokenKind::Operator("&&")) = tokens.peek_kind() {
+        tokens.next();
+        and = Expr::And(Box::new(and), Box::new(parse_comparison(tokens)?));
+    }
+    return Ok(and);
+}
+
+fn parse_comparison(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
+    let mut cmp = parse_bit_or(tokens)?;
+    while let Some(TokenKind::Operator(op)) = tokens.peek_kind() {
+        let build = match op {
+            "<" => Expr::Less,
+            "<=" => Expr::LessEqual,
+            "==" => Expr::Equal,
+            "!=" => Expr::NotEqual,
+            ">=" => Expr::GreaterEqual,
+            ">" => Expr::Greater,
+            _ => break,
+        };
+        tokens.next();
+        cmp = build(Box::new(cmp), Box::new(parse_bit_or(tokens)?));
+    }
+    return Ok(cmp);
+}
+
+fn parse_bit_or(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
+    let mut or = parse_bit_xor(tokens)?;
+    while let Some(TokenKind::Operator("|")) = tokens.peek_kind() {
+        tokens.next();
+        or = Expr::BitOr(Box::new(or), Box::new(parse_bit_xor(tokens)?));
+    }
+    return Ok(or);
+}
+
+fn parse_bit_xor(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
+    let mut xor = parse_bit_and(tokens)?;
+    while tokens.peek_keyword("xor") {
+        tokens.next();
+        xor = Expr::BitXor(Box::new(xor), Box::new(parse_bit_and(tokens)?));
+    }
+    return Ok(xor);
+}
+
+fn parse_bit_and(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
+    let mut and = parse_shift(tokens)?;
+    while let Some(TokenKind::Operator("&")) = tokens.peek_kind() {
+        tokens.next();
+        and = Expr::BitAnd(Box::new(and), Box::new(parse_shift(tokens)?));
+    }
+    return Ok(and);
+}
+
+fn parse_shift(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
+    let mut shift = parse_sum(tokens)?;
+    while let Some(TokenKind::Operator(op)) = tokens.peek_kind() {
+        let build = match op {
+            "<<" => Expr::ShiftLeft,
+            ">>" => Expr::ShiftRight,
+            _ => break,
+        };
+        tokens.next();
+        shift = build(Box::new(shift), Box::new(parse_sum(tokens)?));
+    }
+    return Ok(shift);
 }
 
 fn parse_sum(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
     let mut sum = parse_product(tokens)?;
     while let Some(TokenKind::Operator(c)) = tokens.peek_kind() {
-        if c == '+' {
+        if c == "+" {
             tokens.next();
             sum = Expr::Add(Box::new(sum), Box::new(parse_product(tokens)?));
-        } else if c == '-' {
+        } else if c == "-" {
             tokens.next();
             sum = Expr::Sub(Box::new(sum), Box::new(parse_product(tokens)?));
         } else {
@@ -197,17 +521,23 @@ fn parse_sum(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
 }
 
 fn parse_product(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
-    let mut prod = parse_power(tokens)?;
+    let mut prod = parse_uncertain(tokens)?;
     loop {
         let kind = tokens.peek_kind();
-        if kind == Some(TokenKind::Operator('*')) {
+        if kind == Some(TokenKind::Operator("*")) {
+            tokens.next();
+            prod = Expr::Mul(Box::new(prod), Box::new(parse_uncertain(tokens)?));
+        } else if kind == Some(TokenKind::Operator("/")) {
             tokens.next();
-            prod = Expr::Mul(Box::new(prod), Box::new(parse_power(tokens)?));
-        } else if kind == Some(TokenKind::Operator('/')) {
+            prod = Expr::Div(Box::new(prod), Box::new(parse_uncertain(tokens)?));
+        } else if kind == Some(TokenKind::Operator("%")) {
             tokens.next();
-            prod = Expr::Div(Box::new(prod), Box::new(parse_power(tokens)?));
-        } else if let Some(TokenKind::Identifier | TokenKind::Literal | TokenKind::OpenBracket(_)) = kind {
-            prod = Expr::Mul(Box::new(prod), Box::new(parse_power(tokens)?));
+            prod = Expr::Mod(Box::new(prod), Box::new(parse_implicit_product(tokens)?));
+        } else if kind == Some(TokenKind::Operator("//")) {
+            tokens.next();
+            prod = Expr::FloorDiv(Box::new(prod), Box::new(parse_implicit_product(tokens)?));
+        } else if implicit_factor_follows(tokens) {
+            prod = Expr::Mul(Box::new(prod), Box::new(parse_uncertain(tokens)?));
         } else {
             break;
         }
@@ -215,59 +545,133 @@ fn parse_product(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
     return Ok(prod);
 }
 
+fn implicit_factor_follows(tokens: &mut ExprTokenizer) -> bool {
+    match tokens.peek_kind() {
+        Some(TokenKind::Identifier) => !tokens.peek_keyword("to") && !tokens.peek_keyword("xor"),
+        Some(TokenKind::Literal | TokenKind::OpenBracket(_)) => true,
+        _ => false,
+    }
+}
+
+// The divisor of a modulo includes implicit multiplication, so that '100 min % 1 h' takes the remainder by an hour
+fn parse_implicit_product(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
+    let mut prod = parse_uncertain(tokens)?;
+    while implicit_factor_follows(tokens) {
+        prod = Expr::Mul(Box::new(prod), Box::new(parse_uncertain(tokens)?));
+    }
+    return Ok(prod);
+}
+
+fn parse_uncertain(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
+    let mut value = parse_power(tokens)?;
+    if let Some(TokenKind::Operator("±" | "+-")) = tokens.peek_kind() {
+        tokens.next();
+        value = Expr::PlusMinus(Box::new(value), Box::new(parse_power(tokens)?));
+    }
+    return Ok(value);
+}
+
 fn parse_power(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
-    let mut pow = parse_base(tokens)?;
-    if let Some(TokenKind::Operator('^')) = tokens.peek_kind() {
+    let mut pow = parse_factorial(tokens)?;
+    if let Some(TokenKind::Operator("^")) = tokens.peek_kind() {
         tokens.next();
         pow = Expr::Pow(Box::new(pow), Box::new(parse_power(tokens)?));
     }
     return Ok(pow);
 }
 
+fn parse_factorial(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
+    let mut value = parse_base(tokens)?;
+    while let Some(TokenKind::Operator("!")) = tokens.peek_kind() {
+        tokens.next();
+        value = Expr::Function("factorial".to_owned(), vec![value]);
+    }
+    return Ok(value);
+}
+
 fn parse_base(tokens: &mut ExprTokenizer) -> Result<Expr, ParseError> {
-    if let Some(TokenKind::Operator('+')) = tokens.peek_kind() {
+    if let Some(TokenKind::Operator("+")) = tokens.peek_kind() {
         tokens.next();
         return parse_base(tokens);
-    } else if let Some(TokenKind::Operator('-')) = tokens.peek_kind() {
+    } else if let Some(TokenKind::Operator("-")) = tokens.peek_kind() {
         tokens.next();
         return Ok(Expr::Neg(Box::new(parse_base(tokens)?)));
+    } else if let Some(TokenKind::Operator("!")) = tokens.peek_kind() {
+        tokens.next();
+        return Ok(Expr::Not(Box::new(parse_base(tokens)?)));
+    } else if let Some(TokenKind::Operator("~")) = tokens.peek_kind() {
+        tokens.next();
+        return Ok(Expr::BitNot(Box::new(parse_base(tokens)?)));
     } else if let Some(TokenKind::Identifier) = tokens.peek_kind() {
         let name = tokens.next().unwrap();
         if let Some(TokenKind::OpenBracket('(')) = tokens.peek_kind() {
-            tokens.next();
+            let open = tokens.next().unwrap();
             let mut args = Vec::new();
+            let mut spans = Vec::new();
             if tokens.peek_kind() != Some(TokenKind::CloseBracket(')')) {
-                args.push(parse_expr(tokens)?);
+                args.push(parse_argument(tokens, &mut spans)?);
                 while Some(TokenKind::Separator(',')) == tokens.peek_kind() {
                     tokens.next();
-                    args.push(parse_expr(tokens)?);
+                    args.push(parse_argument(tokens, &mut spans)?);
                 }
             }
             let closing = tokens.next();
             if let Some(Token { kind: TokenKind::CloseBracket(')'), .. }) = closing {
-                return Ok(Expr::Function(name.source.unwrap(), args));
+                tokens.call_args = spans;
+                let name = name.source.unwrap();
+                if name == "if" && args.len() == 3 {
+                    let otherwise = args.pop().unwrap();
+                    let then = args.pop().unwrap();
+                    let cond = args.pop().unwrap();
+                    return Ok(Expr::If(Box::new(cond), Box::new(then), Box::new(otherwise)));
+                } else {
+                    return Ok(Expr::Function(name, args));
+                }
             } else {
-                return Err(ParseError::from(
-                    &closing.unwrap_or(tokens.empty()),
-                    "Expected matching closing bracket for function arguments"
+                return Err(mismatched_bracket(
+                    tokens, &open, closing.as_ref(), "Expected matching closing bracket for function arguments", &["','", "')'"]
                 ));
             }
         } else {
             return Ok(Expr::Variable(name.source.unwrap()));
         }
-    } else if let Some(TokenKind::OpenBracket('(')) = tokens.peek_kind() {
-        tokens.next();
+    } else if let Some(TokenKind::OpenBracket(c)) = tokens.peek_kind() {
+        let open = tokens.next().unwrap();
         let expr = parse_expr(tokens)?;
         let closing = tokens.next();
-        if let Some(Token { kind: TokenKind::CloseBracket(')'), .. }) = closing {
+        if closing.as_ref().map(|t| t.kind) == Some(TokenKind::CloseBracket(closing_bracket(c))) {
             return Ok(expr);
         } else {
-            return Err(ParseError::from(&closing.unwrap_or(tokens.empty()), "Expected matching closing bracket"));
+            let expected = format!("'{}'", closing_bracket(c));
+            return Err(mismatched_bracket(
+                tokens, &open, closing.as_ref(), "Expected matching closing bracket", &[&expected]
+            ));
         }
     } else if let Some(TokenKind::Literal) = tokens.peek_kind() {
         return Ok(Expr::Literal(tokens.next().unwrap().source.unwrap()));
     } else {
-        return Err(ParseError::from(&tokens.next().unwrap_or(tokens.empty()), "Expected an expression"));
+        let found = tokens.next();
+        return Err(tokens.error(
+            found.as_ref(), "Expected an expression", &["literal", "identifier", "'('", "'+'", "'-'", "'!'", "'~'"]
+        ));
+    }
+}
+
+fn parse_argument(tokens: &mut ExprTokenizer, spans: &mut Vec<Range<usize>>) -> Result<Expr, ParseError> {
+    tokens.peek();
+    let start = tokens.end_position();
+    let arg = parse_expr(tokens)?;
+    spans.push(start..tokens.last_end);
+    return Ok(arg);
+}
+
+fn mismatched_bracket(
+    tokens: &ExprTokenizer, open: &Token, closing: Option<&Token>, msg: &str, expected: &[&str]
+) -> ParseError {
+    if let Some(Token { kind: TokenKind::CloseBracket(_), .. }) = closing {
+        return tokens.error(closing, "Mismatched brackets", expected).with_related(open);
+    } else {
+        return tokens.error(closing, msg, expected).with_related(open);
     }
 }
 
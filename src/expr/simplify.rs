@@ -0,0 +1,400 @@
+
+use std::str::FromStr;
+use num::{BigRational, BigInt, Zero, One, Signed, ToPrimitive};
+use num::traits::Pow;
+
+use crate::Number;
+use crate::Value;
+use crate::EvalError;
+
+use super::Expr;
+
+const MAX_FOLDED_EXPONENT: i32 = 1024;
+
+#[derive(Clone, PartialEq)]
+struct Term {
+    coefficient: BigRational,
+    factors: Vec<(Expr, Expr)>,
+}
+
+impl Term {
+    fn constant(value: BigRational) -> Term {
+        Term { coefficient: value, factors: Vec::new() }
+    }
+
+    fn factor(base: Expr, exponent: Expr) -> Term {
+        Term { coefficient: BigRational::one(), factors: vec![(base, exponent)] }
+    }
+
+    fn neg(mut self) -> Term {
+        self.coefficient = -self.coefficient;
+        return self;
+    }
+
+    fn mul(mut self, other: Term) -> Term {
+        self.coefficient *= other.coefficient;
+        self.factors.extend(other.factors);
+        return self;
+    }
+
+    // Replaces every exponent, unless that would make a factor defined where it was not before
+    fn map_exponents<F: Fn(Expr) -> Expr>(self, coefficient: BigRational, f: F) -> Option<Term> {
+        let mut factors = Vec::new();
+        for (base, exp) in self.factors {
+            let new = f(exp.clone());
+            if !keeps_domain(&base, &new, &[&exp]) {
+                return None;
+            }
+            factors.push((base, new));
+        }
+        return Some(Term { coefficient, factors });
+    }
+
+    fn inverse(self) -> Option<Term> {
+        if self.coefficient.is_zero() {
+            return None;
+        } else {
+            let coefficient = self.coefficient.recip();
+            return self.map_exponents(coefficient, |exp| simplify(&Expr::Neg(Box::new(exp))));
+        }
+    }
+
+    fn pow(self, exp: i32) -> Option<Term> {
+        if exp.abs() > MAX_FOLDED_EXPONENT || (exp <= 0 && self.coefficient.is_zero()) {
+            return None;
+        } else {
+            let k = constant(BigRational::from_integer(BigInt::from(exp)));
+            let coefficient = Pow::pow(&self.coefficient, exp);
+            return self.map_exponents(coefficient, |e| simplify(&Expr::Mul(Box::new(e), Box::new(k.clone()))));
+        }
+    }
+
+    fn normalize(mut self) -> Term {
+        let mut factors: Vec<(Expr, Expr)> = Vec::new();
+        for (base, exp) in self.factors {
+            let merged = factors.iter_mut().filter(|(b, _)| b == &base).find_map(|existing| {
+                let sum = simplify(&Expr::Add(Box::new(existing.1.clone()), Box::new(exp.clone())));
+                if keeps_domain(&base, &sum, &[&existing.1, &exp]) {
+                    existing.1 = sum;
+                    return Some(());
+                } else {
+                    return None;
+                }
+            });
+            if merged.is_none() {
+                factors.push((base, exp));
+            }
+        }
+        factors.retain(|(base, exp)| !(is_zero(exp) && is_positive(base)));
+        factors.sort_by_key(|(base, _)| base.to_string());
+        self.factors = factors;
+        return self;
+    }
+
+    fn key(&self) -> String {
+        build_product(&Term { coefficient: BigRational::one(), factors: self.factors.clone() }).to_string()
+    }
+}
+
+fn constant(value: BigRational) -> Expr {
+    let num = Expr::Literal(value.numer().abs().to_string());
+    let res = if value.is_integer() {
+        num
+    } else {
+        Expr::Div(Box::new(num), Box::new(Expr::Literal(value.denom().to_string())))
+    };
+    if value.is_negative() {
+        return Expr::Neg(Box::new(res));
+    } else {
+        return res;
+    }
+}
+
+fn is_zero(expr: &Expr) -> bool {
+    as_constant(expr).map_or(false, |v| v.is_zero())
+}
+
+fn is_positive(expr: &Expr) -> bool {
+    as_constant(expr).map_or(false, |v| v.is_positive())
+}
+
+// Whether `base^exp` fails for a zero base and whether it is NaN for a negative one
+fn restrictions(base: &Expr, exp: &Expr) -> (bool, bool) {
+    if is_positive(base) {
+        return (false, false);
+    }
+    match as_constant(exp) {
+        Some(e) => return (!e.is_positive(), !e.is_integer()),
+        None => return (true, true),
+    }
+}
+
+// Rewriting factors of `base` with exponents `old` into a single one with exponent `new` must not remove an
+// error or a NaN, so `x / x` and `x^0.5 * x^0.5` stay as they are
+fn keeps_domain(base: &Expr, new: &Expr, old: &[&Expr]) -> bool {
+    if old.len() > 1 && is_zero(new) && !is_positive(base) {
+        return false;
+    }
+    let (zero, negative) = restrictions(base, new);
+    return old.iter().all(|exp| {
+        let (z, n) = restrictions(base, exp);
+        (zero || !z) && (negative || !n)
+    });
+}
+
+fn as_constant(expr: &Expr) -> Option<BigRational> {
+    let term = product_term(expr);
+    if term.factors.is_empty() {
+        return Some(term.coefficient);
+    } else {
+        return None;
+    }
+}
+
+fn literal_value(literal: &str) -> Option<BigRational> {
+    if let Ok(Number::Rational(r)) = Number::from_str(literal) {
+        return Some(r);
+    } else {
+        return None;
+    }
+}
+
+fn sum_terms(expr: &Expr) -> Vec<Term> {
+    match expr {
+        Expr::Add(l, r) => {
+            let mut terms = sum_terms(l);
+            terms.extend(sum_terms(r));
+            return terms;
+        },
+        Expr::Sub(l, r) => {
+            let mut terms = sum_terms(l);
+            terms.extend(sum_terms(r).into_iter().map(Term::neg));
+            return terms;
+        },
+        Expr::Neg(o) => {
+            return sum_terms(o).into_iter().map(Term::neg).collect();
+        },
+        _ => {
+            return vec![product_term(expr).normalize()];
+        },
+    }
+}
+
+fn product_term(expr: &Expr) -> Term {
+    match expr {
+        Expr::Literal(s) => {
+            if let Some(value) = literal_value(s) {
+                return Term::constant(value);
+            } else {
+                return Term::factor(expr.clone(), Expr::Literal("1".to_owned()));
+            }
+        },
+        Expr::Neg(o) => product_term(o).neg(),
+        Expr::Mul(l, r) => product_term(l).mul(product_term(r)).normalize(),
+        Expr::Div(l, r) => {
+            if let Some(inverse) = product_term(r).inverse() {
+                return product_term(l).mul(inverse).normalize();
+            } else {
+                return Term::factor(Expr::Div(Box::new(simplify(l)), Box::new(simplify(r))), Expr::Literal("1".to_owned()));
+            }
+        },
+        Expr::Pow(l, r) => {
+            let exp = simplify(r);
+            if let Some(k) = as_constant(&exp) {
+                if k.is_integer() {
+                    if let Some(term) = k.to_i32().and_then(|k| product_term(l).pow(k)) {
+                        return term.normalize();
+                    }
+                }
+            }
+            let base = simplify(l);
+            if is_zero(&exp) && as_constant(&base).map_or(false, |b| !b.is_zero()) {
+                return Term::constant(BigRational::one());
+            } else if base == Expr::Literal("1".to_owned()) && as_constant(&exp).is_some() {
+                return Term::constant(BigRational::one());
+            } else {
+                return Term::factor(base, exp);
+            }
+        },
+        Expr::Add(..) | Expr::Sub(..) => {
+            let sum = simplify(expr);
+            if let Expr::Add(..) | Expr::Sub(..) = sum {
+                return Term::factor(sum, Expr::Literal("1".to_owned()));
+            } else {
+                return product_term(&sum);
+            }
+        },
+        _ => {
+            match simplify_other(expr) {
+                Some(other @ (Expr::Literal(_) | Expr::Neg(_) | Expr::Add(..) | Expr::Sub(..)
+                | Expr::Mul(..) | Expr::Div(..) | Expr::Pow(..))) => return product_term(&other),
+                Some(other) => return Term::factor(other, Expr::Literal("1".to_owned())),
+                None => return Term::factor(expr.clone(), Expr::Literal("1".to_owned())),
+            }
+        },
+    }
+}
+
+fn build_product(term: &Term) -> Expr {
+    let mut num = Vec::new();
+    let mut den = Vec::new();
+    if !term.coefficient.numer().abs().is_one() || term.factors.is_empty() {
+        num.push(Expr::Literal(term.coefficient.numer().abs().to_string()));
+    }
+    if !term.coefficient.denom().is_one() {
+        den.push(Expr::Literal(term.coefficient.denom().to_string()));
+    }
+    for (base, exp) in &term.factors {
+        let (exp, list) = match as_constant(exp) {
+            Some(e) if e.is_negative() => (constant(-e), &mut den),
+            _ => (exp.clone(), &mut num),
+        };
+        if exp == Expr::Literal("1".to_owned()) {
+            list.push(base.clone());
+        } else {
+            list.push(Expr::Pow(Box::new(base.clone()), Box::new(exp)));
+        }
+    }
+    if num.is_empty() {
+        num.push(Expr::Literal("1".to_owned()));
+    }
+    if term.coefficient.is_negative() {
+        num[0] = Expr::Neg(Box::new(num[0].clone()));
+    }
+    let product = |list: Vec<Expr>| list.into_iter()
+        .reduce(|l, r| Expr::Mul(Box::new(l), Box::new(r)))
+        .unwrap();
+    if den.is_empty() {
+        return product(num);
+    } else {
+        return Expr::Div(Box::new(product(num)), Box::new(product(den)));
+    }
+}
+
+fn build_sum(terms: Vec<Term>) -> Expr {
+    let mut collected: Vec<Term> = Vec::new();
+    for term in terms {
+        if let Some(existing) = collected.iter_mut().find(|t| t.factors == term.factors) {
+            existing.coefficient += term.coefficient;
+        } else {
+            collected.push(term);
+        }
+    }
+    // Variables may carry units, so vanishing terms are only dropped from sums of constants, which keeps `x - x` as
+    // `0 * x` and `x + 0` as it is
+    let constant_sum = collected.iter().all(|t| t.factors.is_empty());
+    collected.retain(|t| !t.coefficient.is_zero() || !constant_sum);
+    collected.sort_by_key(|t| (t.factors.is_empty(), t.key()));
+    let mut res: Option<Expr> = None;
+    for term in collected {
+        res = Some(match res {
+            None => build_product(&term),
+            Some(acc) if term.coefficient.is_negative() => {
+                Expr::Sub(Box::new(acc), Box::new(build_product(&term.neg())))
+            },
+            Some(acc) => Expr::Add(Box::new(acc), Box::new(build_product(&term))),
+        });
+    }
+    return res.unwrap_or(Expr::Literal("0".to_owned()));
+}
+
+fn truth(value: bool) -> Expr {
+    Expr::Literal(if value { "1" } else { "0" }.to_owned())
+}
+
+fn simplify_comparison<F>(l: &Expr, r: &Expr, build: F, cmp: fn(&BigRational, &BigRational) -> bool) -> Expr
+where F: Fn(Box<Expr>, Box<Expr>) -> Expr
+{
+    let l = simplify(l);
+    let r = simplify(r);
+    if let (Some(a), Some(b)) = (as_constant(&l), as_constant(&r)) {
+        return truth(cmp(&a, &b));
+    } else {
+        return build(Box::new(l), Box::new(r));
+    }
+}
+
+fn simplify_integer<F>(l: &Expr, r: &Expr, build: F, op: fn(Number, Number) -> Result<Number, EvalError>) -> Expr
+where F: Fn(Box<Expr>, Box<Expr>) -> Expr
+{
+    let l = simplify(l);
+    let r = simplify(r);
+    if let (Some(a), Some(b)) = (as_constant(&l), as_constant(&r)) {
+        if let Ok(Number::Rational(res)) = op(Number::Rational(a), Number::Rational(b)) {
+            return constant(res);
+        }
+    }
+    return build(Box::new(l), Box::new(r));
+}
+
+fn simplify_other(expr: &Expr) -> Option<Expr> {
+    match expr {
+        Expr::Function(name, args) => Some(Expr::Function(name.clone(), args.iter().map(simplify).collect())),
+        Expr::Less(l, r) => Some(simplify_comparison(l, r, Expr::Less, |a, b| a < b)),
+        Expr::LessEqual(l, r) => Some(simplify_comparison(l, r, Expr::LessEqual, |a, b| a <= b)),
+        Expr::Equal(l, r) => Some(simplify_comparison(l, r, Expr::Equal, |a, b| a == b)),
+        Expr::NotEqual(l, r) => Some(simplify_comparison(l, r, Expr::NotEqual, |a, b| a != b)),
+        Expr::GreaterEqual(l, r) => Some(simplify_comparison(l, r, Expr::GreaterEqual, |a, b| a >= b)),
+        Expr::Greater(l, r) => Some(simplify_comparison(l, r, Expr::Greater, |a, b| a > b)),
+        Expr::Mod(l, r) => Some(simplify_integer(l, r, Expr::Mod, Value::modulo)),
+        Expr::FloorDiv(l, r) => Some(simplify_integer(l, r, Expr::FloorDiv, Value::floor_div)),
+        Expr::BitAnd(l, r) => Some(simplify_integer(l, r, Expr::BitAnd, Value::bit_and)),
+        Expr::BitOr(l, r) => Some(simplify_integer(l, r, Expr::BitOr, Value::bit_or)),
+        Expr::BitXor(l, r) => Some(simplify_integer(l, r, Expr::BitXor, Value::bit_xor)),
+        Expr::ShiftLeft(l, r) => Some(simplify_integer(l, r, Expr::ShiftLeft, Value::shift_left)),
+        Expr::ShiftRight(l, r) => Some(simplify_integer(l, r, Expr::ShiftRight, Value::shift_right)),
+        Expr::BitNot(o) => {
+            let o = simplify(o);
+            match as_constant(&o).map(|v| Value::bit_not(Number::Rational(v))) {
+                Some(Ok(Number::Rational(res))) => Some(constant(res)),
+                _ => Some(Expr::BitNot(Box::new(o))),
+            }
+        },
+        Expr::Not(o) => {
+            let o = simplify(o);
+            match as_constant(&o) {
+                Some(v) => Some(truth(v.is_zero())),
+                None => Some(Expr::Not(Box::new(o))),
+            }
+        },
+        Expr::And(l, r) => {
+            let l = simplify(l);
+            let r = simplify(r);
+            match (as_constant(&l), as_constant(&r)) {
+                (Some(a), _) if a.is_zero() => Some(truth(false)),
+                (Some(_), Some(b)) => Some(truth(!b.is_zero())),
+                _ => Some(Expr::And(Box::new(l), Box::new(r))),
+            }
+        },
+        Expr::Or(l, r) => {
+            let l = simplify(l);
+            let r = simplify(r);
+            match (as_constant(&l), as_constant(&r)) {
+                (Some(a), _) if !a.is_zero() => Some(truth(true)),
+                (Some(_), Some(b)) => Some(truth(!b.is_zero())),
+                _ => Some(Expr::Or(Box::new(l), Box::new(r))),
+            }
+        },
+        Expr::If(c, t, o) => {
+            let c = simplify(c);
+            match as_constant(&c) {
+                Some(v) if v.is_zero() => Some(simplify(o)),
+                Some(_) => Some(simplify(t)),
+                None => Some(Expr::If(Box::new(c), Box::new(simplify(t)), Box::new(simplify(o)))),
+            }
+        },
+        Expr::Assign(name, value) => Some(Expr::Assign(name.clone(), Box::new(simplify(value)))),
+        Expr::Define(name, params, body) => Some(Expr::Define(name.clone(), params.clone(), Box::new(simplify(body)))),
+        Expr::PlusMinus(value, error) => Some(Expr::PlusMinus(Box::new(simplify(value)), Box::new(simplify(error)))),
+        Expr::Convert(value, target) => Some(Expr::Convert(Box::new(simplify(value)), Box::new(simplify(target)))),
+        _ => None,
+    }
+}
+
+pub fn simplify(expr: &Expr) -> Expr {
+    match expr {
+        Expr::Literal(_) | Expr::Neg(_) | Expr::Add(..) | Expr::Sub(..)
+        | Expr::Mul(..) | Expr::Div(..) | Expr::Pow(..) => build_sum(sum_terms(expr)),
+        _ => simplify_other(expr).unwrap_or_else(|| expr.clone()),
+    }
+}
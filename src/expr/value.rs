@@ -1,24 +1,123 @@
 
-use std::str::FromStr;
-use std::ops::*;
-use num::traits::Pow;
+use std::cmp::Ordering;
+use std::rc::Rc;
 
 use super::EvalError;
+use super::EvalErrorKind;
+use super::function;
+use super::function::FunctionDefinition;
+
+fn unsupported(op: &str) -> EvalError {
+    EvalError::new(EvalErrorKind::NotSupported, &format!("Values of this type do not support the {} operator", op))
+}
 
 pub trait Value
-where Self:
-    Sized + ToString
-    + FromStr<Err = EvalError> + Add<Output = Result<Self, EvalError>>
-    + Sub<Output = Result<Self, EvalError>> + Mul<Output = Result<Self, EvalError>>
-    + Div<Output = Result<Self, EvalError>> + Neg<Output = Result<Self, EvalError>>
-    + Pow<Self, Output = Result<Self, EvalError>> + PartialOrd
+where Self: Sized + Clone + ToString + PartialOrd
 {
     type DefaultContext: Context<Self>;
 
     fn default_context() -> Self::DefaultContext;
+
+    fn parse_literal(literal: &str) -> Result<Self, EvalError>;
+
+    fn add(self, other: Self) -> Result<Self, EvalError>;
+
+    fn sub(self, other: Self) -> Result<Self, EvalError>;
+
+    fn mul(self, other: Self) -> Result<Self, EvalError>;
+
+    fn div(self, other: Self) -> Result<Self, EvalError>;
+
+    fn pow(self, other: Self) -> Result<Self, EvalError>;
+
+    fn neg(self) -> Result<Self, EvalError>;
+
+    fn plus_minus(self, _error: Self) -> Result<Self, EvalError> {
+        return Err(EvalError::new(EvalErrorKind::NotSupported, "Values of this type can not carry an uncertainty"));
+    }
+
+    fn modulo(self, _other: Self) -> Result<Self, EvalError> {
+        return Err(unsupported("%"));
+    }
+
+    fn floor_div(self, _other: Self) -> Result<Self, EvalError> {
+        return Err(unsupported("//"));
+    }
+
+    fn bit_and(self, _other: Self) -> Result<Self, EvalError> {
+        return Err(unsupported("&"));
+    }
+
+    fn bit_or(self, _other: Self) -> Result<Self, EvalError> {
+        return Err(unsupported("|"));
+    }
+
+    fn bit_xor(self, _other: Self) -> Result<Self, EvalError> {
+        return Err(unsupported("xor"));
+    }
+
+    fn shift_left(self, _other: Self) -> Result<Self, EvalError> {
+        return Err(unsupported("<<"));
+    }
+
+    fn shift_right(self, _other: Self) -> Result<Self, EvalError> {
+        return Err(unsupported(">>"));
+    }
+
+    fn bit_not(self) -> Result<Self, EvalError> {
+        return Err(unsupported("~"));
+    }
+
+    fn from_bool(value: bool) -> Self;
+
+    fn to_bool(&self) -> Result<bool, EvalError>;
+
+    fn convert(self, target: Self) -> Result<Self, EvalError> {
+        return self.div(target);
+    }
+
+    fn compare(&self, other: &Self) -> Result<Option<Ordering>, EvalError> {
+        return Ok(self.partial_cmp(other));
+    }
+
+    fn less(self, other: Self) -> Result<Self, EvalError> {
+        let ord = self.compare(&other)?;
+        return Ok(Self::from_bool(ord == Some(Ordering::Less)));
+    }
+
+    fn less_equal(self, other: Self) -> Result<Self, EvalError> {
+        let ord = self.compare(&other)?;
+        return Ok(Self::from_bool(ord == Some(Ordering::Less) || ord == Some(Ordering::Equal)));
+    }
+
+    fn equal(self, other: Self) -> Result<Self, EvalError> {
+        let ord = self.compare(&other)?;
+        return Ok(Self::from_bool(ord == Some(Ordering::Equal)));
+    }
+
+    fn not_equal(self, other: Self) -> Result<Self, EvalError> {
+        let ord = self.compare(&other)?;
+        return Ok(Self::from_bool(ord != Some(Ordering::Equal)));
+    }
+
+    fn greater_equal(self, other: Self) -> Result<Self, EvalError> {
+        let ord = self.compare(&other)?;
+        return Ok(Self::from_bool(ord == Some(Ordering::Greater) || ord == Some(Ordering::Equal)));
+    }
+
+    fn greater(self, other: Self) -> Result<Self, EvalError> {
+        let ord = self.compare(&other)?;
+        return Ok(Self::from_bool(ord == Some(Ordering::Greater)));
+    }
+
+    fn not(self) -> Result<Self, EvalError> {
+        return Ok(Self::from_bool(!self.to_bool()?));
+    }
 }
 
-pub type ContextFn<V> = dyn Fn(Vec<V>) -> Result<V, EvalError>;
+pub const DEFAULT_RECURSION_LIMIT: usize = 64;
+
+pub type ContextFn<V> = dyn Fn(&[V]) -> Result<V, EvalError>;
 
 pub trait Context<V: Value> {
     fn set_variable(&mut self, name: &str, value: V);
@@ -27,6 +126,36 @@ pub trait Context<V: Value> {
 
     fn get_variable(&self, name: &str) -> Option<V>;
 
-    fn get_function<'a>(&'a self, name: &str) -> Option<&'a ContextFn<V>>;
+    fn get_function(&self, name: &str) -> Option<Rc<ContextFn<V>>>;
+
+    fn recursion_limit(&self) -> usize {
+        return DEFAULT_RECURSION_LIMIT;
+    }
+
+    // Replaces `Value::pow` for the `^` operator
+    fn power(&self) -> Option<Rc<ContextFn<V>>> {
+        return None;
+    }
+
+    // Contexts that cannot store definitions keep a copy of everything the function refers to
+    fn set_definition(&mut self, name: &str, definition: Rc<FunctionDefinition>)
+    where Self: Sized, V: 'static
+    {
+        let function = function::to_function(name, &definition.params, &definition.body, self);
+        self.set_function(name, function);
+    }
+
+    fn get_definition(&self, _name: &str) -> Option<Rc<FunctionDefinition>> {
+        return None;
+    }
+
+    // The context that function bodies are evaluated in, if this is a local scope
+    fn globals(&self) -> Option<&dyn Context<V>> {
+        return None;
+    }
+
+    fn call_depth(&self) -> usize {
+        return 0;
+    }
 }
 
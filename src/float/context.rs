@@ -0,0 +1,113 @@
+
+use std::collections::HashMap;
+use std::f64::consts;
+use std::rc::Rc;
+
+use crate::Context;
+use crate::ContextFn;
+use crate::FunctionDefinition;
+use crate::DEFAULT_RECURSION_LIMIT;
+use crate::EvalError;
+use crate::EvalErrorKind;
+
+pub struct F64Context {
+    vars: HashMap<String, f64>,
+    funcs: HashMap<String, Rc<ContextFn<f64>>>,
+    defs: HashMap<String, Rc<FunctionDefinition>>,
+    recursion_limit: usize,
+}
+
+fn check_length(args: &[f64], min: usize, max: usize) -> Result<&[f64], EvalError> {
+    if args.len() < min {
+        return Err(EvalError::new(EvalErrorKind::ArgumentMismatch, "Too few arguments to function"));
+    } else if args.len() > max {
+        return Err(EvalError::new(EvalErrorKind::ArgumentMismatch, "Too many arguments to function"));
+    } else {
+        return Ok(args);
+    }
+}
+
+fn sign(x: f64) -> f64 {
+    if x > 0.0 {
+        return 1.0;
+    } else if x < 0.0 {
+        return -1.0;
+    } else {
+        return x;
+    }
+}
+
+impl F64Context {
+    pub fn new() -> F64Context {
+        let mut res = F64Context {
+            vars: HashMap::new(), funcs: HashMap::new(), defs: HashMap::new(), recursion_limit: DEFAULT_RECURSION_LIMIT
+        };
+        res.set_variable("pi", consts::PI);
+        res.set_variable("e", consts::E);
+        res.set_function("floor", Box::new(|v| Ok(check_length(v, 1, 1)?[0].floor())));
+        res.set_function("ceil", Box::new(|v| Ok(check_length(v, 1, 1)?[0].ceil())));
+        res.set_function("round", Box::new(|v| Ok(check_length(v, 1, 1)?[0].round())));
+        res.set_function("trunc", Box::new(|v| Ok(check_length(v, 1, 1)?[0].trunc())));
+        res.set_function("fract", Box::new(|v| Ok(check_length(v, 1, 1)?[0].fract())));
+        res.set_function("abs", Box::new(|v| Ok(check_length(v, 1, 1)?[0].abs())));
+        res.set_function("sign", Box::new(|v| Ok(sign(check_length(v, 1, 1)?[0]))));
+        res.set_function("sqrt", Box::new(|v| Ok(check_length(v, 1, 1)?[0].sqrt())));
+        res.set_function("ln", Box::new(|v| Ok(check_length(v, 1, 1)?[0].ln())));
+        res.set_function("log", Box::new(|v| Ok(check_length(v, 1, 1)?[0].log10())));
+        res.set_function("cbrt", Box::new(|v| Ok(check_length(v, 1, 1)?[0].cbrt())));
+        res.set_function("sin", Box::new(|v| Ok(check_length(v, 1, 1)?[0].sin())));
+        res.set_function("cos", Box::new(|v| Ok(check_length(v, 1, 1)?[0].cos())));
+        res.set_function("tan", Box::new(|v| Ok(check_length(v, 1, 1)?[0].tan())));
+        res.set_function("asin", Box::new(|v| Ok(check_length(v, 1, 1)?[0].asin())));
+        res.set_function("acos", Box::new(|v| Ok(check_length(v, 1, 1)?[0].acos())));
+        res.set_function("atan", Box::new(|v| Ok(check_length(v, 1, 1)?[0].atan())));
+        res.set_function("atan2", Box::new(|v| {
+            let v = check_length(v, 2, 2)?; Ok(v[0].atan2(v[1]))
+        }));
+        res.set_function("sinh", Box::new(|v| Ok(check_length(v, 1, 1)?[0].sinh())));
+        res.set_function("cosh", Box::new(|v| Ok(check_length(v, 1, 1)?[0].cosh())));
+        res.set_function("tanh", Box::new(|v| Ok(check_length(v, 1, 1)?[0].tanh())));
+        res.set_function("asinh", Box::new(|v| Ok(check_length(v, 1, 1)?[0].asinh())));
+        res.set_function("acosh", Box::new(|v| Ok(check_length(v, 1, 1)?[0].acosh())));
+        res.set_function("atanh", Box::new(|v| Ok(check_length(v, 1, 1)?[0].atanh())));
+        res.set_function("min", Box::new(|v| Ok(check_length(v, 1, usize::MAX)?.iter().copied().fold(f64::INFINITY, f64::min))));
+        res.set_function("max", Box::new(|v| Ok(check_length(v, 1, usize::MAX)?.iter().copied().fold(f64::NEG_INFINITY, f64::max))));
+        return res;
+    }
+
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.recursion_limit = limit;
+    }
+}
+
+impl Context<f64> for F64Context {
+    fn set_variable(&mut self, name: &str, value: f64) {
+        self.vars.insert(name.to_owned(), value);
+    }
+
+    fn set_function(&mut self, name: &str, value: Box<ContextFn<f64>>) {
+        self.defs.remove(name);
+        self.funcs.insert(name.to_owned(), Rc::from(value));
+    }
+
+    fn get_variable(&self, name: &str) -> Option<f64> {
+        return self.vars.get(name).copied();
+    }
+
+    fn get_function(&self, name: &str) -> Option<Rc<ContextFn<f64>>> {
+        return self.funcs.get(name).cloned();
+    }
+
+    fn set_definition(&mut self, name: &str, definition: Rc<FunctionDefinition>) {
+        self.funcs.remove(name);
+        self.defs.insert(name.to_owned(), definition);
+    }
+
+    fn get_definition(&self, name: &str) -> Option<Rc<FunctionDefinition>> {
+        return self.defs.get(name).cloned();
+    }
+
+    fn recursion_limit(&self) -> usize {
+        self.recursion_limit
+    }
+}
@@ -0,0 +1,67 @@
+
+use std::str::FromStr;
+
+use crate::{Value, Number, EvalError};
+
+use super::F64Context;
+
+impl Value for f64 {
+    type DefaultContext = F64Context;
+
+    fn default_context() -> Self::DefaultContext {
+        F64Context::new()
+    }
+
+    fn parse_literal(literal: &str) -> Result<Self, EvalError> {
+        return Ok(Number::from_str(literal)?.to_f64());
+    }
+
+    fn add(self, other: Self) -> Result<Self, EvalError> {
+        Ok(self + other)
+    }
+
+    fn sub(self, other: Self) -> Result<Self, EvalError> {
+        Ok(self - other)
+    }
+
+    fn mul(self, other: Self) -> Result<Self, EvalError> {
+        Ok(self * other)
+    }
+
+    fn div(self, other: Self) -> Result<Self, EvalError> {
+        Ok(self / other)
+    }
+
+    fn pow(self, other: Self) -> Result<Self, EvalError> {
+        Ok(self.powf(other))
+    }
+
+    fn neg(self) -> Result<Self, EvalError> {
+        Ok(-self)
+    }
+
+    fn modulo(self, other: Self) -> Result<Self, EvalError> {
+        let r = self % other;
+        if r != 0.0 && (r < 0.0) != (other < 0.0) {
+            return Ok(r + other);
+        } else {
+            return Ok(r);
+        }
+    }
+
+    fn floor_div(self, other: Self) -> Result<Self, EvalError> {
+        Ok((self / other).floor())
+    }
+
+    fn from_bool(value: bool) -> Self {
+        if value {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn to_bool(&self) -> Result<bool, EvalError> {
+        return Ok(*self != 0.0);
+    }
+}
@@ -0,0 +1,5 @@
+
+mod float;
+mod context;
+
+pub use context::F64Context;
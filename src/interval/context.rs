@@ -0,0 +1,143 @@
+
+use std::collections::HashMap;
+use std::f64::consts;
+use std::rc::Rc;
+
+use crate::Context;
+use crate::ContextFn;
+use crate::FunctionDefinition;
+use crate::DEFAULT_RECURSION_LIMIT;
+use crate::EvalError;
+use crate::EvalErrorKind;
+use crate::Number;
+
+use super::Interval;
+use super::interval::{smallest, largest};
+
+pub struct IntervalContext {
+    vars: HashMap<String, Interval>,
+    funcs: HashMap<String, Rc<ContextFn<Interval>>>,
+    defs: HashMap<String, Rc<FunctionDefinition>>,
+    recursion_limit: usize,
+}
+
+fn check_length(args: &[Interval], min: usize, max: usize) -> Result<&[Interval], EvalError> {
+    if args.len() < min {
+        return Err(EvalError::new(EvalErrorKind::ArgumentMismatch, "Too few arguments to function"));
+    } else if args.len() > max {
+        return Err(EvalError::new(EvalErrorKind::ArgumentMismatch, "Too many arguments to function"));
+    } else {
+        return Ok(args);
+    }
+}
+
+fn min(args: &[Interval]) -> Result<Interval, EvalError> {
+    let args = check_length(args, 1, usize::MAX)?;
+    let lower = args.iter().map(|a| a.lower().clone()).collect::<Vec<_>>();
+    let upper = args.iter().map(|a| a.upper().clone()).collect::<Vec<_>>();
+    return Interval::new(smallest(&lower), smallest(&upper));
+}
+
+fn max(args: &[Interval]) -> Result<Interval, EvalError> {
+    let args = check_length(args, 1, usize::MAX)?;
+    let lower = args.iter().map(|a| a.lower().clone()).collect::<Vec<_>>();
+    let upper = args.iter().map(|a| a.upper().clone()).collect::<Vec<_>>();
+    return Interval::new(largest(&lower), largest(&upper));
+}
+
+fn hull(args: &[Interval]) -> Result<Interval, EvalError> {
+    let args = check_length(args, 2, 2)?;
+    if args[0].lower() > args[1].upper() {
+        return Err(EvalError::new(
+            EvalErrorKind::ArgumentMismatch, "The lower bound of an interval must not exceed its upper bound"
+        ));
+    }
+    return Interval::new(
+        smallest(&[args[0].lower().clone(), args[1].lower().clone()]),
+        largest(&[args[0].upper().clone(), args[1].upper().clone()]),
+    );
+}
+
+fn constant(value: f64) -> Interval {
+    Interval::new(Number::Float(value.next_down()), Number::Float(value.next_up())).unwrap()
+}
+
+impl IntervalContext {
+    pub fn new() -> IntervalContext {
+        let mut res = IntervalContext {
+            vars: HashMap::new(), funcs: HashMap::new(), defs: HashMap::new(), recursion_limit: DEFAULT_RECURSION_LIMIT
+        };
+        res.set_variable("pi", constant(consts::PI));
+        res.set_variable("e", constant(consts::E));
+        res.set_function("floor", Box::new(|v| check_length(v, 1, 1)?[0].floor()));
+        res.set_function("ceil", Box::new(|v| check_length(v, 1, 1)?[0].ceil()));
+        res.set_function("round", Box::new(|v| check_length(v, 1, 1)?[0].round()));
+        res.set_function("trunc", Box::new(|v| check_length(v, 1, 1)?[0].trunc()));
+        res.set_function("fract", Box::new(|v| check_length(v, 1, 1)?[0].fract()));
+        res.set_function("abs", Box::new(|v| check_length(v, 1, 1)?[0].abs()));
+        res.set_function("sign", Box::new(|v| check_length(v, 1, 1)?[0].sign()));
+        res.set_function("sqrt", Box::new(|v| check_length(v, 1, 1)?[0].sqrt()));
+        res.set_function("ln", Box::new(|v| check_length(v, 1, 1)?[0].ln()));
+        res.set_function("log", Box::new(|v| check_length(v, 1, 1)?[0].log()));
+        res.set_function("cbrt", Box::new(|v| check_length(v, 1, 1)?[0].cbrt()));
+        res.set_function("sin", Box::new(|v| check_length(v, 1, 1)?[0].sin()));
+        res.set_function("cos", Box::new(|v| check_length(v, 1, 1)?[0].cos()));
+        res.set_function("tan", Box::new(|v| check_length(v, 1, 1)?[0].tan()));
+        res.set_function("asin", Box::new(|v| check_length(v, 1, 1)?[0].asin()));
+        res.set_function("acos", Box::new(|v| check_length(v, 1, 1)?[0].acos()));
+        res.set_function("atan", Box::new(|v| check_length(v, 1, 1)?[0].atan()));
+        res.set_function("atan2", Box::new(|v| {
+            let v = check_length(v, 2, 2)?; v[0].atan2(&v[1])
+        }));
+        res.set_function("sinh", Box::new(|v| check_length(v, 1, 1)?[0].sinh()));
+        res.set_function("cosh", Box::new(|v| check_length(v, 1, 1)?[0].cosh()));
+        res.set_function("tanh", Box::new(|v| check_length(v, 1, 1)?[0].tanh()));
+        res.set_function("asinh", Box::new(|v| check_length(v, 1, 1)?[0].asinh()));
+        res.set_function("acosh", Box::new(|v| check_length(v, 1, 1)?[0].acosh()));
+        res.set_function("atanh", Box::new(|v| check_length(v, 1, 1)?[0].atanh()));
+        res.set_function("min", Box::new(min));
+        res.set_function("max", Box::new(max));
+        res.set_function("interval", Box::new(hull));
+        res.set_function("lower", Box::new(|v| Ok(Interval::point(check_length(v, 1, 1)?[0].lower().clone()))));
+        res.set_function("upper", Box::new(|v| Ok(Interval::point(check_length(v, 1, 1)?[0].upper().clone()))));
+        res.set_function("mid", Box::new(|v| Ok(Interval::point(check_length(v, 1, 1)?[0].mid()?))));
+        res.set_function("width", Box::new(|v| Ok(Interval::point(check_length(v, 1, 1)?[0].width()?))));
+        return res;
+    }
+
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.recursion_limit = limit;
+    }
+}
+
+impl Context<Interval> for IntervalContext {
+    fn set_variable(&mut self, name: &str, value: Interval) {
+        self.vars.insert(name.to_owned(), value);
+    }
+
+    fn set_function(&mut self, name: &str, value: Box<ContextFn<Interval>>) {
+        self.defs.remove(name);
+        self.funcs.insert(name.to_owned(), Rc::from(value));
+    }
+
+    fn get_variable(&self, name: &str) -> Option<Interval> {
+        return self.vars.get(name).cloned();
+    }
+
+    fn get_function(&self, name: &str) -> Option<Rc<ContextFn<Interval>>> {
+        return self.funcs.get(name).cloned();
+    }
+
+    fn set_definition(&mut self, name: &str, definition: Rc<FunctionDefinition>) {
+        self.funcs.remove(name);
+        self.defs.insert(name.to_owned(), definition);
+    }
+
+    fn get_definition(&self, name: &str) -> Option<Rc<FunctionDefinition>> {
+        return self.defs.get(name).cloned();
+    }
+
+    fn recursion_limit(&self) -> usize {
+        self.recursion_limit
+    }
+}
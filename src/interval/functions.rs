@@ -0,0 +1,181 @@
+
+use std::f64::consts::{PI, FRAC_PI_2};
+
+use crate::{Number, EvalError, EvalErrorKind};
+
+use super::Interval;
+use super::interval::{smallest, largest};
+
+fn contains_phase(lo: f64, hi: f64, phase: f64, period: f64) -> bool {
+    let eps = 1e-9 * lo.abs().max(hi.abs()).max(1.0);
+    let k = ((lo - eps - phase) / period).ceil();
+    return phase + k * period <= hi + eps;
+}
+
+impl Interval {
+    fn increasing(&self, f: fn(&Number) -> Number) -> Result<Interval, EvalError> {
+        Interval::rounded(f(self.lower()), f(self.upper()))
+    }
+
+    fn decreasing(&self, f: fn(&Number) -> Number) -> Result<Interval, EvalError> {
+        Interval::rounded(f(self.upper()), f(self.lower()))
+    }
+
+    fn clamped(self, lo: Number, hi: Number) -> Result<Interval, EvalError> {
+        Interval::new(largest(&[self.lower().clone(), lo]), smallest(&[self.upper().clone(), hi]))
+    }
+
+    fn domain(&self, name: &str, valid: bool) -> Result<(), EvalError> {
+        if valid {
+            return Ok(());
+        } else {
+            return Err(EvalError::new(
+                EvalErrorKind::MathError, &format!("Function {} is not defined on all of {}", name, self.to_string())
+            ));
+        }
+    }
+
+    fn periodic(&self, f: fn(&Number) -> Number, max_phase: f64, min_phase: f64) -> Result<Interval, EvalError> {
+        let (lo, hi) = (self.lower().to_f64(), self.upper().to_f64());
+        let (one, neg_one) = (Number::one(), Number::neg_one());
+        if hi - lo >= 2.0 * PI {
+            return Interval::new(neg_one, one);
+        }
+        let values = Interval::enclosing(&[f(self.lower()), f(self.upper())])?;
+        let upper = if contains_phase(lo, hi, max_phase, 2.0 * PI) { one.clone() } else { values.upper().clone() };
+        let lower = if contains_phase(lo, hi, min_phase, 2.0 * PI) { neg_one.clone() } else { values.lower().clone() };
+        return Interval::new(lower, upper)?.clamped(neg_one, one);
+    }
+
+    pub fn floor(&self) -> Result<Interval, EvalError> {
+        self.increasing(Number::floor)
+    }
+
+    pub fn ceil(&self) -> Result<Interval, EvalError> {
+        self.increasing(Number::ceil)
+    }
+
+    pub fn round(&self) -> Result<Interval, EvalError> {
+        self.increasing(Number::round)
+    }
+
+    pub fn trunc(&self) -> Result<Interval, EvalError> {
+        self.increasing(Number::trunc)
+    }
+
+    pub fn fract(&self) -> Result<Interval, EvalError> {
+        if self.lower().floor() == self.upper().floor() {
+            return self.increasing(Number::fract);
+        } else {
+            return Interval::new(Number::zero(), Number::one());
+        }
+    }
+
+    pub fn abs(&self) -> Result<Interval, EvalError> {
+        if !self.lower().is_negative() {
+            return Ok(self.clone());
+        } else if !self.upper().is_positive() {
+            return self.decreasing(Number::abs);
+        } else {
+            return Interval::new(Number::zero(), largest(&[self.lower().abs(), self.upper().abs()]));
+        }
+    }
+
+    pub fn sign(&self) -> Result<Interval, EvalError> {
+        self.increasing(Number::sign)
+    }
+
+    pub fn sqrt(&self) -> Result<Interval, EvalError> {
+        self.domain("sqrt", !self.lower().is_negative())?;
+        return self.increasing(Number::sqrt);
+    }
+
+    pub fn cbrt(&self) -> Result<Interval, EvalError> {
+        self.increasing(Number::cbrt)
+    }
+
+    pub fn ln(&self) -> Result<Interval, EvalError> {
+        self.domain("ln", self.lower().is_positive())?;
+        return self.increasing(Number::ln);
+    }
+
+    pub fn log(&self) -> Result<Interval, EvalError> {
+        self.domain("log", self.lower().is_positive())?;
+        return self.increasing(Number::log);
+    }
+
+    pub fn sin(&self) -> Result<Interval, EvalError> {
+        self.periodic(Number::sin, FRAC_PI_2, -FRAC_PI_2)
+    }
+
+    pub fn cos(&self) -> Result<Interval, EvalError> {
+        self.periodic(Number::cos, 0.0, PI)
+    }
+
+    pub fn tan(&self) -> Result<Interval, EvalError> {
+        let (lo, hi) = (self.lower().to_f64(), self.upper().to_f64());
+        self.domain("tan", hi - lo < PI && !contains_phase(lo, hi, FRAC_PI_2, PI))?;
+        return self.increasing(Number::tan);
+    }
+
+    pub fn asin(&self) -> Result<Interval, EvalError> {
+        self.domain("asin", self.lower() >= &Number::neg_one() && self.upper() <= &Number::one())?;
+        return self.increasing(Number::asin);
+    }
+
+    pub fn acos(&self) -> Result<Interval, EvalError> {
+        self.domain("acos", self.lower() >= &Number::neg_one() && self.upper() <= &Number::one())?;
+        return self.decreasing(Number::acos);
+    }
+
+    pub fn atan(&self) -> Result<Interval, EvalError> {
+        self.increasing(Number::atan)
+    }
+
+    pub fn atan2(&self, x: &Interval) -> Result<Interval, EvalError> {
+        let zero = Number::zero();
+        if self.contains(&zero) && x.contains(&zero) {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Function atan2 is not defined at the origin"));
+        } else if self.lower().is_negative() && !self.upper().is_negative() && x.lower().is_negative() {
+            return Interval::rounded(Number::Float(-PI), Number::Float(PI));
+        } else {
+            let corner = |y: &Number, x: &Number| Number::Float(y.to_f64().atan2(x.to_f64()));
+            return Interval::enclosing(&[
+                corner(self.lower(), x.lower()), corner(self.lower(), x.upper()),
+                corner(self.upper(), x.lower()), corner(self.upper(), x.upper()),
+            ]);
+        }
+    }
+
+    pub fn sinh(&self) -> Result<Interval, EvalError> {
+        self.increasing(Number::sinh)
+    }
+
+    pub fn cosh(&self) -> Result<Interval, EvalError> {
+        if !self.lower().is_negative() {
+            return self.increasing(Number::cosh);
+        } else if !self.upper().is_positive() {
+            return self.decreasing(Number::cosh);
+        } else {
+            return Interval::rounded(Number::one(), largest(&[self.lower().cosh(), self.upper().cosh()]));
+        }
+    }
+
+    pub fn tanh(&self) -> Result<Interval, EvalError> {
+        self.increasing(Number::tanh)?.clamped(Number::neg_one(), Number::one())
+    }
+
+    pub fn asinh(&self) -> Result<Interval, EvalError> {
+        self.increasing(Number::asinh)
+    }
+
+    pub fn acosh(&self) -> Result<Interval, EvalError> {
+        self.domain("acosh", self.lower() >= &Number::one())?;
+        return self.increasing(Number::acosh);
+    }
+
+    pub fn atanh(&self) -> Result<Interval, EvalError> {
+        self.domain("atanh", self.lower() > &Number::neg_one() && self.upper() < &Number::one())?;
+        return self.increasing(Number::atanh);
+    }
+}
@@ -0,0 +1,290 @@
+
+use std::ops::*;
+use std::str::FromStr;
+use std::cmp::Ordering;
+use num::traits::Pow;
+
+use crate::{Number, Value, EvalError, EvalErrorKind};
+
+use super::IntervalContext;
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct Interval {
+    lo: Number,
+    hi: Number,
+}
+
+pub(super) fn down(n: Number) -> Number {
+    match n {
+        Number::Float(f) => Number::Float(f.next_down()),
+        Number::Symbolic(s) => Number::Float(s.to_f64().next_down()),
+        r => r,
+    }
+}
+
+pub(super) fn up(n: Number) -> Number {
+    match n {
+        Number::Float(f) => Number::Float(f.next_up()),
+        Number::Symbolic(s) => Number::Float(s.to_f64().next_up()),
+        r => r,
+    }
+}
+
+pub(super) fn smallest(values: &[Number]) -> Number {
+    let mut m = values[0].clone();
+    for v in &values[1..] {
+        if v < &m {
+            m = v.clone();
+        }
+    }
+    return m;
+}
+
+pub(super) fn largest(values: &[Number]) -> Number {
+    let mut m = values[0].clone();
+    for v in &values[1..] {
+        if v > &m {
+            m = v.clone();
+        }
+    }
+    return m;
+}
+
+impl Interval {
+    pub fn new(lo: Number, hi: Number) -> Result<Interval, EvalError> {
+        if lo.to_f64().is_nan() || hi.to_f64().is_nan() {
+            return Err(EvalError::new(EvalErrorKind::MathError, "NaN"));
+        } else if lo > hi {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Lower bound of interval exceeds upper bound"));
+        } else {
+            return Ok(Interval { lo, hi });
+        }
+    }
+
+    pub fn point(value: Number) -> Interval {
+        Interval { lo: value.clone(), hi: value }
+    }
+
+    pub(super) fn rounded(lo: Number, hi: Number) -> Result<Interval, EvalError> {
+        Interval::new(down(lo), up(hi))
+    }
+
+    pub(super) fn enclosing(values: &[Number]) -> Result<Interval, EvalError> {
+        Interval::rounded(smallest(values), largest(values))
+    }
+
+    pub fn lower(&self) -> &Number {
+        &self.lo
+    }
+
+    pub fn upper(&self) -> &Number {
+        &self.hi
+    }
+
+    pub fn is_point(&self) -> bool {
+        self.lo == self.hi
+    }
+
+    pub fn contains(&self, value: &Number) -> bool {
+        &self.lo <= value && value <= &self.hi
+    }
+
+    pub fn width(&self) -> Result<Number, EvalError> {
+        return Ok(up((self.hi.clone() - self.lo.clone())?));
+    }
+
+    pub fn mid(&self) -> Result<Number, EvalError> {
+        return (self.lo.clone() + self.hi.clone())? / Number::from_i64(2);
+    }
+
+    fn powi(&self, exp: i32) -> Result<Interval, EvalError> {
+        let n = Number::from_i64(exp.unsigned_abs() as i64);
+        let (lo, hi) = (Pow::pow(self.lo.clone(), n.clone())?, Pow::pow(self.hi.clone(), n)?);
+        let res = if exp % 2 == 0 && self.lo.is_negative() && self.hi.is_positive() {
+            Interval::rounded(Number::zero(), largest(&[lo, hi]))?
+        } else {
+            Interval::enclosing(&[lo, hi])?
+        };
+        if exp < 0 {
+            return Interval::point(Number::one()) / res;
+        } else {
+            return Ok(res);
+        }
+    }
+}
+
+impl ToString for Interval {
+    fn to_string(&self) -> String {
+        if self.is_point() {
+            self.lo.to_string()
+        } else {
+            format!("[{}, {}]", self.lo.to_string(), self.hi.to_string())
+        }
+    }
+}
+
+impl FromStr for Interval {
+    type Err = EvalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Interval::point(Number::from_str(s)?))
+    }
+}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.hi < other.lo {
+            Some(Ordering::Less)
+        } else if self.lo > other.hi {
+            Some(Ordering::Greater)
+        } else if self.is_point() && self == other {
+            Some(Ordering::Equal)
+        } else {
+            None
+        }
+    }
+}
+
+impl Neg for Interval {
+    type Output = Result<Interval, EvalError>;
+
+    fn neg(self) -> Self::Output {
+        Interval::new((-self.hi)?, (-self.lo)?)
+    }
+}
+
+impl Add for Interval {
+    type Output = Result<Interval, EvalError>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Interval::rounded((self.lo + rhs.lo)?, (self.hi + rhs.hi)?)
+    }
+}
+
+impl Sub for Interval {
+    type Output = Result<Interval, EvalError>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Interval::rounded((self.lo - rhs.hi)?, (self.hi - rhs.lo)?)
+    }
+}
+
+impl Mul for Interval {
+    type Output = Result<Interval, EvalError>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Interval::enclosing(&[
+            (self.lo.clone() * rhs.lo.clone())?, (self.lo * rhs.hi.clone())?,
+            (self.hi.clone() * rhs.lo)?, (self.hi * rhs.hi)?,
+        ])
+    }
+}
+
+impl Div for Interval {
+    type Output = Result<Interval, EvalError>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.contains(&Number::zero()) {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Division by an interval containing zero"));
+        } else {
+            return Interval::enclosing(&[
+                (self.lo.clone() / rhs.lo.clone())?, (self.lo / rhs.hi.clone())?,
+                (self.hi.clone() / rhs.lo)?, (self.hi / rhs.hi)?,
+            ]);
+        }
+    }
+}
+
+impl Pow<Interval> for Interval {
+    type Output = Result<Interval, EvalError>;
+
+    fn pow(self, rhs: Interval) -> Self::Output {
+        if rhs.is_point() && rhs.lo.is_integer() {
+            if let Some(exp) = rhs.lo.to_rational().and_then(|r| num::ToPrimitive::to_i32(&r)) {
+                if exp == 0 && self.contains(&Number::zero()) {
+                    return Err(EvalError::new(EvalErrorKind::MathError, "Zero to the power of zero"));
+                }
+                return self.powi(exp);
+            }
+        }
+        if self.lo.is_negative() {
+            return Err(EvalError::new(
+                EvalErrorKind::MathError, "Cannot take a non-integer power of an interval containing negative values"
+            ));
+        } else if self.lo.is_zero() && !rhs.lo.is_positive() {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Division by zero"));
+        }
+        return Interval::enclosing(&[
+            Pow::pow(self.lo.clone(), rhs.lo.clone())?, Pow::pow(self.lo, rhs.hi.clone())?,
+            Pow::pow(self.hi.clone(), rhs.lo)?, Pow::pow(self.hi, rhs.hi)?,
+        ]);
+    }
+}
+
+impl Value for Interval {
+    type DefaultContext = IntervalContext;
+
+    fn default_context() -> Self::DefaultContext {
+        IntervalContext::new()
+    }
+
+    fn parse_literal(literal: &str) -> Result<Self, EvalError> {
+        Interval::from_str(literal)
+    }
+
+    fn add(self, other: Self) -> Result<Self, EvalError> {
+        self + other
+    }
+
+    fn sub(self, other: Self) -> Result<Self, EvalError> {
+        self - other
+    }
+
+    fn mul(self, other: Self) -> Result<Self, EvalError> {
+        self * other
+    }
+
+    fn div(self, other: Self) -> Result<Self, EvalError> {
+        self / other
+    }
+
+    fn pow(self, other: Self) -> Result<Self, EvalError> {
+        Pow::pow(self, other)
+    }
+
+    fn neg(self) -> Result<Self, EvalError> {
+        -self
+    }
+
+    fn plus_minus(self, error: Self) -> Result<Self, EvalError> {
+        if error.lo.is_negative() {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Tolerances must not be negative"));
+        } else {
+            return Interval::rounded((self.lo - error.hi.clone())?, (self.hi + error.hi)?);
+        }
+    }
+
+    fn from_bool(value: bool) -> Self {
+        Interval::point(Number::from_bool(value))
+    }
+
+    fn to_bool(&self) -> Result<bool, EvalError> {
+        if !self.contains(&Number::zero()) {
+            return Ok(true);
+        } else if self.is_point() {
+            return Ok(false);
+        } else {
+            return Err(EvalError::new(
+                EvalErrorKind::MathError, "Truth value of an interval containing zero is ambiguous"
+            ));
+        }
+    }
+
+    fn compare(&self, other: &Self) -> Result<Option<Ordering>, EvalError> {
+        if let Some(ord) = self.partial_cmp(other) {
+            return Ok(Some(ord));
+        } else {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Comparison of overlapping intervals is ambiguous"));
+        }
+    }
+}
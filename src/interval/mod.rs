@@ -0,0 +1,7 @@
+
+mod interval;
+mod context;
+mod functions;
+
+pub use interval::Interval;
+pub use context::IntervalContext;
@@ -1,9 +1,17 @@
 
 mod expr;
 mod number;
+mod float;
+mod complex;
+mod interval;
+mod uncertain;
 mod units;
 
 pub use expr::*;
 pub use number::*;
+pub use float::*;
+pub use complex::*;
+pub use interval::*;
+pub use uncertain::*;
 pub use units::*;
 
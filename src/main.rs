@@ -1,39 +1,28 @@
-
 use std::io::{stdin, stdout, stderr};
 use std::io::prelude::*;
 
 use matheval::{Expr, Value, Quantity, QuantityContext};
 
-fn repl_step(context: &QuantityContext) -> Result<(), String> {
+fn repl_step(context: &mut QuantityContext) -> Result<(), String> {
     let stdin = stdin();
     let stdout = stdout();
     stdout.lock().write(">>> ".as_bytes()).map_err(|err| err.to_string())?;
     stdout.lock().flush().map_err(|err| err.to_string())?;
     let mut input = String::new();
     stdin.lock().read_line(&mut input).map_err(|err| err.to_string())?;
-    if let Some(idx) = input.find(" to ") {
-        let expr = Expr::parse(&input[..idx]).map_err(|err| err.to_string())?;
-        let res = expr.eval_in(context).map_err(|err| err.to_string())?;
-        let dest = input[idx + 4..].trim();
-        if let Some(result) = res.convert_to_in(dest, context) {
-            stdout.lock().write_fmt(format_args!(" = {} {}\n", result.to_string(), dest)).map_err(|err| err.to_string())?;
-            if result.is_rational() && !result.is_integer() {
-                stdout.lock()
-                    .write_fmt(format_args!(" = {} {}\n", result.to_f64(), dest))
-                    .map_err(|err| err.to_string())?;
-            }
+    let program = Expr::parse_program(&input).map_err(|err| err.render(&input))?;
+    if let Some(res) = Expr::execute_program_in(&program, context).map_err(|err| err.to_string())? {
+        let (coefficient, unit) = if let Some(Expr::Convert(_, dest)) = program.last() {
+            (res.coefficient().clone(), dest.to_string())
         } else {
-            return Err(format!("Cannot convert {} to {}", res.unit().to_string(), dest));
-        }
-    } else {
-        let expr = Expr::parse(&input).map_err(|err| err.to_string())?;
-        let res = expr.eval_in(context).map_err(|err| err.to_string())?;
+            res.best_unit()
+        };
         stdout.lock()
-            .write_fmt(format_args!(" = {} {}\n", res.coefficient().to_string(), res.unit().to_string()))
+            .write_fmt(format_args!(" = {} {}\n", coefficient.to_string(), unit))
             .map_err(|err| err.to_string())?;
-        if res.coefficient().is_rational() && !res.coefficient().is_integer() {
+        if coefficient.is_rational() && !coefficient.is_integer() {
             stdout.lock()
-                .write_fmt(format_args!(" = {} {}\n", res.coefficient().to_f64(), res.unit().to_string()))
+                .write_fmt(format_args!(" = {} {}\n", coefficient.to_f64(), unit))
                 .map_err(|err| err.to_string())?;
         }
     }
@@ -42,11 +31,10 @@ fn repl_step(context: &QuantityContext) -> Result<(), String> {
 
 fn main() {
     let stderr = stderr();
-    let context = Quantity::default_context();
+    let mut context = Quantity::default_context();
     loop {
-        if let Err(s) = repl_step(&context) {
+        if let Err(s) = repl_step(&mut context) {
             let _ = stderr.lock().write_fmt(format_args!("Error: {}\n", s));
         }
     }
 }
-
@@ -0,0 +1,310 @@
+
+use std::cmp::Ordering;
+use std::f64::consts::LOG10_2;
+use num::*;
+use num::traits::Pow;
+
+// An exact binary fraction mantissa * 2^exponent, normalized to an odd mantissa
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Dyadic {
+    mantissa: BigInt,
+    exponent: i64,
+}
+
+fn round_bits(mantissa: BigInt, exponent: i64, precision: u64, sticky: bool) -> Dyadic {
+    let negative = mantissa.is_negative();
+    let abs = mantissa.abs();
+    let bits = abs.bits();
+    if bits <= precision {
+        return Dyadic::new(mantissa, exponent);
+    }
+    let shift = bits - precision;
+    let mut rounded: BigInt = &abs >> shift;
+    let rest = abs - (&rounded << shift);
+    let half = BigInt::one() << (shift - 1);
+    match rest.cmp(&half) {
+        Ordering::Greater => rounded += 1,
+        Ordering::Equal if sticky || rounded.is_odd() => rounded += 1,
+        _ => {},
+    }
+    if negative {
+        rounded = -rounded;
+    }
+    return Dyadic::new(rounded, exponent + shift as i64);
+}
+
+impl Dyadic {
+    pub fn new(mantissa: BigInt, exponent: i64) -> Dyadic {
+        match mantissa.trailing_zeros() {
+            None => Dyadic { mantissa, exponent: 0 },
+            Some(zeros) => Dyadic { mantissa: mantissa >> zeros, exponent: exponent + zeros as i64 },
+        }
+    }
+
+    pub fn zero() -> Dyadic {
+        Dyadic::new(BigInt::zero(), 0)
+    }
+
+    pub fn one() -> Dyadic {
+        Dyadic::new(BigInt::one(), 0)
+    }
+
+    pub fn power_of_two(exponent: i64) -> Dyadic {
+        Dyadic::new(BigInt::one(), exponent)
+    }
+
+    pub fn from_fixed(value: BigInt, scale: u64) -> Dyadic {
+        Dyadic::new(value, -(scale as i64))
+    }
+
+    pub fn from_rational(value: &BigRational, precision: u64) -> Dyadic {
+        if value.is_zero() {
+            return Dyadic::zero();
+        }
+        let shift = precision as i64 + 2 + value.denom().bits() as i64 - value.numer().bits() as i64;
+        let (numer, denom) = if shift >= 0 {
+            (value.numer() << shift as u64, value.denom().clone())
+        } else {
+            (value.numer().clone(), value.denom() << (-shift) as u64)
+        };
+        let (quotient, rest) = numer.div_rem(&denom);
+        return round_bits(quotient, -shift, precision, !rest.is_zero());
+    }
+
+    pub fn to_rational(&self) -> BigRational {
+        if self.exponent >= 0 {
+            BigRational::from_integer(&self.mantissa << self.exponent as u64)
+        } else {
+            BigRational::new(self.mantissa.clone(), BigInt::one() << (-self.exponent) as u64)
+        }
+    }
+
+    // The value scaled by 2^scale, truncated towards zero
+    pub fn to_fixed(&self, scale: u64) -> BigInt {
+        let shift = self.exponent + scale as i64;
+        if shift >= 0 {
+            &self.mantissa << shift as u64
+        } else {
+            shr(&self.mantissa, (-shift) as u64)
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa.is_negative()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.exponent >= 0 || self.is_zero()
+    }
+
+    pub fn bits(&self) -> u64 {
+        self.mantissa.bits()
+    }
+
+    // The smallest m with |self| < 2^m
+    pub fn magnitude(&self) -> i64 {
+        if self.is_zero() {
+            i64::MIN / 4
+        } else {
+            self.mantissa.bits() as i64 + self.exponent
+        }
+    }
+
+    pub fn round(&self, precision: u64) -> Dyadic {
+        round_bits(self.mantissa.clone(), self.exponent, precision, false)
+    }
+
+    pub fn neg(&self) -> Dyadic {
+        Dyadic { mantissa: -&self.mantissa, exponent: self.exponent }
+    }
+
+    pub fn abs(&self) -> Dyadic {
+        Dyadic { mantissa: self.mantissa.abs(), exponent: self.exponent }
+    }
+
+    pub fn scale(&self, exponent: i64) -> Dyadic {
+        Dyadic { mantissa: self.mantissa.clone(), exponent: self.exponent + exponent }
+    }
+
+    pub fn add(&self, other: &Dyadic) -> Dyadic {
+        let exponent = self.exponent.min(other.exponent);
+        let a = &self.mantissa << (self.exponent - exponent) as u64;
+        let b = &other.mantissa << (other.exponent - exponent) as u64;
+        return Dyadic::new(a + b, exponent);
+    }
+
+    pub fn sub(&self, other: &Dyadic) -> Dyadic {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &Dyadic) -> Dyadic {
+        Dyadic::new(&self.mantissa * &other.mantissa, self.exponent + other.exponent)
+    }
+
+    pub fn powi(&self, exponent: u32) -> Dyadic {
+        Dyadic::new(Pow::pow(&self.mantissa, exponent), self.exponent * exponent as i64)
+    }
+
+    pub fn div(&self, other: &Dyadic, precision: u64) -> Dyadic {
+        Dyadic::from_rational(&(self.to_rational() / other.to_rational()), precision)
+    }
+
+    fn root(&self, degree: u32, precision: u64) -> Dyadic {
+        let negative = self.is_negative();
+        let mantissa = self.mantissa.abs();
+        let wanted = (degree as u64 * (precision + 2)) as i64 - mantissa.bits() as i64;
+        let mut shift = wanted.max(0);
+        shift += (self.exponent - shift).rem_euclid(degree as i64);
+        let scaled: BigInt = mantissa << shift as u64;
+        let root = scaled.nth_root(degree);
+        let exact = Pow::pow(&root, degree) == scaled;
+        let root = if negative { -root } else { root };
+        return round_bits(root, (self.exponent - shift) / degree as i64, precision, !exact);
+    }
+
+    pub fn sqrt(&self, precision: u64) -> Dyadic {
+        self.root(2, precision)
+    }
+
+    pub fn cbrt(&self, precision: u64) -> Dyadic {
+        self.root(3, precision)
+    }
+}
+
+// Shift right, truncating towards zero
+pub(super) fn shr(value: &BigInt, shift: u64) -> BigInt {
+    if value.is_negative() {
+        -((-value) >> shift)
+    } else {
+        value >> shift
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BigFloat {
+    value: Dyadic,
+    precision: u32,
+}
+
+impl BigFloat {
+    pub(super) fn from_dyadic(value: Dyadic, precision: u32) -> BigFloat {
+        BigFloat { value: value.round(precision as u64), precision }
+    }
+
+    pub(super) fn dyadic(&self) -> &Dyadic {
+        &self.value
+    }
+
+    pub fn from_rational(value: &BigRational, precision: u32) -> BigFloat {
+        let precision = precision.max(1);
+        BigFloat { value: Dyadic::from_rational(value, precision as u64), precision }
+    }
+
+    pub fn from_f64(value: f64, precision: u32) -> Option<BigFloat> {
+        BigRational::from_f64(value).map(|r| BigFloat::from_rational(&r, precision))
+    }
+
+    pub fn precision(&self) -> u32 {
+        self.precision
+    }
+
+    pub fn with_precision(&self, precision: u32) -> BigFloat {
+        BigFloat::from_dyadic(self.value.clone(), precision.max(1))
+    }
+
+    pub fn to_rational(&self) -> BigRational {
+        self.value.to_rational()
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.to_rational().to_f64().unwrap_or(f64::NAN)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.value.is_negative()
+    }
+
+    pub fn is_positive(&self) -> bool {
+        !self.is_zero() && !self.is_negative()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.value.is_integer()
+    }
+
+    pub fn neg(&self) -> BigFloat {
+        BigFloat { value: self.value.neg(), precision: self.precision }
+    }
+
+    pub fn abs(&self) -> BigFloat {
+        BigFloat { value: self.value.abs(), precision: self.precision }
+    }
+
+    pub fn sqrt(&self) -> Option<BigFloat> {
+        if self.is_negative() {
+            return None;
+        } else {
+            return Some(BigFloat { value: self.value.sqrt(self.precision as u64), precision: self.precision });
+        }
+    }
+
+    pub fn cbrt(&self) -> Option<BigFloat> {
+        Some(BigFloat { value: self.value.cbrt(self.precision as u64), precision: self.precision })
+    }
+}
+
+impl PartialEq for BigFloat {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl ToString for BigFloat {
+    fn to_string(&self) -> String {
+        if self.is_zero() {
+            return "0".to_owned();
+        }
+        let digits = (self.precision as f64 * LOG10_2).floor() as i32 + 1;
+        let value = self.to_rational().abs();
+        let ten = BigRational::from_integer(BigInt::from(10));
+        let lower = BigInt::from(10).pow(digits as u32 - 1);
+        let upper = &lower * 10;
+        let mut exp10 = ((self.value.magnitude() - 1) as f64 * LOG10_2).floor() as i32;
+        let mut scaled;
+        loop {
+            scaled = (&value * Pow::pow(&ten, digits - 1 - exp10)).round().to_integer();
+            if scaled >= upper {
+                exp10 += 1;
+            } else if scaled < lower {
+                exp10 -= 1;
+            } else {
+                break;
+            }
+        }
+        let sign = if self.is_negative() { "-" } else { "" };
+        let mantissa = scaled.to_string();
+        let mantissa = mantissa.trim_end_matches('0');
+        if exp10 <= -10 || exp10 >= 30 {
+            if mantissa.len() == 1 {
+                return format!("{}{}e{}", sign, mantissa, exp10);
+            } else {
+                return format!("{}{}.{}e{}", sign, &mantissa[..1], &mantissa[1..], exp10);
+            }
+        } else if exp10 < 0 {
+            return format!("{}0.{}{}", sign, "0".repeat((-exp10 - 1) as usize), mantissa);
+        } else if mantissa.len() <= exp10 as usize + 1 {
+            return format!("{}{}{}", sign, mantissa, "0".repeat(exp10 as usize + 1 - mantissa.len()));
+        } else {
+            let point = exp10 as usize + 1;
+            return format!("{}{}.{}", sign, &mantissa[..point], &mantissa[point..]);
+        }
+    }
+}
@@ -4,6 +4,7 @@ use std::ops::Neg;
 use num::*;
 
 use super::Number;
+use super::Symbolic;
 
 impl Number {
     pub fn zero() -> Number {
@@ -19,11 +20,11 @@ impl Number {
     }
 
     pub fn pi() -> Number {
-        Number::Float(3.1415926535897932384626433832795)
+        Number::Symbolic(Box::new(Symbolic::Pi(BigRational::one())))
     }
 
     pub fn e() -> Number {
-        Number::Float(2.7182818284590452353602874713527)
+        Number::Symbolic(Box::new(Symbolic::Exp(BigRational::one(), BigRational::one())))
     }
 }
 
@@ -1,30 +1,41 @@
 
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::rc::Rc;
+use num::ToPrimitive;
 
 use crate::Context;
 use crate::ContextFn;
+use crate::FunctionDefinition;
+use crate::DEFAULT_RECURSION_LIMIT;
+use crate::Value;
 use crate::EvalError;
+use crate::EvalErrorKind;
 
 use super::Number;
+use super::BigFloat;
 
 pub struct NumberContext {
     vars: HashMap<String, Number>,
-    funcs: HashMap<String, Box<ContextFn<Number>>>,
+    funcs: HashMap<String, Rc<ContextFn<Number>>>,
+    defs: HashMap<String, Rc<FunctionDefinition>>,
+    recursion_limit: usize,
+    precision: Option<u32>,
+    power: Option<Rc<ContextFn<Number>>>,
 }
 
-fn check_length(args: Vec<Number>, min: usize, max: usize) -> Result<Vec<Number>, EvalError> {
+fn check_length(args: &[Number], min: usize, max: usize) -> Result<&[Number], EvalError> {
     if args.len() < min {
-        return Err(EvalError::ArgumentMismatch("Too few arguments to function".to_owned()));
+        return Err(EvalError::new(EvalErrorKind::ArgumentMismatch, "Too few arguments to function"));
     } else if args.len() > max {
-        return Err(EvalError::ArgumentMismatch("Too many arguments to function".to_owned()));
+        return Err(EvalError::new(EvalErrorKind::ArgumentMismatch, "Too many arguments to function"));
     } else {
         return Ok(args);
     }
 }
 
-fn min(mut args: Vec<Number>) -> Result<Number, EvalError> {
-    args = check_length(args, 1, usize::MAX)?;
+fn min(args: &[Number]) -> Result<Number, EvalError> {
+    let args = check_length(args, 1, usize::MAX)?;
     let mut m = 0;
     for i in 1..args.len() {
         let ord = args[i].partial_cmp(&args[m]);
@@ -33,14 +44,14 @@ fn min(mut args: Vec<Number>) -> Result<Number, EvalError> {
                 m = i;
             }
         } else {
-            return Err(EvalError::NotSupported("Values in min function are not comparable".to_owned()));
+            return Err(EvalError::new(EvalErrorKind::NotSupported, "Values in min function are not comparable"));
         }
     }
     return Ok(args[m].clone());
 }
 
-fn max(mut args: Vec<Number>) -> Result<Number, EvalError> {
-    args = check_length(args, 1, usize::MAX)?;
+fn max(args: &[Number]) -> Result<Number, EvalError> {
+    let args = check_length(args, 1, usize::MAX)?;
     let mut m = 0;
     for i in 1..args.len() {
         let ord = args[i].partial_cmp(&args[m]);
@@ -49,17 +60,91 @@ fn max(mut args: Vec<Number>) -> Result<Number, EvalError> {
                 m = i;
             }
         } else {
-            return Err(EvalError::NotSupported("Values in max function are not comparable".to_owned()));
+            return Err(EvalError::new(EvalErrorKind::NotSupported, "Values in max function are not comparable"));
         }
     }
     return Ok(args[m].clone());
 }
 
+fn gcd(args: &[Number]) -> Result<Number, EvalError> {
+    let args = check_length(args, 1, usize::MAX)?;
+    let mut res = args[0].gcd(&Number::zero())?;
+    for arg in &args[1..] {
+        res = res.gcd(arg)?;
+    }
+    return Ok(res);
+}
+
+fn lcm(args: &[Number]) -> Result<Number, EvalError> {
+    let args = check_length(args, 1, usize::MAX)?;
+    let mut res = args[0].lcm(&Number::one())?;
+    for arg in &args[1..] {
+        res = res.lcm(arg)?;
+    }
+    return Ok(res);
+}
+
+fn binomial(args: &[Number]) -> Result<Number, EvalError> {
+    let args = check_length(args, 2, 2)?;
+    return args[0].binomial(&args[1]);
+}
+
+fn factor(args: &[Number]) -> Result<Number, EvalError> {
+    let args = check_length(args, 1, 2)?;
+    if args.len() == 1 {
+        return args[0].factorization();
+    }
+    let index = args[1].to_integer().and_then(|i| i.to_usize()).filter(|i| *i >= 1)
+        .ok_or_else(|| EvalError::new(EvalErrorKind::ArgumentMismatch, "Factor index must be a positive integer"))?;
+    let factors = args[0].factor()?;
+    return factors.get(index - 1).cloned()
+        .ok_or_else(|| EvalError::new(EvalErrorKind::ArgumentMismatch, "Factor index exceeds the number of prime factors"));
+}
+
+// Recomputes results that left the rational domain as big floats of the given precision
+fn promote(x: &Number, precision: Option<u32>, f: fn(&Number) -> Number) -> Number {
+    let res = f(x);
+    match (precision, &res) {
+        (Some(p), Number::Float(_)) => f(&x.with_precision(p)),
+        (Some(p), Number::Symbolic(_)) => res.with_precision(p),
+        _ => res,
+    }
+}
+
+fn power(base: &Number, exponent: &Number, precision: u32) -> Result<Number, EvalError> {
+    let res = base.clone().pow(exponent.clone())?;
+    match res {
+        Number::Float(_) => return base.with_precision(precision).pow(exponent.clone()),
+        Number::Symbolic(_) => return Ok(res.with_precision(precision)),
+        _ => return Ok(res),
+    }
+}
+
+fn unary(precision: Option<u32>, f: fn(&Number) -> Number) -> Box<ContextFn<Number>> {
+    Box::new(move |v| promote(&check_length(v, 1, 1)?[0], precision, f).nan_to_err())
+}
+
 impl NumberContext {
     pub fn new() -> NumberContext {
-        let mut res = NumberContext { vars: HashMap::new(), funcs: HashMap::new() };
-        res.set_variable("pi", Number::pi());
-        res.set_variable("e", Number::e());
+        Self::create(None)
+    }
+
+    pub fn with_precision(bits: u32) -> NumberContext {
+        Self::create(Some(bits.max(1)))
+    }
+
+    fn create(precision: Option<u32>) -> NumberContext {
+        let mut res = NumberContext {
+            vars: HashMap::new(), funcs: HashMap::new(), defs: HashMap::new(), recursion_limit: DEFAULT_RECURSION_LIMIT, precision,
+            power: precision.map(|p| Rc::new(move |v: &[Number]| power(&v[0], &v[1], p)) as Rc<ContextFn<Number>>),
+        };
+        if let Some(p) = precision {
+            res.set_variable("pi", Number::BigFloat(BigFloat::pi(p)));
+            res.set_variable("e", Number::BigFloat(BigFloat::e(p)));
+        } else {
+            res.set_variable("pi", Number::pi());
+            res.set_variable("e", Number::e());
+        }
         res.set_function("floor",Box::new(|v| check_length(v, 1, 1)?[0].floor().nan_to_err()));
         res.set_function("ceil",Box::new(|v| check_length(v, 1, 1)?[0].ceil().nan_to_err()));
         res.set_function("round", Box::new(|v| check_length(v, 1, 1)?[0].round().nan_to_err()));
@@ -67,29 +152,54 @@ impl NumberContext {
         res.set_function("fract", Box::new(|v| check_length(v, 1, 1)?[0].fract().nan_to_err()));
         res.set_function("abs", Box::new(|v| check_length(v, 1, 1)?[0].abs().nan_to_err()));
         res.set_function("sign", Box::new(|v| check_length(v, 1, 1)?[0].sign().nan_to_err()));
-        res.set_function("sqrt", Box::new(|v| check_length(v, 1, 1)?[0].sqrt().nan_to_err()));
-        res.set_function("ln", Box::new(|v| check_length(v, 1, 1)?[0].ln().nan_to_err()));
-        res.set_function("log", Box::new(|v| check_length(v, 1, 1)?[0].log().nan_to_err()));
-        res.set_function("cbrt", Box::new(|v| check_length(v, 1, 1)?[0].cbrt().nan_to_err()));
-        res.set_function("sin", Box::new(|v| check_length(v, 1, 1)?[0].sin().nan_to_err()));
-        res.set_function("cos", Box::new(|v| check_length(v, 1, 1)?[0].cos().nan_to_err()));
-        res.set_function("tan", Box::new(|v| check_length(v, 1, 1)?[0].tan().nan_to_err()));
-        res.set_function("asin", Box::new(|v| check_length(v, 1, 1)?[0].asin().nan_to_err()));
-        res.set_function("acos", Box::new(|v| check_length(v, 1, 1)?[0].acos().nan_to_err()));
-        res.set_function("atan", Box::new(|v| check_length(v, 1, 1)?[0].atan().nan_to_err()));
-        res.set_function("atan2", Box::new(|mut v| {
-            v = check_length(v, 2, 2)?; v[0].atan2(&v[1])
+        res.set_function("sqrt", unary(precision, Number::sqrt));
+        res.set_function("ln", unary(precision, Number::ln));
+        res.set_function("log", unary(precision, Number::log));
+        res.set_function("cbrt", unary(precision, Number::cbrt));
+        res.set_function("sin", unary(precision, Number::sin));
+        res.set_function("cos", unary(precision, Number::cos));
+        res.set_function("tan", unary(precision, Number::tan));
+        res.set_function("asin", unary(precision, Number::asin));
+        res.set_function("acos", unary(precision, Number::acos));
+        res.set_function("atan", unary(precision, Number::atan));
+        res.set_function("atan2", Box::new(move |v| {
+            let v = check_length(v, 2, 2)?;
+            match (precision, v[0].atan2(&v[1])) {
+                (Some(p), Number::Float(_)) => v[0].with_precision(p).atan2(&v[1]),
+                (_, res) => res,
+            }
         }.nan_to_err()));
-        res.set_function("sinh", Box::new(|v| check_length(v, 1, 1)?[0].sinh().nan_to_err()));
-        res.set_function("cosh", Box::new(|v| check_length(v, 1, 1)?[0].cosh().nan_to_err()));
-        res.set_function("tanh", Box::new(|v| check_length(v, 1, 1)?[0].tanh().nan_to_err()));
-        res.set_function("asinh", Box::new(|v| check_length(v, 1, 1)?[0].asinh().nan_to_err()));
-        res.set_function("acosh", Box::new(|v| check_length(v, 1, 1)?[0].acosh().nan_to_err()));
-        res.set_function("atanh", Box::new(|v| check_length(v, 1, 1)?[0].atanh().nan_to_err()));
+        res.set_function("sinh", unary(precision, Number::sinh));
+        res.set_function("cosh", unary(precision, Number::cosh));
+        res.set_function("tanh", unary(precision, Number::tanh));
+        res.set_function("asinh", unary(precision, Number::asinh));
+        res.set_function("acosh", unary(precision, Number::acosh));
+        res.set_function("atanh", unary(precision, Number::atanh));
         res.set_function("min", Box::new(min));
         res.set_function("max", Box::new(max));
+        res.set_function("gcd", Box::new(gcd));
+        res.set_function("lcm", Box::new(lcm));
+        res.set_function("mod", Box::new(|v| { let v = check_length(v, 2, 2)?; Number::modulo(&v[0], &v[1]) }));
+        res.set_function("factorial", Box::new(|v| check_length(v, 1, 1)?[0].factorial()));
+        res.set_function("binomial", Box::new(binomial));
+        res.set_function("nCr", Box::new(binomial));
+        res.set_function("nPr", Box::new(|v| { let v = check_length(v, 2, 2)?; v[0].permutations(&v[1]) }));
+        res.set_function("isprime", Box::new(|v| Ok(Number::from_bool(check_length(v, 1, 1)?[0].is_prime()?))));
+        res.set_function("nextprime", Box::new(|v| check_length(v, 1, 1)?[0].next_prime()));
+        res.set_function("factor", Box::new(factor));
+        res.set_function("modpow", Box::new(|v| { let v = check_length(v, 3, 3)?; v[0].mod_pow(&v[1], &v[2]) }));
+        res.set_function("modinv", Box::new(|v| { let v = check_length(v, 2, 2)?; v[0].mod_inv(&v[1]) }));
+        res.set_function("totient", Box::new(|v| check_length(v, 1, 1)?[0].totient()));
         return res;
     }
+
+    pub fn precision(&self) -> Option<u32> {
+        self.precision
+    }
+
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.recursion_limit = limit;
+    }
 }
 
 impl Context<Number> for NumberContext {
@@ -98,15 +208,33 @@ impl Context<Number> for NumberContext {
     }
 
     fn set_function(&mut self, name: &str, value: Box<ContextFn<Number>>) {
-        self.funcs.insert(name.to_owned(), value);
+        self.defs.remove(name);
+        self.funcs.insert(name.to_owned(), Rc::from(value));
     }
 
     fn get_variable(&self, name: &str) -> Option<Number> {
         return self.vars.get(name).and_then(|n| Some(n.clone()));
     }
 
-    fn get_function<'a>(&'a self, name: &str) -> Option<&'a ContextFn<Number>> {
-        return self.funcs.get(name).and_then(|x| Some(x.as_ref()));
+    fn get_function(&self, name: &str) -> Option<Rc<ContextFn<Number>>> {
+        return self.funcs.get(name).cloned();
+    }
+
+    fn set_definition(&mut self, name: &str, definition: Rc<FunctionDefinition>) {
+        self.funcs.remove(name);
+        self.defs.insert(name.to_owned(), definition);
+    }
+
+    fn get_definition(&self, name: &str) -> Option<Rc<FunctionDefinition>> {
+        return self.defs.get(name).cloned();
+    }
+
+    fn recursion_limit(&self) -> usize {
+        self.recursion_limit
+    }
+
+    fn power(&self) -> Option<Rc<ContextFn<Number>>> {
+        self.power.clone()
     }
 }
 
@@ -1,13 +1,28 @@
 
 use num::{Signed, BigRational, traits::Pow};
 
-use super::Number;
+use super::{Number, BigFloat, Symbolic, symbolic};
+
+fn big_or(value: Option<BigFloat>, fallback: f64) -> Number {
+    value.map_or(Number::Float(fallback), Number::BigFloat)
+}
+
+fn exact_big(b: &BigFloat, f: fn(&BigRational) -> BigRational) -> Number {
+    Number::BigFloat(BigFloat::from_rational(&f(&b.to_rational()), b.precision()))
+}
+
+// Symbolic numbers are irrational, so an approximation decides integer rounding exactly
+fn exact_symbolic(s: &Symbolic, f: fn(&BigRational) -> BigRational) -> Number {
+    s.to_big_float(256).map_or(Number::Float(f64::NAN), |b| Number::Rational(f(&b.to_rational())))
+}
 
 impl Number {
     pub fn floor(&self) -> Number {
         match self {
             Number::Rational(r) => Number::Rational(r.floor()),
             Number::Float(f) => Number::Float(f.floor()),
+            Number::BigFloat(b) => exact_big(b, BigRational::floor),
+            Number::Symbolic(s) => exact_symbolic(s, BigRational::floor),
         }
     }
 
@@ -15,6 +30,8 @@ impl Number {
         match self {
             Number::Rational(r) => Number::Rational(r.ceil()),
             Number::Float(f) => Number::Float(f.ceil()),
+            Number::BigFloat(b) => exact_big(b, BigRational::ceil),
+            Number::Symbolic(s) => exact_symbolic(s, BigRational::ceil),
         }
     }
 
@@ -22,6 +39,8 @@ impl Number {
         match self {
             Number::Rational(r) => Number::Rational(r.round()),
             Number::Float(f) => Number::Float(f.round()),
+            Number::BigFloat(b) => exact_big(b, BigRational::round),
+            Number::Symbolic(s) => exact_symbolic(s, BigRational::round),
         }
     }
 
@@ -29,6 +48,8 @@ impl Number {
         match self {
             Number::Rational(r) => Number::Rational(r.trunc()),
             Number::Float(f) => Number::Float(f.trunc()),
+            Number::BigFloat(b) => exact_big(b, BigRational::trunc),
+            Number::Symbolic(s) => exact_symbolic(s, BigRational::trunc),
         }
     }
 
@@ -36,6 +57,8 @@ impl Number {
         match self {
             Number::Rational(r) => Number::Rational(r.fract()),
             Number::Float(f) => Number::Float(f.fract()),
+            Number::BigFloat(b) => exact_big(b, BigRational::fract),
+            Number::Symbolic(_) => symbolic::sub(self, &self.trunc()).unwrap_or(Number::Float(self.to_f64().fract())),
         }
     }
 
@@ -43,6 +66,9 @@ impl Number {
         match self {
             Number::Rational(r) => Number::Rational(r.abs()),
             Number::Float(f) => Number::Float(f.abs()),
+            Number::BigFloat(b) => Number::BigFloat(b.abs()),
+            Number::Symbolic(s) if s.signum() == std::cmp::Ordering::Less => symbolic::neg(s),
+            Number::Symbolic(_) => self.clone(),
         }
     }
 
@@ -65,15 +91,21 @@ impl Number {
                         return Number::Rational(sqrt);
                     }
                 }
-                return Number::Float(self.to_f64().sqrt());
+                return symbolic::sqrt(self).unwrap_or(Number::Float(self.to_f64().sqrt()));
             },
             Number::Float(f) => return Number::Float(f.sqrt()),
+            Number::BigFloat(b) => return big_or(b.sqrt(), f64::NAN),
+            Number::Symbolic(s) => return symbolic::sqrt(self).unwrap_or(Number::Float(s.to_f64().sqrt())),
         }
     }
 
     pub fn ln(&self) -> Number {
         if self == &Number::one() {
             Number::zero()
+        } else if let Some(res) = symbolic::ln_of(self) {
+            res
+        } else if let Number::BigFloat(b) = self {
+            big_or(b.ln(), f64::NAN)
         } else {
             Number::Float(self.to_f64().ln())
         }
@@ -82,6 +114,10 @@ impl Number {
     pub fn log(&self) -> Number {
         if self == &Number::one() {
             Number::zero()
+        } else if let Some(res) = symbolic::log(self) {
+            res
+        } else if let Number::BigFloat(b) = self {
+            big_or(b.log(), f64::NAN)
         } else {
             Number::Float(self.to_f64().log10())
         }
@@ -99,12 +135,21 @@ impl Number {
                 return Number::Float(self.to_f64().cbrt());
             },
             Number::Float(f) => return Number::Float(f.cbrt()),
+            Number::BigFloat(b) => return big_or(b.cbrt(), f64::NAN),
+            Number::Symbolic(s) => match s.exact() {
+                Some(r) => return Number::Rational(r.clone()).cbrt(),
+                None => return Number::Float(s.to_f64().cbrt()),
+            },
         }
     }
 
     pub fn sin(&self) -> Number {
         if self.is_zero() {
             Number::zero()
+        } else if let Some(res) = symbolic::sin(self) {
+            res
+        } else if let Number::BigFloat(b) = self {
+            big_or(b.sin(), f64::NAN)
         } else {
             Number::Float(self.to_f64().sin())
         }
@@ -113,6 +158,10 @@ impl Number {
     pub fn cos(&self) -> Number {
         if self.is_zero() {
             Number::one()
+        } else if let Some(res) = symbolic::cos(self) {
+            res
+        } else if let Number::BigFloat(b) = self {
+            big_or(b.cos(), f64::NAN)
         } else {
             Number::Float(self.to_f64().cos())
         }
@@ -121,6 +170,10 @@ impl Number {
     pub fn tan(&self) -> Number {
         if self.is_zero() {
             Number::zero()
+        } else if let Some(res) = symbolic::tan(self) {
+            res
+        } else if let Number::BigFloat(b) = self {
+            big_or(b.tan(), f64::NAN)
         } else {
             Number::Float(self.to_f64().tan())
         }
@@ -129,6 +182,10 @@ impl Number {
     pub fn asin(&self) -> Number {
         if self.is_zero() {
             Number::zero()
+        } else if let Some(res) = symbolic::asin(self) {
+            res
+        } else if let Number::BigFloat(b) = self {
+            big_or(b.asin(), f64::NAN)
         } else {
             Number::Float(self.to_f64().asin())
         }
@@ -137,6 +194,10 @@ impl Number {
     pub fn acos(&self) -> Number {
         if self == &Number::one() {
             Number::zero()
+        } else if let Some(res) = symbolic::acos(self) {
+            res
+        } else if let Number::BigFloat(b) = self {
+            big_or(b.acos(), f64::NAN)
         } else {
             Number::Float(self.to_f64().acos())
         }
@@ -145,6 +206,10 @@ impl Number {
     pub fn atan(&self) -> Number {
         if self.is_zero() {
             Number::zero()
+        } else if let Some(res) = symbolic::atan(self) {
+            res
+        } else if let Number::BigFloat(b) = self {
+            big_or(b.atan(), f64::NAN)
         } else {
             Number::Float(self.to_f64().atan())
         }
@@ -153,6 +218,12 @@ impl Number {
     pub fn atan2(&self, o: &Self) -> Number {
         if self.is_zero() {
             Number::zero()
+        } else if let (Number::BigFloat(b), _) | (_, Number::BigFloat(b)) = (self, o) {
+            let (y, x) = (self.with_precision(b.precision()), o.with_precision(b.precision()));
+            match (&y, &x) {
+                (Number::BigFloat(y), Number::BigFloat(x)) => big_or(y.atan2(x), f64::NAN),
+                _ => Number::Float(self.to_f64().atan2(o.to_f64())),
+            }
         } else {
             Number::Float(self.to_f64().atan2(o.to_f64()))
         }
@@ -161,6 +232,8 @@ impl Number {
     pub fn sinh(&self) -> Number {
         if self.is_zero() {
             Number::zero()
+        } else if let Number::BigFloat(b) = self {
+            big_or(b.sinh(), b.to_f64().sinh())
         } else {
             Number::Float(self.to_f64().sinh())
         }
@@ -169,6 +242,8 @@ impl Number {
     pub fn cosh(&self) -> Number {
         if self.is_zero() {
             Number::one()
+        } else if let Number::BigFloat(b) = self {
+            big_or(b.cosh(), b.to_f64().cosh())
         } else {
             Number::Float(self.to_f64().cosh())
         }
@@ -177,6 +252,8 @@ impl Number {
     pub fn tanh(&self) -> Number {
         if self.is_zero() {
             Number::zero()
+        } else if let Number::BigFloat(b) = self {
+            big_or(b.tanh(), b.to_f64().tanh())
         } else {
             Number::Float(self.to_f64().tanh())
         }
@@ -185,6 +262,8 @@ impl Number {
     pub fn asinh(&self) -> Number {
         if self.is_zero() {
             Number::zero()
+        } else if let Number::BigFloat(b) = self {
+            big_or(b.asinh(), f64::NAN)
         } else {
             Number::Float(self.to_f64().asinh())
         }
@@ -193,6 +272,8 @@ impl Number {
     pub fn acosh(&self) -> Number {
         if self == &Number::one() {
             Number::zero()
+        } else if let Number::BigFloat(b) = self {
+            big_or(b.acosh(), f64::NAN)
         } else {
             Number::Float(self.to_f64().acosh())
         }
@@ -201,9 +282,10 @@ impl Number {
     pub fn atanh(&self) -> Number {
         if self.is_zero() {
             Number::zero()
+        } else if let Number::BigFloat(b) = self {
+            big_or(b.atanh(), f64::NAN)
         } else {
             Number::Float(self.to_f64().atanh())
         }
     }
 }
-
@@ -0,0 +1,348 @@
+
+use num::*;
+use num::integer::ExtendedGcd;
+
+use crate::EvalError;
+use crate::EvalErrorKind;
+
+use super::Number;
+use super::Symbolic;
+
+const MAX_FACTORIAL: u64 = 100_000;
+const TRIAL_DIVISION_LIMIT: u32 = 1000;
+// Enough to find prime factors up to about 10^9 with Pollard's rho
+const MAX_RHO_ITERATIONS: u32 = 1 << 16;
+// Miller-Rabin with these bases is deterministic below 3.3 * 10^24
+const WITNESSES: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+fn mismatch(name: &str, expected: &str) -> EvalError {
+    EvalError::new(EvalErrorKind::ArgumentMismatch, &format!("Function {} expects {}", name, expected))
+}
+
+fn integer(n: &Number, name: &str) -> Result<BigInt, EvalError> {
+    n.to_integer().ok_or_else(|| mismatch(name, "integer arguments"))
+}
+
+fn natural(n: &Number, name: &str) -> Result<BigInt, EvalError> {
+    match n.to_integer() {
+        Some(i) if !i.is_negative() => Ok(i),
+        _ => Err(mismatch(name, "non-negative integer arguments")),
+    }
+}
+
+fn modulus(n: &Number, name: &str) -> Result<BigInt, EvalError> {
+    let m = integer(n, name)?;
+    if m.is_zero() {
+        return Err(EvalError::new(EvalErrorKind::MathError, "Modulus must not be zero"));
+    } else {
+        return Ok(m.abs());
+    }
+}
+
+fn operand(n: &Number, op: &str) -> Result<BigInt, EvalError> {
+    n.to_integer().ok_or_else(|| EvalError::new(
+        EvalErrorKind::ArgumentMismatch, &format!("Operator {} expects integer operands", op)
+    ))
+}
+
+pub(super) fn bitwise(a: &Number, b: &Number, op: &str, f: fn(BigInt, BigInt) -> BigInt) -> Result<Number, EvalError> {
+    Ok(exact(f(operand(a, op)?, operand(b, op)?)))
+}
+
+// Shifts left by the given amount, or right for negative amounts, rounding towards negative infinity
+pub(super) fn shift(a: &Number, amount: &Number, op: &str, left: bool) -> Result<Number, EvalError> {
+    let value = operand(a, op)?;
+    let amount = operand(amount, op)?.to_i32()
+        .ok_or_else(|| EvalError::new(EvalErrorKind::MathError, "Shift amount is too large"))?;
+    let amount = if left { amount as i64 } else { -(amount as i64) };
+    if amount >= 0 {
+        return Ok(exact(value << amount as u64));
+    } else {
+        return Ok(exact(value >> (-amount) as u64));
+    }
+}
+
+pub(super) fn complement(a: &Number) -> Result<Number, EvalError> {
+    Ok(exact(-operand(a, "~")? - 1))
+}
+
+fn exact(n: BigInt) -> Number {
+    Number::Rational(BigRational::from_integer(n))
+}
+
+// Product of the integers in from..=to
+fn product(from: BigInt, to: &BigInt) -> BigInt {
+    let mut res = BigInt::one();
+    let mut i = from;
+    while &i <= to {
+        res *= &i;
+        i += 1;
+    }
+    return res;
+}
+
+fn binomial(n: &BigInt, k: &BigInt) -> BigInt {
+    if k.is_negative() || (!n.is_negative() && k > n) {
+        return BigInt::zero();
+    } else if n.is_negative() {
+        let res = binomial(&(k - n - 1), k);
+        return if k.is_odd() { -res } else { res };
+    }
+    let k = k.clone().min(n - k);
+    let mut res = BigInt::one();
+    let mut i = BigInt::one();
+    while i <= k {
+        res = res * (n - &k + &i) / &i;
+        i += 1;
+    }
+    return res;
+}
+
+fn is_prime(n: &BigInt) -> bool {
+    if n < &BigInt::from(2) {
+        return false;
+    }
+    for p in WITNESSES {
+        if n == &BigInt::from(p) {
+            return true;
+        } else if (n % p).is_zero() {
+            return false;
+        }
+    }
+    let m: BigInt = n - 1;
+    let s = m.trailing_zeros().unwrap_or(0);
+    let d = &m >> s;
+    'witnesses: for a in WITNESSES {
+        let mut x = BigInt::from(a).modpow(&d, n);
+        if x.is_one() || x == m {
+            continue;
+        }
+        for _ in 1..s {
+            x = &x * &x % n;
+            if x == m {
+                continue 'witnesses;
+            }
+        }
+        return false;
+    }
+    return true;
+}
+
+// Finds a nontrivial divisor of an odd composite number with Brent's variant of Pollard's rho, giving up after
+// MAX_RHO_ITERATIONS steps
+fn pollard_rho(n: &BigInt) -> Option<BigInt> {
+    const BATCH: u32 = 64;
+    let mut c = BigInt::one();
+    let mut iterations = 0;
+    loop {
+        let step = |x: &BigInt| (x * x + &c) % n;
+        let (mut y, mut r, mut q, mut d) = (BigInt::from(2), 1, BigInt::one(), BigInt::one());
+        let (mut x, mut saved) = (y.clone(), y.clone());
+        while d.is_one() {
+            x = y.clone();
+            for _ in 0..r {
+                y = step(&y);
+            }
+            let mut k = 0;
+            while k < r && d.is_one() {
+                saved = y.clone();
+                for _ in 0..BATCH.min(r - k) {
+                    y = step(&y);
+                    q = q * (&x - &y).abs() % n;
+                }
+                d = q.gcd(n);
+                k += BATCH;
+            }
+            iterations += r;
+            if iterations > MAX_RHO_ITERATIONS {
+                return None;
+            }
+            r *= 2;
+        }
+        if &d == n {
+            // The batch overshot, so retrace it one step at a time
+            loop {
+                saved = step(&saved);
+                d = (&x - &saved).abs().gcd(n);
+                if !d.is_one() {
+                    break;
+                }
+            }
+        }
+        if &d != n {
+            return Some(d);
+        }
+        c += 1;
+    }
+}
+
+fn prime_factors(n: &BigInt) -> Result<Vec<BigInt>, EvalError> {
+    let mut factors = Vec::new();
+    let mut rest = n.abs();
+    for p in 2..TRIAL_DIVISION_LIMIT {
+        while (&rest % p).is_zero() {
+            factors.push(BigInt::from(p));
+            rest /= p;
+        }
+    }
+    let mut pending = if rest.is_one() { Vec::new() } else { vec![rest] };
+    while let Some(m) = pending.pop() {
+        if is_prime(&m) {
+            factors.push(m);
+        } else {
+            let d = pollard_rho(&m).ok_or_else(|| EvalError::new(
+                EvalErrorKind::MathError, &format!("Could not find a factor of {} within the iteration limit", m)
+            ))?;
+            pending.push(&m / &d);
+            pending.push(d);
+        }
+    }
+    factors.sort();
+    return Ok(factors);
+}
+
+impl Number {
+    pub fn to_integer(&self) -> Option<BigInt> {
+        match self {
+            Number::Rational(r) if r.is_integer() => Some(r.to_integer()),
+            Number::Float(f) if f.is_finite() && f.fract() == 0.0 => BigInt::from_f64(*f),
+            Number::BigFloat(b) if b.is_integer() => Some(b.to_rational().to_integer()),
+            Number::Symbolic(s) => s.exact().filter(|r| r.is_integer()).map(|r| r.to_integer()),
+            _ => None,
+        }
+    }
+
+    pub fn gcd(&self, other: &Number) -> Result<Number, EvalError> {
+        Ok(exact(integer(self, "gcd")?.gcd(&integer(other, "gcd")?)))
+    }
+
+    pub fn lcm(&self, other: &Number) -> Result<Number, EvalError> {
+        Ok(exact(integer(self, "lcm")?.lcm(&integer(other, "lcm")?)))
+    }
+
+    // The remainder of floor division, taking the sign of the divisor
+    pub fn modulo(&self, other: &Number) -> Result<Number, EvalError> {
+        if other.is_zero() {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Division by zero"));
+        }
+        match (self, other) {
+            (Number::Rational(a), Number::Rational(b)) => Ok(Number::Rational(a - b * (a / b).floor())),
+            (Number::Float(a), Number::Float(b)) => {
+                let r = a % b;
+                if r != 0.0 && (r < 0.0) != (*b < 0.0) {
+                    Ok(Number::Float(r + b))
+                } else {
+                    Ok(Number::Float(r))
+                }
+            },
+            (a, b) => a.clone() - (b.clone() * (a.clone() / b.clone())?.floor())?,
+        }
+    }
+
+    pub fn factorial(&self) -> Result<Number, EvalError> {
+        let n = natural(self, "factorial")?;
+        if n > BigInt::from(MAX_FACTORIAL) {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Argument of factorial is too large"));
+        } else {
+            return Ok(exact(product(BigInt::one(), &n)));
+        }
+    }
+
+    pub fn binomial(&self, k: &Number) -> Result<Number, EvalError> {
+        let (n, k) = (integer(self, "binomial")?, integer(k, "binomial")?);
+        if k > BigInt::from(MAX_FACTORIAL) && (n.is_negative() || &n - &k > BigInt::from(MAX_FACTORIAL)) {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Arguments of binomial are too large"));
+        } else {
+            return Ok(exact(binomial(&n, &k)));
+        }
+    }
+
+    pub fn permutations(&self, k: &Number) -> Result<Number, EvalError> {
+        let (n, k) = (natural(self, "nPr")?, natural(k, "nPr")?);
+        if k > n {
+            return Ok(Number::zero());
+        } else if k > BigInt::from(MAX_FACTORIAL) {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Arguments of nPr are too large"));
+        } else {
+            return Ok(exact(product(&n - &k + 1, &n)));
+        }
+    }
+
+    pub fn is_prime(&self) -> Result<bool, EvalError> {
+        Ok(is_prime(&integer(self, "isprime")?))
+    }
+
+    pub fn next_prime(&self) -> Result<Number, EvalError> {
+        let mut n = integer(self, "nextprime")?.max(BigInt::one()) + 1;
+        while !is_prime(&n) {
+            n += 1;
+        }
+        return Ok(exact(n));
+    }
+
+    // The prime factors in ascending order, repeated according to their multiplicity
+    pub fn factor(&self) -> Result<Vec<Number>, EvalError> {
+        let n = integer(self, "factor")?;
+        if n.is_zero() {
+            return Err(mismatch("factor", "a nonzero integer"));
+        } else {
+            return Ok(prime_factors(&n)?.into_iter().map(exact).collect());
+        }
+    }
+
+    // The prime factorization, which is shown as a product of prime powers
+    pub fn factorization(&self) -> Result<Number, EvalError> {
+        let n = integer(self, "factor")?;
+        let mut powers: Vec<(BigInt, u32)> = Vec::new();
+        for p in self.factor()? {
+            let p = integer(&p, "factor")?;
+            match powers.last_mut() {
+                Some((q, k)) if q == &p => *k += 1,
+                _ => powers.push((p, 1)),
+            }
+        }
+        if powers.is_empty() {
+            return Ok(exact(n));
+        } else {
+            return Ok(Number::Symbolic(Box::new(Symbolic::Factors(BigRational::from_integer(n), powers))));
+        }
+    }
+
+    pub fn mod_pow(&self, exponent: &Number, m: &Number) -> Result<Number, EvalError> {
+        let m = modulus(m, "modpow")?;
+        let e = integer(exponent, "modpow")?;
+        let base = if e.is_negative() {
+            integer(&self.mod_inv(&exact(m.clone()))?, "modpow")?
+        } else {
+            integer(self, "modpow")?.mod_floor(&m)
+        };
+        return Ok(exact(base.modpow(&e.abs(), &m)));
+    }
+
+    pub fn mod_inv(&self, m: &Number) -> Result<Number, EvalError> {
+        let m = modulus(m, "modinv")?;
+        let a = integer(self, "modinv")?.mod_floor(&m);
+        let ExtendedGcd { gcd, x, .. } = a.extended_gcd(&m);
+        if !gcd.is_one() {
+            return Err(EvalError::new(
+                EvalErrorKind::MathError, &format!("{} is not invertible modulo {}", a, m)
+            ));
+        } else {
+            return Ok(exact(x.mod_floor(&m)));
+        }
+    }
+
+    pub fn totient(&self) -> Result<Number, EvalError> {
+        let n = integer(self, "totient")?;
+        if !n.is_positive() {
+            return Err(mismatch("totient", "a positive integer"));
+        }
+        let mut factors = prime_factors(&n)?;
+        factors.dedup();
+        let mut res = n;
+        for p in factors {
+            res = res / &p * (&p - 1);
+        }
+        return Ok(exact(res));
+    }
+}
@@ -3,8 +3,14 @@ mod number;
 mod context;
 mod functions;
 mod constants;
+mod bigfloat;
+mod transcendental;
+mod symbolic;
+mod integer;
 
 pub use number::Number;
+pub use bigfloat::BigFloat;
+pub use symbolic::Symbolic;
 pub use context::NumberContext;
 pub use functions::*;
 pub use constants::*;
@@ -6,13 +6,46 @@ use num::traits::Pow;
 
 use crate::Value;
 use crate::EvalError;
+use crate::EvalErrorKind;
 
 use super::NumberContext;
+use super::BigFloat;
+use super::Symbolic;
+use super::symbolic;
+use super::integer;
 
 #[derive(Debug, Clone)]
 pub enum Number {
     Rational(BigRational),
     Float(f64),
+    BigFloat(BigFloat),
+    Symbolic(Box<Symbolic>),
+}
+
+fn big_precision(a: &Number, b: &Number) -> Option<u32> {
+    match (a, b) {
+        (Number::BigFloat(x), Number::BigFloat(y)) => Some(x.precision().max(y.precision())),
+        (Number::BigFloat(x), _) | (_, Number::BigFloat(x)) => Some(x.precision()),
+        _ => None,
+    }
+}
+
+// The exact value, or an approximation well beyond the given precision for symbolic numbers
+fn exact_value(n: &Number, precision: u32) -> Option<BigRational> {
+    match n {
+        Number::Symbolic(s) => s.to_big_float(precision + 64).map(|b| b.to_rational()),
+        _ => n.to_rational(),
+    }
+}
+
+fn float_op(a: Number, b: Number, exact: fn(BigRational, BigRational) -> BigRational, float: fn(f64, f64) -> f64) -> Number {
+    match big_precision(&a, &b) {
+        Some(p) => match (exact_value(&a, p), exact_value(&b, p)) {
+            (Some(x), Some(y)) => Number::BigFloat(BigFloat::from_rational(&exact(x, y), p)),
+            _ => Number::Float(float(a.to_f64(), b.to_f64())),
+        },
+        None => Number::Float(float(a.to_f64(), b.to_f64())),
+    }
 }
 
 impl Number {
@@ -39,6 +72,17 @@ impl Number {
         match self {
             Number::Rational(r) => r.to_f64().unwrap_or(f64::NAN),
             Number::Float(f) => *f,
+            Number::BigFloat(b) => b.to_f64(),
+            Number::Symbolic(s) => s.to_f64(),
+        }
+    }
+
+    pub fn with_precision(&self, precision: u32) -> Number {
+        match self {
+            Number::Rational(r) => Number::BigFloat(BigFloat::from_rational(r, precision)),
+            Number::Float(f) => BigFloat::from_f64(*f, precision).map_or(Number::Float(*f), Number::BigFloat),
+            Number::BigFloat(b) => Number::BigFloat(b.with_precision(precision)),
+            Number::Symbolic(s) => s.to_big_float(precision).map_or(Number::Float(s.to_f64()), Number::BigFloat),
         }
     }
 
@@ -46,13 +90,16 @@ impl Number {
         match self {
             Number::Rational(r) => Some(r.clone()),
             Number::Float(f) => BigRational::from_f64(*f),
+            Number::BigFloat(b) => Some(b.to_rational()),
+            Number::Symbolic(s) => s.exact().cloned(),
         }
     }
 
     pub fn is_rational(&self) -> bool {
         match self {
             Number::Rational(..) => true,
-            Number::Float(..) => false,
+            Number::Float(..) | Number::BigFloat(..) => false,
+            Number::Symbolic(s) => s.exact().is_some(),
         }
     }
 
@@ -60,6 +107,19 @@ impl Number {
         match self {
             Number::Rational(r) => r.is_integer(),
             Number::Float(f) => *f == f.trunc(),
+            Number::BigFloat(b) => b.is_integer(),
+            Number::Symbolic(s) => s.exact().map_or(false, |r| r.is_integer()),
+        }
+    }
+
+    // Factorizations take part in arithmetic as the integer they stand for
+    fn settled(self) -> Number {
+        match self {
+            Number::Symbolic(s) => match *s {
+                Symbolic::Factors(r, _) => Number::Rational(r),
+                s => Number::Symbolic(Box::new(s)),
+            },
+            n => n,
         }
     }
 
@@ -67,6 +127,8 @@ impl Number {
         match self {
             Number::Rational(r) => r.is_positive(),
             Number::Float(f) => *f > 0.0,
+            Number::BigFloat(b) => b.is_positive(),
+            Number::Symbolic(s) => s.signum() == std::cmp::Ordering::Greater,
         }
     }
 
@@ -74,6 +136,8 @@ impl Number {
         match self {
             Number::Rational(r) => r.is_negative(),
             Number::Float(f) => *f < 0.0,
+            Number::BigFloat(b) => b.is_negative(),
+            Number::Symbolic(s) => s.signum() == std::cmp::Ordering::Less,
         }
     }
 
@@ -81,13 +145,15 @@ impl Number {
         match self {
             Number::Rational(r) => r.is_zero(),
             Number::Float(f) => f.is_zero(),
+            Number::BigFloat(b) => b.is_zero(),
+            Number::Symbolic(_) => false,
         }
     }
 
     pub fn nan_to_err(self) -> Result<Self, EvalError> {
         if let Self::Float(f) = self {
             if f.is_nan() {
-                return Err(EvalError::MathError("NaN".to_owned()));
+                return Err(EvalError::new(EvalErrorKind::MathError, "NaN"));
             }
         }
         return Ok(self);
@@ -99,6 +165,9 @@ impl PartialEq for Number {
         match (self, other) {
             (Self::Float(l), Self::Float(r)) => l == r,
             (Self::Rational(l), Self::Rational(r)) => l == r,
+            (Self::Symbolic(l), Self::Symbolic(r)) => l == r,
+            (l, r) if l.is_rational() && r.is_rational() => l.to_rational() == r.to_rational(),
+            (Self::Symbolic(_), _) | (_, Self::Symbolic(_)) => false,
             (l, r) => l.to_rational() == r.to_rational(),
         }
     }
@@ -109,6 +178,10 @@ impl PartialOrd for Number {
         match (self, other) {
             (Self::Float(l), Self::Float(r)) => l.partial_cmp(r),
             (Self::Rational(l), Self::Rational(r)) => l.partial_cmp(r),
+            (Self::Symbolic(l), Self::Symbolic(r)) if l == r => Some(std::cmp::Ordering::Equal),
+            (l @ Self::Symbolic(_), r) | (l, r @ Self::Symbolic(_)) => {
+                exact_value(l, 256).partial_cmp(&exact_value(r, 256))
+            },
             (l, r) => l.to_rational().partial_cmp(&r.to_rational()),
         }
     }
@@ -119,6 +192,8 @@ impl ToString for Number {
         match self {
             Number::Rational(r) => r.to_string(),
             Number::Float(f) => f.to_string(),
+            Number::BigFloat(b) => b.to_string(),
+            Number::Symbolic(s) => s.to_string(),
         }
     }
 }
@@ -150,7 +225,7 @@ impl FromStr for Number {
         } else if pos < chars.len() && chars[pos].is_digit(10) {
             base = 10;
         } else {
-            return Err(EvalError::InvalidLiteral("Literals must not be empty".to_owned()));
+            return Err(EvalError::new(EvalErrorKind::InvalidLiteral, "Literals must not be empty"));
         }
         while pos < chars.len() && chars[pos].is_digit(base) {
             num = base * num + chars[pos].to_digit(base).unwrap();
@@ -177,7 +252,7 @@ impl FromStr for Number {
                 }
                 pos += 2;
             } else {
-                return Err(EvalError::InvalidLiteral("Missing exponent".to_owned()));
+                return Err(EvalError::new(EvalErrorKind::InvalidLiteral, "Missing exponent"));
             }
             let mut exp = BigUint::zero();
             while pos < chars.len() && chars[pos].is_digit(base) {
@@ -191,7 +266,7 @@ impl FromStr for Number {
             }
         }
         if pos != chars.len() {
-            return Err(EvalError::InvalidLiteral(format!("Unexpected character '{}'", chars[pos])));
+            return Err(EvalError::new(EvalErrorKind::InvalidLiteral, &format!("Unexpected character '{}'", chars[pos])));
         } else {
             return Ok(Number::Rational(BigRational::new(num, den)));
         }
@@ -204,7 +279,9 @@ impl Neg for Number {
     fn neg(self) -> Self::Output {
         match self {
             Number::Rational(r) => Ok(Number::Rational(r.neg())),
-            Number::Float(f) => Ok(Number::Float(f.neg())),
+            Number::Float(f) => Ok(Number::Float(-f)),
+            Number::BigFloat(b) => Ok(Number::BigFloat(b.neg())),
+            Number::Symbolic(s) => Ok(symbolic::neg(&s)),
         }
     }
 }
@@ -213,9 +290,9 @@ impl Add for Number {
     type Output = Result<Number, EvalError>;
 
     fn add(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
+        match (self.settled(), rhs.settled()) {
             (Number::Rational(a), Number::Rational(b)) => Ok(Number::Rational(a.add(b))),
-            (a, b) => Ok(Number::Float(a.to_f64().add(b.to_f64()))),
+            (a, b) => Ok(symbolic::add(&a, &b).unwrap_or_else(|| float_op(a, b, |x, y| x + y, |x, y| x + y))),
         }
     }
 }
@@ -224,9 +301,9 @@ impl Sub for Number {
     type Output = Result<Number, EvalError>;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
+        match (self.settled(), rhs.settled()) {
             (Number::Rational(a), Number::Rational(b)) => Ok(Number::Rational(a.sub(b))),
-            (a, b) => Ok(Number::Float(a.to_f64().sub(b.to_f64()))),
+            (a, b) => Ok(symbolic::sub(&a, &b).unwrap_or_else(|| float_op(a, b, |x, y| x - y, |x, y| x - y))),
         }
     }
 }
@@ -235,9 +312,9 @@ impl Mul for Number {
     type Output = Result<Number, EvalError>;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
+        match (self.settled(), rhs.settled()) {
             (Number::Rational(a), Number::Rational(b)) => Ok(Number::Rational(a.mul(b))),
-            (a, b) => Ok(Number::Float(a.to_f64().mul(b.to_f64()))),
+            (a, b) => Ok(symbolic::mul(&a, &b).unwrap_or_else(|| float_op(a, b, |x, y| x * y, |x, y| x * y))),
         }
     }
 }
@@ -247,11 +324,11 @@ impl Div for Number {
 
     fn div(self, rhs: Self) -> Self::Output {
         if rhs.is_zero() {
-            Err(EvalError::MathError("Division by zero".to_owned()))
+            Err(EvalError::new(EvalErrorKind::MathError, "Division by zero"))
         } else {
-            match (self, rhs) {
+            match (self.settled(), rhs.settled()) {
                 (Number::Rational(a), Number::Rational(b)) => Ok(Number::Rational(a.div(b))),
-                (a, b) => Ok(Number::Float(a.to_f64().div(b.to_f64()))),
+                (a, b) => Ok(symbolic::div(&a, &b).unwrap_or_else(|| float_op(a, b, |x, y| x / y, |x, y| x / y))),
             }
         }
     }
@@ -261,20 +338,35 @@ impl Pow<Number> for Number {
     type Output = Result<Number, EvalError>;
 
     fn pow(self, rhs: Number) -> Self::Output {
-        if self.is_zero() && rhs.is_negative() {
-            Err(EvalError::MathError("Division by zero".to_owned()))
-        } else if self.is_zero() && rhs.is_zero() {
-            Err(EvalError::MathError("Zero to the power of zero".to_owned()))
+        let (base, rhs) = (self.settled(), rhs.settled());
+        if base.is_zero() && rhs.is_negative() {
+            Err(EvalError::new(EvalErrorKind::MathError, "Division by zero"))
+        } else if base.is_zero() && rhs.is_zero() {
+            Err(EvalError::new(EvalErrorKind::MathError, "Zero to the power of zero"))
+        } else if let Some(res) = symbolic::pow(&base, &rhs) {
+            Ok(res)
         } else {
-            match (self, rhs) {
+            match (base, rhs) {
                 (Number::Rational(a), Number::Rational(b)) if b.is_integer() => {
                     if let Some(i) = b.to_i32() {
                         Ok(Number::Rational(a.pow(i)))
                     } else {
-                        Ok(Number::Float(a.to_f64().unwrap().pow(b.to_f64().unwrap())))
+                        Ok(Number::Float(a.to_f64().unwrap().powf(b.to_f64().unwrap())))
                     }
                 },
-                (a, b) => Ok(Number::Float(a.to_f64().pow(b.to_f64()))),
+                (a, b) => match big_precision(&a, &b) {
+                    Some(p) => {
+                        let (x, y) = (a.with_precision(2 * p + 64), b.with_precision(2 * p + 64));
+                        match (&x, &y) {
+                            (Number::BigFloat(x), Number::BigFloat(y)) => match x.pow(y) {
+                                Some(r) => Ok(Number::BigFloat(r.with_precision(p))),
+                                None => Ok(Number::Float(a.to_f64().powf(b.to_f64()))),
+                            },
+                            _ => Ok(Number::Float(a.to_f64().powf(b.to_f64()))),
+                        }
+                    },
+                    None => Ok(Number::Float(a.to_f64().powf(b.to_f64()))),
+                },
             }
         }
     }
@@ -286,5 +378,77 @@ impl Value for Number {
     fn default_context() -> Self::DefaultContext {
         NumberContext::new()
     }
+
+    fn parse_literal(literal: &str) -> Result<Self, EvalError> {
+        Number::from_str(literal)
+    }
+
+    fn add(self, other: Self) -> Result<Self, EvalError> {
+        self + other
+    }
+
+    fn sub(self, other: Self) -> Result<Self, EvalError> {
+        self - other
+    }
+
+    fn mul(self, other: Self) -> Result<Self, EvalError> {
+        self * other
+    }
+
+    fn div(self, other: Self) -> Result<Self, EvalError> {
+        self / other
+    }
+
+    fn pow(self, other: Self) -> Result<Self, EvalError> {
+        Pow::pow(self, other)
+    }
+
+    fn neg(self) -> Result<Self, EvalError> {
+        -self
+    }
+
+    fn modulo(self, other: Self) -> Result<Self, EvalError> {
+        Number::modulo(&self, &other)
+    }
+
+    fn floor_div(self, other: Self) -> Result<Self, EvalError> {
+        Ok((self / other)?.floor())
+    }
+
+    fn bit_and(self, other: Self) -> Result<Self, EvalError> {
+        integer::bitwise(&self, &other, "&", |a, b| a & b)
+    }
+
+    fn bit_or(self, other: Self) -> Result<Self, EvalError> {
+        integer::bitwise(&self, &other, "|", |a, b| a | b)
+    }
+
+    fn bit_xor(self, other: Self) -> Result<Self, EvalError> {
+        integer::bitwise(&self, &other, "xor", |a, b| a ^ b)
+    }
+
+    fn shift_left(self, other: Self) -> Result<Self, EvalError> {
+        integer::shift(&self, &other, "<<", true)
+    }
+
+    fn shift_right(self, other: Self) -> Result<Self, EvalError> {
+        integer::shift(&self, &other, ">>", false)
+    }
+
+    fn bit_not(self) -> Result<Self, EvalError> {
+        integer::complement(&self)
+    }
+
+    fn from_bool(value: bool) -> Self {
+        if value {
+            Number::one()
+        } else {
+            Number::zero()
+        }
+    }
+
+    fn to_bool(&self) -> Result<bool, EvalError> {
+        return Ok(!self.is_zero());
+    }
 }
 
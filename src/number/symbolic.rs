@@ -0,0 +1,549 @@
+
+use std::cmp::Ordering;
+use num::*;
+use num::traits::Pow;
+
+use super::{Number, BigFloat};
+
+const TRIAL_DIVISION_LIMIT: u32 = 1000;
+const MAX_EXACT_EXPONENT: i32 = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Symbolic {
+    // A rational plus rational multiples of square roots of distinct squarefree integers
+    Surd(BigRational, Vec<(BigRational, BigInt)>),
+    // A rational multiple of pi
+    Pi(BigRational),
+    // c * pi^k with k other than 0 and 1
+    PiPow(BigRational, i32),
+    // c * e^q
+    Exp(BigRational, BigRational),
+    // c * ln(r) with r > 1 not a perfect power
+    Ln(BigRational, BigRational),
+    // An integer shown as the product of its prime factors and their exponents
+    Factors(BigRational, Vec<(BigInt, u32)>),
+}
+
+type Surd = (BigRational, Vec<(BigRational, BigInt)>);
+
+// A borrowed view of a number for matching through the boxed symbolic variant
+enum Form<'a> {
+    Rational(&'a BigRational),
+    Symbolic(&'a Symbolic),
+    Other,
+}
+
+fn form(n: &Number) -> Form<'_> {
+    match n {
+        Number::Rational(r) => Form::Rational(r),
+        Number::Symbolic(s) => match s.as_ref() {
+            Symbolic::Factors(r, _) => Form::Rational(r),
+            s => Form::Symbolic(s),
+        },
+        _ => Form::Other,
+    }
+}
+
+fn rational(num: i64, den: i64) -> BigRational {
+    BigRational::new(BigInt::from(num), BigInt::from(den))
+}
+
+// Splits n > 0 into s^2 * m with m squarefree, if trial division suffices to find the factorization
+fn split_square(n: &BigInt) -> Option<(BigInt, BigInt)> {
+    let mut rest = n.clone();
+    let mut square = BigInt::one();
+    let mut free = BigInt::one();
+    let mut p = 2u32;
+    while p <= TRIAL_DIVISION_LIMIT && BigInt::from(p * p) <= rest {
+        let mut count = 0u32;
+        while (&rest % p).is_zero() {
+            rest /= p;
+            count += 1;
+        }
+        square *= Pow::pow(BigInt::from(p), count / 2u32);
+        if count % 2 == 1 {
+            free *= p;
+        }
+        p += 1;
+    }
+    let root = rest.sqrt();
+    if &root * &root == rest {
+        square *= root;
+    } else if BigInt::from(p * p) > rest || rest < BigInt::from(TRIAL_DIVISION_LIMIT).pow(3u32) {
+        // Rest is a prime or the product of two distinct primes
+        free *= rest;
+    } else {
+        return None;
+    }
+    return Some((square, free));
+}
+
+fn surd(rational: BigRational, terms: Vec<(BigRational, BigInt)>) -> Number {
+    let mut rational = rational;
+    let mut merged: Vec<(BigRational, BigInt)> = Vec::new();
+    for (c, n) in terms {
+        if n.is_one() {
+            rational += c;
+        } else if let Some(term) = merged.iter_mut().find(|(_, m)| m == &n) {
+            term.0 += c;
+        } else {
+            merged.push((c, n));
+        }
+    }
+    merged.retain(|(c, _)| !c.is_zero());
+    merged.sort_by(|a, b| b.1.cmp(&a.1));
+    if merged.is_empty() {
+        return Number::Rational(rational);
+    } else {
+        return Number::Symbolic(Box::new(Symbolic::Surd(rational, merged)));
+    }
+}
+
+fn as_surd(n: &Number) -> Option<Surd> {
+    match form(n) {
+        Form::Rational(r) => Some((r.clone(), Vec::new())),
+        Form::Symbolic(Symbolic::Surd(r, terms)) => Some((r.clone(), terms.clone())),
+        _ => None,
+    }
+}
+
+fn surd_mul(a: &Surd, b: &Surd) -> Number {
+    let mut terms = Vec::new();
+    let left = std::iter::once((a.0.clone(), BigInt::one())).chain(a.1.iter().cloned());
+    for (c, m) in left {
+        let right = std::iter::once((b.0.clone(), BigInt::one())).chain(b.1.iter().cloned());
+        for (d, n) in right {
+            let g = m.gcd(&n);
+            terms.push((&c * &d * BigRational::from_integer(g.clone()), (&m / &g) * (&n / &g)));
+        }
+    }
+    return surd(BigRational::zero(), terms);
+}
+
+fn surd_inverse(a: &Surd) -> Option<Surd> {
+    match a.1.as_slice() {
+        [] if !a.0.is_zero() => Some((a.0.recip(), Vec::new())),
+        [(c, n)] if a.0.is_zero() => {
+            Some((BigRational::zero(), vec![((c * BigRational::from_integer(n.clone())).recip(), n.clone())]))
+        },
+        [(c, n)] => {
+            // 1 / (r + c sqrt(n)) = (r - c sqrt(n)) / (r^2 - c^2 n)
+            let norm = &a.0 * &a.0 - c * c * BigRational::from_integer(n.clone());
+            Some((&a.0 / &norm, vec![(-c / &norm, n.clone())]))
+        },
+        _ => None,
+    }
+}
+
+fn pi(q: BigRational) -> Number {
+    if q.is_zero() {
+        Number::zero()
+    } else {
+        Number::Symbolic(Box::new(Symbolic::Pi(q)))
+    }
+}
+
+fn pi_pow(c: BigRational, k: i32) -> Number {
+    if k == 0 || c.is_zero() {
+        Number::Rational(c)
+    } else if k == 1 {
+        pi(c)
+    } else {
+        Number::Symbolic(Box::new(Symbolic::PiPow(c, k)))
+    }
+}
+
+// The coefficient and exponent of c * pi^k
+fn as_pi_pow(n: &Number) -> Option<(BigRational, i32)> {
+    match form(n) {
+        Form::Symbolic(Symbolic::Pi(q)) => Some((q.clone(), 1)),
+        Form::Symbolic(Symbolic::PiPow(c, k)) => Some((c.clone(), *k)),
+        _ => None,
+    }
+}
+
+fn exp(c: BigRational, q: BigRational) -> Number {
+    if q.is_zero() || c.is_zero() {
+        Number::Rational(c)
+    } else {
+        Number::Symbolic(Box::new(Symbolic::Exp(c, q)))
+    }
+}
+
+fn perfect_root(r: &BigRational) -> (BigRational, u32) {
+    let mut base = r.clone();
+    let mut power = 1;
+    let mut k = 2;
+    while k as u64 <= base.numer().bits() {
+        let (n, d) = (base.numer().nth_root(k), base.denom().nth_root(k));
+        if &Pow::pow(&n, k) == base.numer() && &Pow::pow(&d, k) == base.denom() {
+            base = BigRational::new(n, d);
+            power *= k;
+        } else {
+            k += 1;
+        }
+    }
+    return (base, power);
+}
+
+// c * ln(r) for r > 0
+fn ln(c: BigRational, r: BigRational) -> Number {
+    let (c, r) = if r < BigRational::one() { (-c, r.recip()) } else { (c, r) };
+    if c.is_zero() || r.is_one() {
+        return Number::zero();
+    }
+    let (base, power) = perfect_root(&r);
+    return Number::Symbolic(Box::new(Symbolic::Ln(c * BigRational::from_integer(BigInt::from(power)), base)));
+}
+
+fn sqrt_rational(r: &BigRational) -> Option<Number> {
+    if r.is_negative() {
+        return None;
+    }
+    let (square, free) = split_square(&(r.numer() * r.denom()))?;
+    return Some(surd(BigRational::zero(), vec![(BigRational::new(square, r.denom().clone()), free)]));
+}
+
+fn negate(n: &Number) -> Number {
+    match form(n) {
+        Form::Rational(r) => Number::Rational(-r),
+        Form::Symbolic(s) => neg(s),
+        Form::Other => Number::Float(-n.to_f64()),
+    }
+}
+
+pub(super) fn neg(s: &Symbolic) -> Number {
+    match s {
+        Symbolic::Surd(r, terms) => surd(-r, terms.iter().map(|(c, n)| (-c, n.clone())).collect()),
+        Symbolic::Pi(q) => pi(-q),
+        Symbolic::PiPow(c, k) => pi_pow(-c, *k),
+        Symbolic::Exp(c, q) => exp(-c, q.clone()),
+        Symbolic::Ln(c, r) => ln(-c, r.clone()),
+        Symbolic::Factors(r, factors) => Number::Symbolic(Box::new(Symbolic::Factors(-r, factors.clone()))),
+    }
+}
+
+pub(super) fn add(a: &Number, b: &Number) -> Option<Number> {
+    if let (Some(x), Some(y)) = (as_surd(a), as_surd(b)) {
+        return Some(surd(x.0 + y.0, x.1.into_iter().chain(y.1).collect()));
+    }
+    match (form(a), form(b)) {
+        (Form::Symbolic(Symbolic::Pi(q)), Form::Symbolic(Symbolic::Pi(r))) => Some(pi(q + r)),
+        (Form::Symbolic(Symbolic::PiPow(c, k)), Form::Symbolic(Symbolic::PiPow(d, l))) if k == l => {
+            Some(pi_pow(c + d, *k))
+        },
+        (Form::Symbolic(Symbolic::Exp(c, q)), Form::Symbolic(Symbolic::Exp(d, r))) if q == r => {
+            Some(exp(c + d, q.clone()))
+        },
+        (Form::Symbolic(Symbolic::Ln(c, r)), Form::Symbolic(Symbolic::Ln(d, s))) => {
+            if r == s {
+                Some(ln(c + d, r.clone()))
+            } else if c == d {
+                Some(ln(c.clone(), r * s))
+            } else {
+                let (m, n) = (c.to_integer().to_i32()?, d.to_integer().to_i32()?);
+                if c.is_integer() && d.is_integer() && m.abs() <= MAX_EXACT_EXPONENT && n.abs() <= MAX_EXACT_EXPONENT {
+                    Some(ln(BigRational::one(), r.pow(m) * s.pow(n)))
+                } else {
+                    None
+                }
+            }
+        },
+        _ => None,
+    }
+}
+
+pub(super) fn sub(a: &Number, b: &Number) -> Option<Number> {
+    add(a, &negate(b))
+}
+
+fn scale(s: &Symbolic, k: &BigRational) -> Number {
+    match s {
+        Symbolic::Surd(r, terms) => surd(r * k, terms.iter().map(|(c, n)| (c * k, n.clone())).collect()),
+        Symbolic::Pi(q) => pi(q * k),
+        Symbolic::PiPow(c, l) => pi_pow(c * k, *l),
+        Symbolic::Exp(c, q) => exp(c * k, q.clone()),
+        Symbolic::Ln(c, r) => ln(c * k, r.clone()),
+        Symbolic::Factors(r, _) => Number::Rational(r * k),
+    }
+}
+
+pub(super) fn mul(a: &Number, b: &Number) -> Option<Number> {
+    if let (Some(x), Some(y)) = (as_surd(a), as_surd(b)) {
+        return Some(surd_mul(&x, &y));
+    }
+    match (form(a), form(b)) {
+        (Form::Rational(k), Form::Symbolic(s)) | (Form::Symbolic(s), Form::Rational(k)) => Some(scale(s, k)),
+        (Form::Symbolic(Symbolic::Exp(c, q)), Form::Symbolic(Symbolic::Exp(d, r))) => Some(exp(c * d, q + r)),
+        _ => {
+            let ((c, k), (d, l)) = (as_pi_pow(a)?, as_pi_pow(b)?);
+            Some(pi_pow(c * d, k.checked_add(l).filter(|k| k.abs() <= MAX_EXACT_EXPONENT)?))
+        },
+    }
+}
+
+pub(super) fn div(a: &Number, b: &Number) -> Option<Number> {
+    if let (Some(x), Some(y)) = (as_surd(a), as_surd(b)) {
+        return Some(surd_mul(&x, &surd_inverse(&y)?));
+    }
+    match (form(a), form(b)) {
+        (Form::Symbolic(s), Form::Rational(k)) => Some(scale(s, &k.recip())),
+        (Form::Symbolic(Symbolic::Ln(c, r)), Form::Symbolic(Symbolic::Ln(d, s))) if r == s => {
+            Some(Number::Rational(c / d))
+        },
+        (Form::Symbolic(Symbolic::Exp(c, q)), Form::Symbolic(Symbolic::Exp(d, r))) => Some(exp(c / d, q - r)),
+        (Form::Rational(k), Form::Symbolic(Symbolic::Exp(d, r))) => Some(exp(k / d, -r)),
+        (Form::Rational(k), Form::Symbolic(Symbolic::Pi(_) | Symbolic::PiPow(..))) => {
+            let (d, l) = as_pi_pow(b)?;
+            Some(pi_pow(k / d, -l))
+        },
+        _ => {
+            let ((c, k), (d, l)) = (as_pi_pow(a)?, as_pi_pow(b)?);
+            Some(pi_pow(c / d, k.checked_sub(l).filter(|k| k.abs() <= MAX_EXACT_EXPONENT)?))
+        },
+    }
+}
+
+fn power(x: &Surd, n: i32) -> Option<Number> {
+    if n.abs() > MAX_EXACT_EXPONENT {
+        return None;
+    }
+    let mut result = Number::one();
+    for _ in 0..n.abs() {
+        result = surd_mul(&as_surd(&result)?, x);
+    }
+    if n < 0 {
+        return Some(surd_mul(&(BigRational::one(), Vec::new()), &surd_inverse(&as_surd(&result)?)?));
+    } else {
+        return Some(result);
+    }
+}
+
+pub(super) fn pow(a: &Number, b: &Number) -> Option<Number> {
+    match (form(a), form(b)) {
+        (Form::Symbolic(Symbolic::Exp(c, q)), Form::Symbolic(Symbolic::Ln(d, r))) if c.is_one() => {
+            Pow::pow(Number::Rational(r.clone()), Number::Rational(q * d)).ok()
+        },
+        (Form::Symbolic(Symbolic::Exp(c, q)), Form::Rational(s)) if c.is_one() => Some(exp(c.clone(), q * s)),
+        (Form::Symbolic(Symbolic::Exp(c, q)), Form::Rational(s)) if s.is_integer() => {
+            Some(exp(c.pow(s.to_integer().to_i32()?), q * s))
+        },
+        (Form::Rational(r), Form::Rational(s)) if s.denom() == &BigInt::from(2) && r.is_positive() => {
+            power(&as_surd(&sqrt_rational(r)?)?, s.numer().to_i32()?)
+        },
+        (Form::Symbolic(Symbolic::Surd(..)), Form::Rational(s)) if s.is_integer() => {
+            power(&as_surd(a)?, s.to_integer().to_i32()?)
+        },
+        (Form::Symbolic(Symbolic::Pi(_) | Symbolic::PiPow(..)), Form::Rational(s)) if s.is_integer() => {
+            let ((c, k), n) = (as_pi_pow(a)?, s.to_integer().to_i32()?);
+            let k = k.checked_mul(n).filter(|k| k.abs() <= MAX_EXACT_EXPONENT)?;
+            Some(pi_pow(Pow::pow(c, n), k))
+        },
+        _ => None,
+    }
+}
+
+pub(super) fn sqrt(x: &Number) -> Option<Number> {
+    match form(x) {
+        Form::Rational(r) => sqrt_rational(r),
+        Form::Symbolic(Symbolic::Exp(c, q)) if c.is_one() => Some(exp(c.clone(), q / BigRational::from_integer(BigInt::from(2)))),
+        Form::Symbolic(Symbolic::PiPow(c, k)) if c.is_one() && k % 2 == 0 => Some(pi_pow(c.clone(), k / 2)),
+        _ => None,
+    }
+}
+
+pub(super) fn ln_of(x: &Number) -> Option<Number> {
+    match form(x) {
+        Form::Rational(r) if r.is_positive() => Some(ln(BigRational::one(), r.clone())),
+        Form::Symbolic(Symbolic::Exp(c, q)) if c.is_one() => Some(Number::Rational(q.clone())),
+        _ => None,
+    }
+}
+
+pub(super) fn log(x: &Number) -> Option<Number> {
+    div(&ln_of(x)?, &ln(BigRational::one(), BigRational::from_integer(BigInt::from(10))))
+}
+
+fn twelfths(x: &Number) -> Option<i64> {
+    match form(x) {
+        Form::Symbolic(Symbolic::Pi(q)) => {
+            let k = q * BigRational::from_integer(BigInt::from(12));
+            if k.is_integer() {
+                return k.to_integer().mod_floor(&BigInt::from(24)).to_i64();
+            } else {
+                return None;
+            }
+        },
+        _ => None,
+    }
+}
+
+fn sin_twelfths(k: i64) -> Number {
+    let k = k.rem_euclid(24);
+    if k >= 12 {
+        return negate(&sin_twelfths(k - 12));
+    }
+    match if k > 6 { 12 - k } else { k } {
+        0 => Number::zero(),
+        1 => surd(BigRational::zero(), vec![(rational(1, 4), BigInt::from(6)), (rational(-1, 4), BigInt::from(2))]),
+        2 => Number::Rational(rational(1, 2)),
+        3 => surd(BigRational::zero(), vec![(rational(1, 2), BigInt::from(2))]),
+        4 => surd(BigRational::zero(), vec![(rational(1, 2), BigInt::from(3))]),
+        5 => surd(BigRational::zero(), vec![(rational(1, 4), BigInt::from(6)), (rational(1, 4), BigInt::from(2))]),
+        _ => Number::one(),
+    }
+}
+
+fn tan_twelfths(k: i64) -> Number {
+    let k = k.rem_euclid(12);
+    if k > 6 {
+        return negate(&tan_twelfths(12 - k));
+    }
+    match k {
+        0 => Number::zero(),
+        1 => surd(rational(2, 1), vec![(rational(-1, 1), BigInt::from(3))]),
+        2 => surd(BigRational::zero(), vec![(rational(1, 3), BigInt::from(3))]),
+        3 => Number::one(),
+        4 => surd(BigRational::zero(), vec![(rational(1, 1), BigInt::from(3))]),
+        5 => surd(rational(2, 1), vec![(rational(1, 1), BigInt::from(3))]),
+        _ => Number::Float(f64::NAN),
+    }
+}
+
+pub(super) fn sin(x: &Number) -> Option<Number> {
+    twelfths(x).map(sin_twelfths)
+}
+
+pub(super) fn cos(x: &Number) -> Option<Number> {
+    twelfths(x).map(|k| sin_twelfths(k + 6))
+}
+
+pub(super) fn tan(x: &Number) -> Option<Number> {
+    twelfths(x).map(tan_twelfths)
+}
+
+fn inverse(x: &Number, range: std::ops::RangeInclusive<i64>, f: fn(i64) -> Number) -> Option<Number> {
+    as_surd(x)?;
+    range.into_iter().find(|k| &f(*k) == x).map(|k| pi(rational(k, 12)))
+}
+
+pub(super) fn asin(x: &Number) -> Option<Number> {
+    inverse(x, -6..=6, sin_twelfths)
+}
+
+pub(super) fn acos(x: &Number) -> Option<Number> {
+    inverse(x, 0..=12, |k| sin_twelfths(k + 6))
+}
+
+pub(super) fn atan(x: &Number) -> Option<Number> {
+    inverse(x, -5..=5, tan_twelfths)
+}
+
+impl Symbolic {
+    // The value of symbolic numbers that are rational, i.e. factorizations
+    pub fn exact(&self) -> Option<&BigRational> {
+        match self {
+            Symbolic::Factors(r, _) => Some(r),
+            _ => None,
+        }
+    }
+
+    pub fn to_big_float(&self, precision: u32) -> Option<BigFloat> {
+        let working = precision + 32;
+        let value = match self {
+            Symbolic::Surd(r, terms) => {
+                let mut sum = r.clone();
+                for (c, n) in terms {
+                    let root = BigFloat::from_rational(&BigRational::from_integer(n.clone()), working).sqrt()?;
+                    sum += c * root.to_rational();
+                }
+                sum
+            },
+            Symbolic::Pi(q) => q * BigFloat::pi(working).to_rational(),
+            Symbolic::PiPow(c, k) => c * Pow::pow(BigFloat::pi(working).to_rational(), *k),
+            Symbolic::Exp(c, q) => c * BigFloat::from_rational(q, working).exp()?.to_rational(),
+            Symbolic::Ln(c, r) => c * BigFloat::from_rational(r, working).ln()?.to_rational(),
+            Symbolic::Factors(r, _) => r.clone(),
+        };
+        return Some(BigFloat::from_rational(&value, precision));
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        match self.to_big_float(64) {
+            Some(b) => b.to_f64(),
+            None => match self {
+                Symbolic::Exp(c, q) => c.to_f64().unwrap_or(f64::NAN) * q.to_f64().unwrap_or(f64::NAN).exp(),
+                _ => f64::NAN,
+            },
+        }
+    }
+
+    pub fn signum(&self) -> Ordering {
+        self.to_f64().partial_cmp(&0.0).unwrap_or(Ordering::Equal)
+    }
+}
+
+fn times(c: &BigRational, symbol: &str) -> String {
+    let numer = if c.numer().is_one() {
+        symbol.to_owned()
+    } else if c.numer() == &-BigInt::one() {
+        format!("-{}", symbol)
+    } else {
+        format!("{} {}", c.numer(), symbol)
+    };
+    if c.denom().is_one() {
+        return numer;
+    } else {
+        return format!("{}/{}", numer, c.denom());
+    }
+}
+
+impl ToString for Symbolic {
+    fn to_string(&self) -> String {
+        match self {
+            Symbolic::Surd(r, terms) => {
+                let mut res = if r.is_zero() { String::new() } else { r.to_string() };
+                for (c, n) in terms {
+                    let term = times(c, &format!("sqrt({})", n));
+                    if res.is_empty() {
+                        res = term;
+                    } else if let Some(positive) = term.strip_prefix('-') {
+                        res = format!("{} - {}", res, positive);
+                    } else {
+                        res = format!("{} + {}", res, term);
+                    }
+                }
+                res
+            },
+            Symbolic::Pi(q) => times(q, "pi"),
+            Symbolic::PiPow(c, k) => {
+                if *k > 0 {
+                    times(c, &format!("pi^{}", k))
+                } else {
+                    times(c, &format!("pi^({})", k))
+                }
+            },
+            Symbolic::Exp(c, q) => {
+                if q.is_one() {
+                    times(c, "e")
+                } else if q.is_integer() && q.is_positive() {
+                    times(c, &format!("e^{}", q))
+                } else {
+                    times(c, &format!("e^({})", q))
+                }
+            },
+            Symbolic::Ln(c, r) => times(c, &format!("ln({})", r)),
+            Symbolic::Factors(r, factors) => {
+                let product = factors.iter()
+                    .map(|(p, k)| if *k == 1 { p.to_string() } else { format!("{}^{}", p, k) })
+                    .collect::<Vec<_>>()
+                    .join(" * ");
+                if r.is_negative() {
+                    format!("-{}", product)
+                } else {
+                    product
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,450 @@
+
+use num::*;
+
+use super::bigfloat::{BigFloat, Dyadic, shr};
+
+// Fixed point kernels work on integers scaled by 2^scale and are accurate to a few units in the last place.
+
+// Arguments beyond 2^MAX_MAGNITUDE overflow or underflow exp and are treated as out of range
+const MAX_MAGNITUDE: i64 = 40;
+
+fn guard(scale: u64) -> u64 {
+    16 + (64 - scale.leading_zeros() as u64)
+}
+
+fn one(scale: u64) -> BigInt {
+    BigInt::one() << scale
+}
+
+fn mul(a: &BigInt, b: &BigInt, scale: u64) -> BigInt {
+    shr(&(a * b), scale)
+}
+
+fn div(a: &BigInt, b: &BigInt, scale: u64) -> BigInt {
+    (a << scale) / b
+}
+
+// Sum of x^(2k+1) / (2k+1), with alternating signs unless hyperbolic
+fn atan_series(x: &BigInt, scale: u64, hyperbolic: bool) -> BigInt {
+    let square = mul(x, x, scale);
+    let mut power = x.clone();
+    let mut sum = BigInt::zero();
+    let mut k = 0u64;
+    while !power.is_zero() {
+        let term = &power / (2 * k + 1);
+        if !hyperbolic && k % 2 == 1 {
+            sum -= term;
+        } else {
+            sum += term;
+        }
+        power = mul(&power, &square, scale);
+        k += 1;
+    }
+    return sum;
+}
+
+// atan(1/n) or atanh(1/n)
+fn inverse_series(n: u64, scale: u64, hyperbolic: bool) -> BigInt {
+    let square = n * n;
+    let mut power = one(scale) / n;
+    let mut sum = BigInt::zero();
+    let mut k = 0u64;
+    while !power.is_zero() {
+        let term = &power / (2 * k + 1);
+        if !hyperbolic && k % 2 == 1 {
+            sum -= term;
+        } else {
+            sum += term;
+        }
+        power /= square;
+        k += 1;
+    }
+    return sum;
+}
+
+fn pi_fixed(scale: u64) -> BigInt {
+    let g = guard(scale);
+    let pi = 16 * inverse_series(5, scale + g, false) - 4 * inverse_series(239, scale + g, false);
+    return shr(&pi, g);
+}
+
+fn ln2_fixed(scale: u64) -> BigInt {
+    let g = guard(scale);
+    return shr(&(2 * inverse_series(3, scale + g, true)), g);
+}
+
+// exp(x) for |x| <= 1
+fn exp_fixed(x: &BigInt, scale: u64) -> BigInt {
+    let mut sum = one(scale);
+    let mut term = one(scale);
+    let mut k = 1u64;
+    while !term.is_zero() {
+        term = mul(&term, x, scale) / k;
+        sum += &term;
+        k += 1;
+    }
+    return sum;
+}
+
+// (sin(x), cos(x)) for |x| <= 1
+fn sin_cos_fixed(x: &BigInt, scale: u64) -> (BigInt, BigInt) {
+    let square = mul(x, x, scale);
+    let mut sin = x.clone();
+    let mut cos = one(scale);
+    let mut sin_term = x.clone();
+    let mut cos_term = one(scale);
+    let mut k = 1u64;
+    while !sin_term.is_zero() || !cos_term.is_zero() {
+        sin_term = -mul(&sin_term, &square, scale) / ((2 * k) * (2 * k + 1));
+        cos_term = -mul(&cos_term, &square, scale) / ((2 * k - 1) * (2 * k));
+        sin += &sin_term;
+        cos += &cos_term;
+        k += 1;
+    }
+    return (sin, cos);
+}
+
+// atan(x) for |x| < 2, halving the argument twice to speed up the series
+fn atan_fixed(x: &BigInt, scale: u64) -> BigInt {
+    let mut x = x.clone();
+    for _ in 0..2 {
+        let root = ((one(scale) + mul(&x, &x, scale)) << scale).sqrt();
+        x = div(&x, &(one(scale) + root), scale);
+    }
+    return 4 * atan_series(&x, scale, false);
+}
+
+// Extra bits needed so that an absolute error bound still gives full relative precision for small results
+fn boost(x: &Dyadic) -> u64 {
+    (-x.magnitude()).clamp(0, 1 << 20) as u64
+}
+
+fn abs_error(bits: i64, scale: u64) -> Dyadic {
+    Dyadic::power_of_two(bits - scale as i64)
+}
+
+// Ziv's strategy: refine the working precision until both ends of the error bound round the same way
+fn ziv<F>(precision: u32, f: F) -> Option<BigFloat>
+where F: Fn(u64) -> Option<(Dyadic, Dyadic)>
+{
+    let target = precision as u64;
+    let mut extra = 32;
+    loop {
+        let (approx, error) = f(target + extra)?;
+        let lo = approx.sub(&error).round(target);
+        let hi = approx.add(&error).round(target);
+        if lo == hi || extra > 16 * target + 4096 {
+            return Some(BigFloat::from_dyadic(approx, precision));
+        }
+        extra *= 2;
+    }
+}
+
+fn pi(scale: u64) -> Dyadic {
+    let g = guard(scale);
+    return Dyadic::from_fixed(pi_fixed(scale + g), scale + g);
+}
+
+// exp(x) with a relative error below 2^(8 - scale)
+fn exp(x: &Dyadic, scale: u64) -> Option<Dyadic> {
+    if x.magnitude() > MAX_MAGNITUDE {
+        return None;
+    }
+    let g = scale + guard(scale) + x.magnitude().max(0) as u64;
+    let ln2 = ln2_fixed(g);
+    let x = x.to_fixed(g);
+    let k: BigInt = Integer::div_floor(&(2 * &x + &ln2), &(2 * &ln2));
+    let r = x - &k * ln2;
+    let exponent = k.to_i64()?;
+    return Some(Dyadic::from_fixed(exp_fixed(&r, g), g).scale(exponent));
+}
+
+// ln(x) for x > 0 with an absolute error below 2^(8 - scale)
+fn ln(x: &Dyadic, scale: u64) -> Dyadic {
+    let g = scale + guard(scale);
+    let mut exponent = x.magnitude();
+    let mut y = x.scale(-exponent).to_fixed(g);
+    if 2 * &y * &y < one(2 * g) {
+        y <<= 1;
+        exponent -= 1;
+    }
+    let z = div(&(&y - one(g)), &(&y + one(g)), g);
+    let extra = 64 - exponent.unsigned_abs().leading_zeros() as u64;
+    let ln2 = shr(&(exponent * ln2_fixed(g + extra)), extra);
+    return Dyadic::from_fixed(ln2 + 2 * atan_series(&z, g, true), g);
+}
+
+// (sin(x), cos(x)) with an absolute error below 2^(4 - scale)
+fn sin_cos(x: &Dyadic, scale: u64) -> (Dyadic, Dyadic) {
+    let g = scale + guard(scale) + x.magnitude().max(0) as u64;
+    let half_pi = pi_fixed(g) >> 1;
+    let x = x.to_fixed(g);
+    let q: BigInt = Integer::div_floor(&(2 * &x + &half_pi), &(2 * &half_pi));
+    let r = x - &q * half_pi;
+    let (s, c) = sin_cos_fixed(&r, g);
+    let (s, c) = match q.mod_floor(&BigInt::from(4)).to_u8() {
+        Some(0) => (s, c),
+        Some(1) => (c, -s),
+        Some(2) => (-s, -c),
+        _ => (-c, s),
+    };
+    return (Dyadic::from_fixed(s, g), Dyadic::from_fixed(c, g));
+}
+
+// atan(x) with an absolute error below 2^(8 - scale)
+fn atan(x: &Dyadic, scale: u64) -> Dyadic {
+    let g = scale + guard(scale);
+    if x.magnitude() > 1 {
+        let half_pi = pi(g).scale(-1);
+        let inner = Dyadic::from_fixed(atan_fixed(&Dyadic::one().div(x, g).to_fixed(g), g), g);
+        if x.is_negative() {
+            return half_pi.neg().sub(&inner);
+        } else {
+            return half_pi.sub(&inner);
+        }
+    } else {
+        return Dyadic::from_fixed(atan_fixed(&x.to_fixed(g), g), g);
+    }
+}
+
+fn unit(negative: bool) -> Dyadic {
+    if negative { Dyadic::one().neg() } else { Dyadic::one() }
+}
+
+impl BigFloat {
+    pub fn pi(precision: u32) -> BigFloat {
+        let precision = precision.max(1);
+        return ziv(precision, |w| Some((pi(w), abs_error(4, w)))).unwrap();
+    }
+
+    pub fn e(precision: u32) -> BigFloat {
+        let precision = precision.max(1);
+        return ziv(precision, |w| {
+            let g = w + guard(w);
+            Some((Dyadic::from_fixed(exp_fixed(&one(g), g), g), abs_error(4, w)))
+        }).unwrap();
+    }
+
+    pub fn exp(&self) -> Option<BigFloat> {
+        let x = self.dyadic();
+        ziv(self.precision(), |w| {
+            let w = w + boost(x);
+            let y = exp(x, w)?;
+            let error = Dyadic::power_of_two(y.magnitude() + 8 - w as i64);
+            Some((y, error))
+        })
+    }
+
+    pub fn ln(&self) -> Option<BigFloat> {
+        let x = self.dyadic();
+        if x.is_negative() || x.is_zero() {
+            return None;
+        }
+        ziv(self.precision(), |w| {
+            let w = w + boost(&x.sub(&Dyadic::one()));
+            Some((ln(x, w), abs_error(8, w)))
+        })
+    }
+
+    pub fn log(&self) -> Option<BigFloat> {
+        let x = self.dyadic();
+        if x.is_negative() || x.is_zero() {
+            return None;
+        }
+        ziv(self.precision(), |w| {
+            let w = w + boost(&x.sub(&Dyadic::one()));
+            let ten = Dyadic::new(BigInt::from(10), 0);
+            let y = ln(x, w).div(&ln(&ten, w), w);
+            Some((y.clone(), abs_error(10 + y.magnitude().max(1), w)))
+        })
+    }
+
+    pub fn pow(&self, exponent: &BigFloat) -> Option<BigFloat> {
+        let (x, y) = (self.dyadic(), exponent.dyadic());
+        let precision = self.precision().max(exponent.precision());
+        if x.is_zero() {
+            return if y.is_negative() { None } else { Some(BigFloat::from_dyadic(Dyadic::zero(), precision)) };
+        } else if x.is_negative() && !y.is_integer() {
+            return None;
+        }
+        if let Some(n) = exponent.to_rational().to_integer().to_i32() {
+            if y.is_integer() && (n.unsigned_abs() as u64) * x.bits() <= 4 * precision as u64 + 4096 {
+                let power = x.powi(n.unsigned_abs());
+                if n < 0 {
+                    return Some(BigFloat::from_dyadic(Dyadic::one().div(&power, precision as u64), precision));
+                } else {
+                    return Some(BigFloat::from_dyadic(power, precision));
+                }
+            }
+        }
+        let negative = x.is_negative() && y.to_rational().to_integer().is_odd();
+        let x = x.abs();
+        ziv(precision, |w| {
+            let w = w + y.magnitude().max(0) as u64;
+            let t = y.mul(&ln(&x, w));
+            let r = exp(&t, w)?;
+            let error = Dyadic::power_of_two(r.magnitude() + 10 + y.magnitude().max(0) - w as i64);
+            Some((if negative { r.neg() } else { r }, error))
+        })
+    }
+
+    pub fn sin(&self) -> Option<BigFloat> {
+        let x = self.dyadic();
+        ziv(self.precision(), |w| {
+            let w = w + boost(x);
+            Some((sin_cos(x, w).0, abs_error(4, w)))
+        })
+    }
+
+    pub fn cos(&self) -> Option<BigFloat> {
+        let x = self.dyadic();
+        ziv(self.precision(), |w| Some((sin_cos(x, w).1, abs_error(4, w))))
+    }
+
+    pub fn tan(&self) -> Option<BigFloat> {
+        let x = self.dyadic();
+        ziv(self.precision(), |w| {
+            let w = w + boost(x);
+            let (s, c) = sin_cos(x, w);
+            if c.is_zero() {
+                return Some((Dyadic::zero(), Dyadic::power_of_two(MAX_MAGNITUDE)));
+            }
+            let t = s.div(&c, w + 8);
+            // d(s/c) = ds / c - s dc / c^2
+            let spread = Dyadic::one().div(&c.abs(), 32).add(&s.abs().div(&c.mul(&c), 32));
+            let error = spread.mul(&abs_error(6, w)).add(&Dyadic::power_of_two(t.magnitude() - w as i64));
+            Some((t, error))
+        })
+    }
+
+    pub fn asin(&self) -> Option<BigFloat> {
+        let x = self.dyadic();
+        if x.abs() == Dyadic::one() {
+            return ziv(self.precision(), |w| {
+                let half_pi = pi(w).scale(-1);
+                Some((if x.is_negative() { half_pi.neg() } else { half_pi }, abs_error(4, w)))
+            });
+        } else if x.magnitude() > 0 {
+            return None;
+        }
+        ziv(self.precision(), |w| {
+            let w = w + boost(x);
+            let t = x.div(&Dyadic::one().sub(&x.mul(x)).sqrt(w + 8), w + 8);
+            Some((atan(&t, w), abs_error(9, w)))
+        })
+    }
+
+    pub fn acos(&self) -> Option<BigFloat> {
+        let x = self.dyadic();
+        if x.abs() == Dyadic::one() {
+            return ziv(self.precision(), |w| {
+                if x.is_negative() { Some((pi(w), abs_error(4, w))) } else { Some((Dyadic::zero(), Dyadic::zero())) }
+            });
+        } else if x.magnitude() > 0 {
+            return None;
+        }
+        ziv(self.precision(), |w| {
+            let w = w + boost(&Dyadic::one().sub(&x.abs()));
+            let t = x.div(&Dyadic::one().sub(&x.mul(x)).sqrt(w + 8), w + 8);
+            Some((pi(w).scale(-1).sub(&atan(&t, w)), abs_error(10, w)))
+        })
+    }
+
+    pub fn atan(&self) -> Option<BigFloat> {
+        let x = self.dyadic();
+        ziv(self.precision(), |w| {
+            let w = w + boost(x);
+            Some((atan(x, w), abs_error(8, w)))
+        })
+    }
+
+    pub fn atan2(&self, other: &BigFloat) -> Option<BigFloat> {
+        let (y, x) = (self.dyadic(), other.dyadic());
+        let precision = self.precision().max(other.precision());
+        ziv(precision, |w| {
+            if x.is_zero() {
+                let half_pi = pi(w).scale(-1);
+                return Some((if y.is_negative() { half_pi.neg() } else { half_pi }, abs_error(4, w)));
+            }
+            let w = w + boost(&y.div(x, 32));
+            let angle = atan(&y.div(x, w + 8), w);
+            if !x.is_negative() {
+                Some((angle, abs_error(9, w)))
+            } else if y.is_negative() {
+                Some((angle.sub(&pi(w)), abs_error(10, w)))
+            } else {
+                Some((angle.add(&pi(w)), abs_error(10, w)))
+            }
+        })
+    }
+
+    pub fn sinh(&self) -> Option<BigFloat> {
+        let x = self.dyadic();
+        ziv(self.precision(), |w| {
+            let w = w + boost(x);
+            let a = exp(x, w)?;
+            let b = Dyadic::one().div(&a, w + 8);
+            let error = Dyadic::power_of_two(a.add(&b).magnitude() + 10 - w as i64);
+            Some((a.sub(&b).scale(-1), error))
+        })
+    }
+
+    pub fn cosh(&self) -> Option<BigFloat> {
+        let x = self.dyadic();
+        ziv(self.precision(), |w| {
+            let a = exp(x, w)?;
+            let b = Dyadic::one().div(&a, w + 8);
+            let sum = a.add(&b);
+            let error = Dyadic::power_of_two(sum.magnitude() + 10 - w as i64);
+            Some((sum.scale(-1), error))
+        })
+    }
+
+    pub fn tanh(&self) -> Option<BigFloat> {
+        let x = self.dyadic();
+        if x.magnitude() >= MAX_MAGNITUDE {
+            return Some(BigFloat::from_dyadic(unit(x.is_negative()), self.precision()));
+        }
+        ziv(self.precision(), |w| {
+            let w = w + boost(x);
+            let a = exp(&x.scale(1), w)?;
+            let t = a.sub(&Dyadic::one()).div(&a.add(&Dyadic::one()), w + 8);
+            Some((t, abs_error(10, w)))
+        })
+    }
+
+    pub fn asinh(&self) -> Option<BigFloat> {
+        let x = self.dyadic();
+        ziv(self.precision(), |w| {
+            let w = w + boost(x);
+            let root = x.mul(x).add(&Dyadic::one()).sqrt(w + 8);
+            let y = ln(&x.abs().add(&root), w);
+            Some((if x.is_negative() { y.neg() } else { y }, abs_error(9, w)))
+        })
+    }
+
+    pub fn acosh(&self) -> Option<BigFloat> {
+        let x = self.dyadic();
+        let distance = x.sub(&Dyadic::one());
+        if distance.is_negative() {
+            return None;
+        }
+        ziv(self.precision(), |w| {
+            let w = w + boost(&distance);
+            let root = x.mul(x).sub(&Dyadic::one()).sqrt(w + 8);
+            Some((ln(&x.add(&root), w), abs_error(9, w)))
+        })
+    }
+
+    pub fn atanh(&self) -> Option<BigFloat> {
+        let x = self.dyadic();
+        if x.magnitude() > 0 || x.abs() == Dyadic::one() {
+            return None;
+        }
+        ziv(self.precision(), |w| {
+            let w = w + boost(x);
+            let q = Dyadic::one().add(x).div(&Dyadic::one().sub(x), w + 8);
+            Some((ln(&q, w).scale(-1), abs_error(9, w)))
+        })
+    }
+}
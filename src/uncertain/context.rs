@@ -0,0 +1,124 @@
+
+use std::collections::HashMap;
+use std::f64::consts;
+use std::rc::Rc;
+
+use crate::Context;
+use crate::ContextFn;
+use crate::FunctionDefinition;
+use crate::DEFAULT_RECURSION_LIMIT;
+use crate::EvalError;
+use crate::EvalErrorKind;
+
+use super::Uncertain;
+
+pub struct UncertainContext {
+    vars: HashMap<String, Uncertain>,
+    funcs: HashMap<String, Rc<ContextFn<Uncertain>>>,
+    defs: HashMap<String, Rc<FunctionDefinition>>,
+    recursion_limit: usize,
+}
+
+fn check_length(args: &[Uncertain], min: usize, max: usize) -> Result<&[Uncertain], EvalError> {
+    if args.len() < min {
+        return Err(EvalError::new(EvalErrorKind::ArgumentMismatch, "Too few arguments to function"));
+    } else if args.len() > max {
+        return Err(EvalError::new(EvalErrorKind::ArgumentMismatch, "Too many arguments to function"));
+    } else {
+        return Ok(args);
+    }
+}
+
+fn min(args: &[Uncertain]) -> Result<Uncertain, EvalError> {
+    let args = check_length(args, 1, usize::MAX)?;
+    return Ok(args.iter().reduce(|a, b| if b.mean() < a.mean() { b } else { a }).unwrap().clone());
+}
+
+fn max(args: &[Uncertain]) -> Result<Uncertain, EvalError> {
+    let args = check_length(args, 1, usize::MAX)?;
+    return Ok(args.iter().reduce(|a, b| if b.mean() > a.mean() { b } else { a }).unwrap().clone());
+}
+
+impl UncertainContext {
+    pub fn new() -> UncertainContext {
+        let mut res = UncertainContext {
+            vars: HashMap::new(), funcs: HashMap::new(), defs: HashMap::new(), recursion_limit: DEFAULT_RECURSION_LIMIT
+        };
+        res.set_variable("pi", Uncertain::exact(consts::PI));
+        res.set_variable("e", Uncertain::exact(consts::E));
+        res.set_function("floor", Box::new(|v| check_length(v, 1, 1)?[0].floor()));
+        res.set_function("ceil", Box::new(|v| check_length(v, 1, 1)?[0].ceil()));
+        res.set_function("round", Box::new(|v| check_length(v, 1, 1)?[0].round()));
+        res.set_function("trunc", Box::new(|v| check_length(v, 1, 1)?[0].trunc()));
+        res.set_function("fract", Box::new(|v| check_length(v, 1, 1)?[0].fract()));
+        res.set_function("abs", Box::new(|v| check_length(v, 1, 1)?[0].abs()));
+        res.set_function("sign", Box::new(|v| check_length(v, 1, 1)?[0].sign()));
+        res.set_function("sqrt", Box::new(|v| check_length(v, 1, 1)?[0].sqrt()));
+        res.set_function("ln", Box::new(|v| check_length(v, 1, 1)?[0].ln()));
+        res.set_function("log", Box::new(|v| check_length(v, 1, 1)?[0].log()));
+        res.set_function("cbrt", Box::new(|v| check_length(v, 1, 1)?[0].cbrt()));
+        res.set_function("sin", Box::new(|v| check_length(v, 1, 1)?[0].sin()));
+        res.set_function("cos", Box::new(|v| check_length(v, 1, 1)?[0].cos()));
+        res.set_function("tan", Box::new(|v| check_length(v, 1, 1)?[0].tan()));
+        res.set_function("asin", Box::new(|v| check_length(v, 1, 1)?[0].asin()));
+        res.set_function("acos", Box::new(|v| check_length(v, 1, 1)?[0].acos()));
+        res.set_function("atan", Box::new(|v| check_length(v, 1, 1)?[0].atan()));
+        res.set_function("atan2", Box::new(|v| {
+            let v = check_length(v, 2, 2)?; v[0].atan2(&v[1])
+        }));
+        res.set_function("sinh", Box::new(|v| check_length(v, 1, 1)?[0].sinh()));
+        res.set_function("cosh", Box::new(|v| check_length(v, 1, 1)?[0].cosh()));
+        res.set_function("tanh", Box::new(|v| check_length(v, 1, 1)?[0].tanh()));
+        res.set_function("asinh", Box::new(|v| check_length(v, 1, 1)?[0].asinh()));
+        res.set_function("acosh", Box::new(|v| check_length(v, 1, 1)?[0].acosh()));
+        res.set_function("atanh", Box::new(|v| check_length(v, 1, 1)?[0].atanh()));
+        res.set_function("min", Box::new(min));
+        res.set_function("max", Box::new(max));
+        res.set_function("mean", Box::new(|v| Ok(Uncertain::exact(check_length(v, 1, 1)?[0].mean()))));
+        res.set_function("sd", Box::new(|v| Ok(Uncertain::exact(check_length(v, 1, 1)?[0].std_dev()))));
+        res.set_function("corr", Box::new(|v| {
+            let v = check_length(v, 2, 2)?; Ok(Uncertain::exact(v[0].correlation(&v[1])))
+        }));
+        return res;
+    }
+
+    pub fn set_input(&mut self, name: &str, mean: f64, std_dev: f64) {
+        self.set_variable(name, Uncertain::named(name, mean, std_dev));
+    }
+
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.recursion_limit = limit;
+    }
+}
+
+impl Context<Uncertain> for UncertainContext {
+    fn set_variable(&mut self, name: &str, value: Uncertain) {
+        self.vars.insert(name.to_owned(), value);
+    }
+
+    fn set_function(&mut self, name: &str, value: Box<ContextFn<Uncertain>>) {
+        self.defs.remove(name);
+        self.funcs.insert(name.to_owned(), Rc::from(value));
+    }
+
+    fn get_variable(&self, name: &str) -> Option<Uncertain> {
+        return self.vars.get(name).cloned();
+    }
+
+    fn get_function(&self, name: &str) -> Option<Rc<ContextFn<Uncertain>>> {
+        return self.funcs.get(name).cloned();
+    }
+
+    fn set_definition(&mut self, name: &str, definition: Rc<FunctionDefinition>) {
+        self.funcs.remove(name);
+        self.defs.insert(name.to_owned(), definition);
+    }
+
+    fn get_definition(&self, name: &str) -> Option<Rc<FunctionDefinition>> {
+        return self.defs.get(name).cloned();
+    }
+
+    fn recursion_limit(&self) -> usize {
+        self.recursion_limit
+    }
+}
@@ -0,0 +1,114 @@
+
+use std::f64::consts::LN_10;
+
+use crate::EvalError;
+
+use super::Uncertain;
+
+impl Uncertain {
+    fn apply(&self, f: fn(f64) -> f64, derivative: fn(f64) -> f64) -> Result<Uncertain, EvalError> {
+        if self.is_exact() {
+            return Uncertain::propagate(f(self.mean()), &[]);
+        } else {
+            return Uncertain::propagate(f(self.mean()), &[(derivative(self.mean()), self)]);
+        }
+    }
+
+    pub fn floor(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::floor, |_| 0.0)
+    }
+
+    pub fn ceil(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::ceil, |_| 0.0)
+    }
+
+    pub fn round(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::round, |_| 0.0)
+    }
+
+    pub fn trunc(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::trunc, |_| 0.0)
+    }
+
+    pub fn fract(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::fract, |_| 1.0)
+    }
+
+    pub fn abs(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::abs, f64::signum)
+    }
+
+    pub fn sign(&self) -> Result<Uncertain, EvalError> {
+        self.apply(|x| if x == 0.0 { 0.0 } else { x.signum() }, |_| 0.0)
+    }
+
+    pub fn sqrt(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::sqrt, |x| 0.5 / x.sqrt())
+    }
+
+    pub fn cbrt(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::cbrt, |x| 1.0 / (3.0 * x.cbrt().powi(2)))
+    }
+
+    pub fn ln(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::ln, |x| 1.0 / x)
+    }
+
+    pub fn log(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::log10, |x| 1.0 / (x * LN_10))
+    }
+
+    pub fn sin(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::sin, f64::cos)
+    }
+
+    pub fn cos(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::cos, |x| -x.sin())
+    }
+
+    pub fn tan(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::tan, |x| 1.0 / x.cos().powi(2))
+    }
+
+    pub fn asin(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::asin, |x| 1.0 / (1.0 - x * x).sqrt())
+    }
+
+    pub fn acos(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::acos, |x| -1.0 / (1.0 - x * x).sqrt())
+    }
+
+    pub fn atan(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::atan, |x| 1.0 / (1.0 + x * x))
+    }
+
+    pub fn atan2(&self, x: &Uncertain) -> Result<Uncertain, EvalError> {
+        let (a, b) = (self.mean(), x.mean());
+        let r = a * a + b * b;
+        return Uncertain::propagate(a.atan2(b), &[(b / r, self), (-a / r, x)]);
+    }
+
+    pub fn sinh(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::sinh, f64::cosh)
+    }
+
+    pub fn cosh(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::cosh, f64::sinh)
+    }
+
+    pub fn tanh(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::tanh, |x| 1.0 / x.cosh().powi(2))
+    }
+
+    pub fn asinh(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::asinh, |x| 1.0 / (x * x + 1.0).sqrt())
+    }
+
+    pub fn acosh(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::acosh, |x| 1.0 / (x * x - 1.0).sqrt())
+    }
+
+    pub fn atanh(&self) -> Result<Uncertain, EvalError> {
+        self.apply(f64::atanh, |x| 1.0 / (1.0 - x * x))
+    }
+}
@@ -0,0 +1,7 @@
+
+mod uncertain;
+mod context;
+mod functions;
+
+pub use uncertain::Uncertain;
+pub use context::UncertainContext;
@@ -0,0 +1,218 @@
+
+use std::ops::*;
+use std::str::FromStr;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use num::traits::Pow;
+
+use crate::{Number, Value, EvalError, EvalErrorKind};
+
+use super::UncertainContext;
+
+static NEXT_SOURCE: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct Uncertain {
+    mean: f64,
+    // Contribution of each independent error source to the standard deviation
+    terms: BTreeMap<String, f64>,
+}
+
+impl Uncertain {
+    pub fn exact(mean: f64) -> Uncertain {
+        Uncertain { mean, terms: BTreeMap::new() }
+    }
+
+    pub fn independent(mean: f64, std_dev: f64) -> Uncertain {
+        let source = format!("#{}", NEXT_SOURCE.fetch_add(1, AtomicOrdering::Relaxed));
+        return Uncertain::named(&source, mean, std_dev);
+    }
+
+    pub fn named(name: &str, mean: f64, std_dev: f64) -> Uncertain {
+        let mut terms = BTreeMap::new();
+        if std_dev != 0.0 {
+            terms.insert(name.to_owned(), std_dev);
+        }
+        return Uncertain { mean, terms };
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.terms.values().map(|c| c * c).sum::<f64>().sqrt()
+    }
+
+    pub fn is_exact(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn covariance(&self, other: &Uncertain) -> f64 {
+        self.terms.iter()
+            .filter_map(|(source, c)| other.terms.get(source).map(|d| c * d))
+            .sum()
+    }
+
+    pub fn correlation(&self, other: &Uncertain) -> f64 {
+        self.covariance(other) / (self.std_dev() * other.std_dev())
+    }
+
+    pub(super) fn propagate(mean: f64, parts: &[(f64, &Uncertain)]) -> Result<Uncertain, EvalError> {
+        let mut terms = BTreeMap::new();
+        for (derivative, value) in parts {
+            for (source, c) in &value.terms {
+                *terms.entry(source.clone()).or_insert(0.0) += derivative * c;
+            }
+        }
+        terms.retain(|_, c| *c != 0.0);
+        if mean.is_nan() || terms.values().any(|c| c.is_nan()) {
+            return Err(EvalError::new(EvalErrorKind::MathError, "NaN"));
+        } else {
+            return Ok(Uncertain { mean, terms });
+        }
+    }
+}
+
+impl ToString for Uncertain {
+    fn to_string(&self) -> String {
+        if self.is_exact() {
+            self.mean.to_string()
+        } else {
+            format!("{} ± {}", self.mean, self.std_dev())
+        }
+    }
+}
+
+impl FromStr for Uncertain {
+    type Err = EvalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Uncertain::exact(Number::from_str(s)?.to_f64()))
+    }
+}
+
+impl PartialOrd for Uncertain {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.mean.partial_cmp(&other.mean)
+    }
+}
+
+impl Neg for Uncertain {
+    type Output = Result<Uncertain, EvalError>;
+
+    fn neg(self) -> Self::Output {
+        Uncertain::propagate(-self.mean, &[(-1.0, &self)])
+    }
+}
+
+impl Add for Uncertain {
+    type Output = Result<Uncertain, EvalError>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Uncertain::propagate(self.mean + rhs.mean, &[(1.0, &self), (1.0, &rhs)])
+    }
+}
+
+impl Sub for Uncertain {
+    type Output = Result<Uncertain, EvalError>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Uncertain::propagate(self.mean - rhs.mean, &[(1.0, &self), (-1.0, &rhs)])
+    }
+}
+
+impl Mul for Uncertain {
+    type Output = Result<Uncertain, EvalError>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Uncertain::propagate(self.mean * rhs.mean, &[(rhs.mean, &self), (self.mean, &rhs)])
+    }
+}
+
+impl Div for Uncertain {
+    type Output = Result<Uncertain, EvalError>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.mean == 0.0 {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Division by zero"));
+        } else {
+            let mean = self.mean / rhs.mean;
+            return Uncertain::propagate(mean, &[(1.0 / rhs.mean, &self), (-mean / rhs.mean, &rhs)]);
+        }
+    }
+}
+
+impl Pow<Uncertain> for Uncertain {
+    type Output = Result<Uncertain, EvalError>;
+
+    fn pow(self, rhs: Uncertain) -> Self::Output {
+        if self.mean == 0.0 && rhs.mean == 0.0 {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Zero to the power of zero"));
+        }
+        let mean = self.mean.powf(rhs.mean);
+        let mut parts = Vec::new();
+        if !self.is_exact() {
+            parts.push((rhs.mean * self.mean.powf(rhs.mean - 1.0), &self));
+        }
+        if !rhs.is_exact() {
+            parts.push((mean * self.mean.ln(), &rhs));
+        }
+        return Uncertain::propagate(mean, &parts);
+    }
+}
+
+impl Value for Uncertain {
+    type DefaultContext = UncertainContext;
+
+    fn default_context() -> Self::DefaultContext {
+        UncertainContext::new()
+    }
+
+    fn parse_literal(literal: &str) -> Result<Self, EvalError> {
+        Uncertain::from_str(literal)
+    }
+
+    fn add(self, other: Self) -> Result<Self, EvalError> {
+        self + other
+    }
+
+    fn sub(self, other: Self) -> Result<Self, EvalError> {
+        self - other
+    }
+
+    fn mul(self, other: Self) -> Result<Self, EvalError> {
+        self * other
+    }
+
+    fn div(self, other: Self) -> Result<Self, EvalError> {
+        self / other
+    }
+
+    fn pow(self, other: Self) -> Result<Self, EvalError> {
+        Pow::pow(self, other)
+    }
+
+    fn neg(self) -> Result<Self, EvalError> {
+        -self
+    }
+
+    fn plus_minus(self, error: Self) -> Result<Self, EvalError> {
+        if !error.is_exact() {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Uncertainties must be exact values"));
+        } else if error.mean < 0.0 {
+            return Err(EvalError::new(EvalErrorKind::MathError, "Uncertainties must not be negative"));
+        } else {
+            let source = Uncertain::independent(0.0, error.mean);
+            return self + source;
+        }
+    }
+
+    fn from_bool(value: bool) -> Self {
+        Uncertain::exact(if value { 1.0 } else { 0.0 })
+    }
+
+    fn to_bool(&self) -> Result<bool, EvalError> {
+        return Ok(self.mean != 0.0);
+    }
+}
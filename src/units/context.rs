@@ -1,28 +1,38 @@
 
-use std::{collections::HashMap, cmp::Ordering, ops::*};
+use std::{collections::HashMap, cmp::Ordering, ops::*, rc::Rc};
 use num::traits::Pow;
 
-use crate::{Context, ContextFn, EvalError, Number};
+use crate::{Context, ContextFn, FunctionDefinition, EvalError, EvalErrorKind, Number, DEFAULT_RECURSION_LIMIT};
 
 use super::{Quantity, Unit, unit::BaseUnit};
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum UnitSystem {
+    Imperial,
+    UsCustomary,
+}
+
 pub struct QuantityContext {
     vars: HashMap<String, Quantity>,
-    funcs: HashMap<String, Box<ContextFn<Quantity>>>,
+    funcs: HashMap<String, Rc<ContextFn<Quantity>>>,
+    defs: HashMap<String, Rc<FunctionDefinition>>,
+    units: HashMap<String, Quantity>,
+    system: UnitSystem,
+    recursion_limit: usize,
 }
 
-fn check_length(args: Vec<Quantity>, min: usize, max: usize) -> Result<Vec<Quantity>, EvalError> {
+fn check_length(args: &[Quantity], min: usize, max: usize) -> Result<&[Quantity], EvalError> {
     if args.len() < min {
-        return Err(EvalError::ArgumentMismatch("Too few arguments to function".to_owned()));
+        return Err(EvalError::new(EvalErrorKind::ArgumentMismatch, "Too few arguments to function"));
     } else if args.len() > max {
-        return Err(EvalError::ArgumentMismatch("Too many arguments to function".to_owned()));
+        return Err(EvalError::new(EvalErrorKind::ArgumentMismatch, "Too many arguments to function"));
     } else {
         return Ok(args);
     }
 }
 
-fn min(mut args: Vec<Quantity>) -> Result<Quantity, EvalError> {
-    args = check_length(args, 1, usize::MAX)?;
+fn min(args: &[Quantity]) -> Result<Quantity, EvalError> {
+    let args = check_length(args, 1, usize::MAX)?;
     let mut m = 0;
     for i in 1..args.len() {
         let ord = args[i].partial_cmp(&args[m]);
@@ -31,14 +41,14 @@ fn min(mut args: Vec<Quantity>) -> Result<Quantity, EvalError> {
                 m = i;
             }
         } else {
-            return Err(EvalError::NotSupported("Values in min function are not comparable".to_owned()));
+            return Err(EvalError::new(EvalErrorKind::NotSupported, "Values in min function are not comparable"));
         }
     }
     return Ok(args[m].clone());
 }
 
-fn max(mut args: Vec<Quantity>) -> Result<Quantity, EvalError> {
-    args = check_length(args, 1, usize::MAX)?;
+fn max(args: &[Quantity]) -> Result<Quantity, EvalError> {
+    let args = check_length(args, 1, usize::MAX)?;
     let mut m = 0;
     for i in 1..args.len() {
         let ord = args[i].partial_cmp(&args[m]);
@@ -47,27 +57,44 @@ fn max(mut args: Vec<Quantity>) -> Result<Quantity, EvalError> {
                 m = i;
             }
         } else {
-            return Err(EvalError::NotSupported("Values in max function are not comparable".to_owned()));
+            return Err(EvalError::new(EvalErrorKind::NotSupported, "Values in max function are not comparable"));
         }
     }
     return Ok(args[m].clone());
 }
 
-fn unitless_function<F: Fn(&Number) -> Number>(mut vec: Vec<Quantity>, f: F) -> Result<Quantity, EvalError> {
-    vec = check_length(vec, 1, 1)?;
+fn unitless_function<F: Fn(&Number) -> Number>(vec: &[Quantity], f: F) -> Result<Quantity, EvalError> {
+    let vec = check_length(vec, 1, 1)?;
     if vec[0].is_unitless() {
         let num = f(vec[0].coefficient());
         return num.nan_to_err().map(|x| Quantity::unitless(x));
     } else {
-        return Err(EvalError::UnitError("Function can only be applied to unitless quantity".to_owned()));
+        return Err(EvalError::new(EvalErrorKind::UnitError, "Function can only be applied to unitless quantity"));
     }
 }
 
-fn get_base_unit_for(name: &str) -> Option<Quantity> {
+pub(super) const PREFIXES: [(&str, i64, i64); 64] = [
+    ("quetta", 10, 30), ("ronna", 10, 27), ("yotta", 10, 24), ("zetta", 10, 21), ("exa", 10, 18),
+    ("peta", 10, 15), ("tera", 10, 12), ("giga", 10, 9), ("mega", 10, 6), ("kilo", 10, 3),
+    ("hecto", 10, 2), ("deca", 10, 1), ("deci", 10, -1), ("centi", 10, -2), ("milli", 10, -3),
+    ("micro", 10, -6), ("nano", 10, -9), ("pico", 10, -12), ("femto", 10, -15), ("atto", 10, -18),
+    ("zepto", 10, -21), ("yocto", 10, -24), ("ronto", 10, -27), ("quecto", 10, -30),
+    ("kibi", 2, 10), ("mebi", 2, 20), ("gibi", 2, 30), ("tebi", 2, 40),
+    ("pebi", 2, 50), ("exbi", 2, 60), ("zebi", 2, 70), ("yobi", 2, 80),
+    ("Ki", 2, 10), ("Mi", 2, 20), ("Gi", 2, 30), ("Ti", 2, 40),
+    ("Pi", 2, 50), ("Ei", 2, 60), ("Zi", 2, 70), ("Yi", 2, 80),
+    ("da", 10, 1),
+    ("Q", 10, 30), ("R", 10, 27), ("Y", 10, 24), ("Z", 10, 21), ("E", 10, 18), ("P", 10, 15),
+    ("T", 10, 12), ("G", 10, 9), ("M", 10, 6), ("k", 10, 3), ("h", 10, 2), ("d", 10, -1),
+    ("c", 10, -2), ("m", 10, -3), ("u", 10, -6), ("n", 10, -9), ("p", 10, -12), ("f", 10, -15),
+    ("a", 10, -18), ("z", 10, -21), ("y", 10, -24), ("r", 10, -27), ("q", 10, -30),
+];
+
+pub(super) fn get_base_unit_for(name: &str) -> Option<Quantity> {
     match name {
         "second" | "seconds" | "s" => Some(Quantity::new(Number::one(), Unit::base(BaseUnit::Second))),
         "meter" | "meters" | "metre" | "m" => Some(Quantity::new(Number::one(), Unit::base(BaseUnit::Meter))),
-        "gram" | "grams" | "g" => Some(Quantity::new(Number::one(), Unit::base(BaseUnit::Gram))),
+        "gram" | "grams" | "g" => Some(Quantity::new(Number::from_i64s(1, 1000), Unit::base(BaseUnit::Kilogram))),
         "ampere" | "amperes" | "A" => Some(Quantity::new(Number::one(), Unit::base(BaseUnit::Ampere))),
         "mole" | "moles" | "mol" => Some(Quantity::new(Number::one(), Unit::base(BaseUnit::Mole))),
         "kelvin" | "K" => Some(Quantity::new(Number::one(), Unit::base(BaseUnit::Kelvin))),
@@ -75,64 +102,64 @@ fn get_base_unit_for(name: &str) -> Option<Quantity> {
         "radian" | "radians" | "rad" => Some(Quantity::unitless(Number::one())),
         "steradian" | "steradians" | "sr" => Some(Quantity::unitless(Number::one())),
         "hertz" | "Hz" => Some(Quantity::new(Number::one(), Unit::base(BaseUnit::Second).pow(Number::neg_one()))),
-        "newton" | "newtons" | "N" => Some(Quantity::new(Number::from_i64(1000),
-            Unit::base(BaseUnit::Gram)
+        "newton" | "newtons" | "N" => Some(Quantity::new(Number::one(),
+            Unit::base(BaseUnit::Kilogram)
                 .mul(Unit::base(BaseUnit::Meter))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-2)))
         )),
-        "pascal" | "Pa" => Some(Quantity::new(Number::from_i64(1000),
-            Unit::base(BaseUnit::Gram)
+        "pascal" | "Pa" => Some(Quantity::new(Number::one(),
+            Unit::base(BaseUnit::Kilogram)
                 .mul(Unit::base(BaseUnit::Meter).pow(Number::neg_one()))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-2)))
         )),
-        "joule" | "joules" | "J" => Some(Quantity::new(Number::from_i64(1000),
-            Unit::base(BaseUnit::Gram)
+        "joule" | "joules" | "J" => Some(Quantity::new(Number::one(),
+            Unit::base(BaseUnit::Kilogram)
                 .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(2)))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-2)))
         )),
-        "watt" | "watts" | "W" => Some(Quantity::new(Number::from_i64(1000),
-            Unit::base(BaseUnit::Gram)
+        "watt" | "watts" | "W" => Some(Quantity::new(Number::one(),
+            Unit::base(BaseUnit::Kilogram)
                 .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(2)))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-3)))
         )),
         "coulomb" | "coulombs" | "C" => Some(Quantity::new(Number::one(), Unit::base(BaseUnit::Second).mul(Unit::base(BaseUnit::Ampere)))),
-        "volt" | "volts" | "V" => Some(Quantity::new(Number::from_i64(1000),
-            Unit::base(BaseUnit::Gram)
+        "volt" | "volts" | "V" => Some(Quantity::new(Number::one(),
+            Unit::base(BaseUnit::Kilogram)
                 .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(2)))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-3)))
                 .mul(Unit::base(BaseUnit::Ampere).pow(Number::neg_one()))
         )),
-        "farad" | "farads" | "F" => Some(Quantity::new(Number::from_i64s(1, 1000),
-            Unit::base(BaseUnit::Gram).pow(Number::neg_one())
+        "farad" | "farads" | "F" => Some(Quantity::new(Number::one(),
+            Unit::base(BaseUnit::Kilogram).pow(Number::neg_one())
                 .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(-2)))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(4)))
                 .mul(Unit::base(BaseUnit::Ampere).pow(Number::from_i64(2)))
         )),
-        "ohm" | "ohms" => Some(Quantity::new(Number::from_i64(1000),
-            Unit::base(BaseUnit::Gram)
+        "ohm" | "ohms" => Some(Quantity::new(Number::one(),
+            Unit::base(BaseUnit::Kilogram)
                 .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(2)))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-3)))
                 .mul(Unit::base(BaseUnit::Ampere).pow(Number::from_i64(-2)))
         )),
-        "siemens" | "S" => Some(Quantity::new(Number::from_i64s(1, 1000),
-            Unit::base(BaseUnit::Gram).pow(Number::neg_one())
+        "siemens" | "S" => Some(Quantity::new(Number::one(),
+            Unit::base(BaseUnit::Kilogram).pow(Number::neg_one())
                 .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(-2)))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(3)))
                 .mul(Unit::base(BaseUnit::Ampere).pow(Number::from_i64(2)))
         )),
-        "weber" | "Wb" => Some(Quantity::new(Number::from_i64(1000),
-            Unit::base(BaseUnit::Gram)
+        "weber" | "Wb" => Some(Quantity::new(Number::one(),
+            Unit::base(BaseUnit::Kilogram)
                 .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(2)))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-2)))
                 .mul(Unit::base(BaseUnit::Ampere).pow(Number::neg_one()))
         )),
-        "tesla" | "T" => Some(Quantity::new(Number::from_i64(1000),
-            Unit::base(BaseUnit::Gram)
+        "tesla" | "T" => Some(Quantity::new(Number::one(),
+            Unit::base(BaseUnit::Kilogram)
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-2)))
                 .mul(Unit::base(BaseUnit::Ampere).pow(Number::neg_one()))
         )),
-        "henry" | "H" => Some(Quantity::new(Number::from_i64(1000),
-            Unit::base(BaseUnit::Gram)
+        "henry" | "H" => Some(Quantity::new(Number::one(),
+            Unit::base(BaseUnit::Kilogram)
                 .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(2)))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-2)))
                 .mul(Unit::base(BaseUnit::Ampere).pow(Number::from_i64(-2)))
@@ -143,21 +170,23 @@ fn get_base_unit_for(name: &str) -> Option<Quantity> {
         )),
         "becquerel" | "Bq" => Some(Quantity::new(Number::one(), Unit::base(BaseUnit::Candela).pow(Number::neg_one()))),
         "gray" | "Gy" => Some(Quantity::new(Number::one(),
-            Unit::base(BaseUnit::Gram).pow(Number::from_i64(2))
+            Unit::base(BaseUnit::Meter).pow(Number::from_i64(2))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-2)))
         )),
         "sievert" | "Sv" => Some(Quantity::new(Number::one(),
-            Unit::base(BaseUnit::Gram).pow(Number::from_i64(2))
+            Unit::base(BaseUnit::Meter).pow(Number::from_i64(2))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-2)))
         )),
+        "bit" | "bits" => Some(Quantity::new(Number::one(), Unit::base(BaseUnit::Bit))),
+        "byte" | "bytes" | "B" => Some(Quantity::new(Number::from_i64(8), Unit::base(BaseUnit::Bit))),
         "katal" | "kat" => Some(Quantity::new(Number::one(),
             Unit::base(BaseUnit::Mole).mul(Unit::base(BaseUnit::Second).pow(Number::neg_one()))
         )),
         // Not SI units, but can be used with SI prefix
         "liter" | "liters" | "litre" | "l" | "L" => Some(Quantity::new(Number::from_i64s(1, 1_000), Unit::base(BaseUnit::Meter).pow(Number::from_i64(3)))),
-        "tonne" | "tonnes" | "t" => Some(Quantity::new(Number::from_i64(1_000_000), Unit::base(BaseUnit::Gram))),
-        "electronvolt" | "eV" => Some(Quantity::new(Number::from_i128s(1_602_176_634, (10 as i128).pow(25)),
-            Unit::base(BaseUnit::Gram)
+        "tonne" | "tonnes" | "t" => Some(Quantity::new(Number::from_i64(1_000), Unit::base(BaseUnit::Kilogram))),
+        "electronvolt" | "eV" => Some(Quantity::new(Number::from_i128s(1_602_176_634, (10 as i128).pow(28)),
+            Unit::base(BaseUnit::Kilogram)
                 .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(2)))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-2)))
         )),
@@ -167,10 +196,99 @@ fn get_base_unit_for(name: &str) -> Option<Quantity> {
 
 impl QuantityContext {
     pub fn new() -> QuantityContext {
-        QuantityContext { vars: HashMap::new(), funcs: HashMap::new() }
+        Self::with_system(UnitSystem::Imperial)
+    }
+
+    pub fn with_system(system: UnitSystem) -> QuantityContext {
+        QuantityContext {
+            vars: HashMap::new(), funcs: HashMap::new(), defs: HashMap::new(), units: HashMap::new(), system,
+            recursion_limit: DEFAULT_RECURSION_LIMIT,
+        }
+    }
+
+    pub fn system(&self) -> UnitSystem {
+        self.system
+    }
+
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.recursion_limit = limit;
+    }
+
+    pub fn set_unit(&mut self, name: &str, value: Quantity) {
+        self.units.insert(name.to_owned(), value);
+    }
+
+    // A new dimension must not shadow a unit, such as the built-in dimension 'bit'
+    pub(super) fn check_unused(&self, name: &str) -> Result<(), EvalError> {
+        if self.units.contains_key(name) || self.buildin_variable_for(name).is_some() {
+            return Err(EvalError::new(EvalErrorKind::UnitError, &format!("The unit {} already exists", name)));
+        } else {
+            return Ok(());
+        }
+    }
+
+    fn check_unnamed(&self, name: &str) -> Result<(), EvalError> {
+        let mut chars = name.chars();
+        let identifier = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+            && chars.all(|c| c.is_alphanumeric() || c == '_');
+        if !identifier {
+            return Err(EvalError::new(
+                EvalErrorKind::UnitError, &format!("The unit {} is not a valid identifier, try e.g. USD instead of $", name)
+            ));
+        } else if self.units.contains_key(name) || self.named_variable_for(name).is_some() {
+            return Err(EvalError::new(EvalErrorKind::UnitError, &format!("The unit {} already exists", name)));
+        } else {
+            return Ok(());
+        }
+    }
+
+    pub fn add_base_dimension(&mut self, name: &str, symbol: &str) -> Result<(), EvalError> {
+        // An explicit dimension may shadow names that are only known as a prefixed unit, e.g. `ct`
+        self.check_unnamed(name)?;
+        self.check_unnamed(symbol)?;
+        let unit = Quantity::new(Number::one(), Unit::dimension(symbol));
+        self.set_unit(name, unit.clone());
+        self.set_unit(symbol, unit);
+        return Ok(());
+    }
+
+    fn system_variable_for(&self, name: &str) -> Option<Quantity> {
+        let system = match self.system {
+            UnitSystem::Imperial => "uk",
+            UnitSystem::UsCustomary => "us",
+        };
+        let specific = match name {
+            "fluidounce" | "fluidounces" | "floz" => "floz",
+            "gill" | "gills" | "gi" => "gill",
+            "pint" | "pints" | "pt" => "pint",
+            "quart" | "quarts" | "qt" => "quart",
+            "gallon" | "gallons" | "gal" => "gal",
+            "tons" | "ton" => "ton",
+            _ => return None,
+        };
+        return self.buildin_variable_for(&format!("{}{}", system, specific));
     }
 
     fn buildin_variable_for(&self, name: &str) -> Option<Quantity> {
+        return self.named_variable_for(name).or_else(|| self.prefixed_variable_for(name));
+    }
+
+    fn prefixed_variable_for(&self, name: &str) -> Option<Quantity> {
+        for (prefix, base, exp) in PREFIXES {
+            if name.len() > prefix.len() && name.starts_with(prefix) {
+                let unit = &name[prefix.len()..];
+                // Loaded units and custom dimensions take prefixes just like the built-in base units
+                let val = self.units.get(unit).cloned().or_else(|| get_base_unit_for(unit));
+                let factor = Number::from_i64(base).pow(Number::from_i64(exp)).unwrap();
+                if let Some(Ok(val)) = val.map(|v| v * Quantity::unitless(factor)) {
+                    return Some(val);
+                }
+            }
+        }
+        return None;
+    }
+
+    fn named_variable_for(&self, name: &str) -> Option<Quantity> {
         return match name {
             "pi" => Some(Quantity::pi()),
             "e" => Some(Quantity::e()),
@@ -206,93 +324,80 @@ impl QuantityContext {
                 Number::from_i128s(2589988_110336, 1_000_000), Unit::base(BaseUnit::Meter).pow(Number::from_i64(2))
             )),
             // Imperial volume
-            "fluidounce" | "fluidounces" | "floz" => Some(Quantity::new(
+            "ukfloz" => Some(Quantity::new(
                 Number::from_i128s(28_4130625, (10 as i128).pow(13)), Unit::base(BaseUnit::Meter).pow(Number::from_i64(3))
             )),
-            "gill" | "gills" | "gi" => Some(Quantity::new(
+            "ukgill" => Some(Quantity::new(
                 Number::from_i128s(142_0653125, (10 as i128).pow(13)), Unit::base(BaseUnit::Meter).pow(Number::from_i64(3))
             )),
-            "pint" | "pints" | "pt" => Some(Quantity::new(
+            "ukpint" => Some(Quantity::new(
                 Number::from_i128s(568_26125, (10 as i128).pow(11)), Unit::base(BaseUnit::Meter).pow(Number::from_i64(3))
             )),
-            "quart" | "quarts" | "qt" => Some(Quantity::new(
+            "ukquart" => Some(Quantity::new(
                 Number::from_i128s(1136_5225, (10 as i128).pow(10)), Unit::base(BaseUnit::Meter).pow(Number::from_i64(3))
             )),
-            "gallon" | "gallons" | "gal" => Some(Quantity::new(
+            "ukgal" => Some(Quantity::new(
                 Number::from_i128s(4546_09, (10 as i128).pow(8)), Unit::base(BaseUnit::Meter).pow(Number::from_i64(3))
             )),
             // Imperial mass
-            "grain" | "grains" | "gr" => Some(Quantity::new(Number::from_i128s(0_06479891, (10 as i128).pow(8)), Unit::base(BaseUnit::Gram))),
-            "drachm" | "drachms" | "dr" => Some(Quantity::new(Number::from_i128s(1_7718451953125, (10 as i128).pow(13)), Unit::base(BaseUnit::Gram))),
-            "ounce" | "ounces" | "oz" => Some(Quantity::new(Number::from_i128s(28_349523125, (10 as i128).pow(9)), Unit::base(BaseUnit::Gram))),
-            "pound" | "pounds" | "lb" => Some(Quantity::new(Number::from_i128s(453_59237, (10 as i128).pow(5)), Unit::base(BaseUnit::Gram))),
-            "stone" | "stones" | "st" => Some(Quantity::new(Number::from_i128s(6350_29318, (10 as i128).pow(5)), Unit::base(BaseUnit::Gram))),
-            "quarter" | "quarters" | "qr" => Some(Quantity::new(Number::from_i128s(12700_58636, (10 as i128).pow(5)), Unit::base(BaseUnit::Gram))),
-            "hundredweight" | "hundredweights" | "cwt" => Some(Quantity::new(Number::from_i128s(50802_34544, (10 as i128).pow(5)), Unit::base(BaseUnit::Gram))),
-            "tons" | "ton" => Some(Quantity::new(Number::from_i128s(1016046_9088, (10 as i128).pow(4)), Unit::base(BaseUnit::Gram))),
-            "slugs" | "slug"=> Some(Quantity::new(Number::from_i128s(14593_90294, (10 as i128).pow(5)), Unit::base(BaseUnit::Gram))),
+            "grain" | "grains" | "gr" => Some(Quantity::new(Number::from_i128s(0_06479891, (10 as i128).pow(11)), Unit::base(BaseUnit::Kilogram))),
+            "drachm" | "drachms" | "dr" => Some(Quantity::new(Number::from_i128s(1_7718451953125, (10 as i128).pow(16)), Unit::base(BaseUnit::Kilogram))),
+            "ounce" | "ounces" | "oz" => Some(Quantity::new(Number::from_i128s(28_349523125, (10 as i128).pow(12)), Unit::base(BaseUnit::Kilogram))),
+            "pound" | "pounds" | "lb" => Some(Quantity::new(Number::from_i128s(453_59237, (10 as i128).pow(8)), Unit::base(BaseUnit::Kilogram))),
+            "stone" | "stones" | "st" => Some(Quantity::new(Number::from_i128s(6350_29318, (10 as i128).pow(8)), Unit::base(BaseUnit::Kilogram))),
+            "quarter" | "quarters" | "qr" => Some(Quantity::new(Number::from_i128s(12700_58636, (10 as i128).pow(8)), Unit::base(BaseUnit::Kilogram))),
+            "hundredweight" | "hundredweights" | "cwt" => Some(Quantity::new(Number::from_i128s(50802_34544, (10 as i128).pow(8)), Unit::base(BaseUnit::Kilogram))),
+            "longton" | "longtons" | "ukton" => Some(Quantity::new(Number::from_i128s(1016046_9088, (10 as i128).pow(7)), Unit::base(BaseUnit::Kilogram))),
+            "slugs" | "slug"=> Some(Quantity::new(Number::from_i128s(14593_90294, (10 as i128).pow(8)), Unit::base(BaseUnit::Kilogram))),
+            // US customary length
+            "surveyfoot" | "surveyfeet" | "ftUS" => Some(Quantity::new(Number::from_i128s(1200, 3937), Unit::base(BaseUnit::Meter))),
+            "surveymile" | "surveymiles" | "miUS" => Some(Quantity::new(Number::from_i128s(6336000, 3937), Unit::base(BaseUnit::Meter))),
+            // US customary volume
+            "usfloz" => Some(Quantity::new(
+                Number::from_i128s(29_5735295625, (10 as i128).pow(16)), Unit::base(BaseUnit::Meter).pow(Number::from_i64(3))
+            )),
+            "usgill" => Some(Quantity::new(
+                Number::from_i128s(118_29411825, (10 as i128).pow(14)), Unit::base(BaseUnit::Meter).pow(Number::from_i64(3))
+            )),
+            "uspint" => Some(Quantity::new(
+                Number::from_i128s(473_176473, (10 as i128).pow(12)), Unit::base(BaseUnit::Meter).pow(Number::from_i64(3))
+            )),
+            "usquart" => Some(Quantity::new(
+                Number::from_i128s(946_352946, (10 as i128).pow(12)), Unit::base(BaseUnit::Meter).pow(Number::from_i64(3))
+            )),
+            "usgal" => Some(Quantity::new(
+                Number::from_i128s(3785_411784, (10 as i128).pow(12)), Unit::base(BaseUnit::Meter).pow(Number::from_i64(3))
+            )),
+            // Cooking measures only have a common definition in the US customary system
+            "cup" | "cups" | "uscup" => Some(Quantity::new(
+                Number::from_i128s(236_5882365, (10 as i128).pow(13)), Unit::base(BaseUnit::Meter).pow(Number::from_i64(3))
+            )),
+            "tablespoon" | "tablespoons" | "tbsp" => Some(Quantity::new(
+                Number::from_i128s(14_78676478125, (10 as i128).pow(17)), Unit::base(BaseUnit::Meter).pow(Number::from_i64(3))
+            )),
+            "teaspoon" | "teaspoons" | "tsp" => Some(Quantity::new(
+                Number::from_i128s(4_92892159375, (10 as i128).pow(17)), Unit::base(BaseUnit::Meter).pow(Number::from_i64(3))
+            )),
+            // US customary mass
+            "shortton" | "shorttons" | "uston" => Some(Quantity::new(Number::from_i128s(907184_74, (10 as i128).pow(5)), Unit::base(BaseUnit::Kilogram))),
             // SI accepted
             "minute" | "minutes" | "min" => Some(Quantity::new(Number::from_i64(60), Unit::base(BaseUnit::Second))),
             "hour" | "hours" | "h" => Some(Quantity::new(Number::from_i64(3600), Unit::base(BaseUnit::Second))),
             "day" | "days" | "d" => Some(Quantity::new(Number::from_i64(86400), Unit::base(BaseUnit::Second))),
             "astronomicalunit" | "au" => Some(Quantity::new(Number::from_i64(149_597_870_700), Unit::base(BaseUnit::Meter))),
             "hectare" | "hectares" | "ha" => Some(Quantity::new(Number::from_i64(10_000), Unit::base(BaseUnit::Meter).pow(Number::from_i64(2)))),
-            "dalton" | "Da" => Some(Quantity::new(Number::from_i128s(166_053_904_020, (10 as i128).pow(35)), Unit::base(BaseUnit::Gram))),
-            _ => {
-                if let Some(val) = get_base_unit_for(name) {
-                    return Some(val);
-                }
-                let (mult, div, len) = match name {
-                    _ if name.starts_with("yotta") => (1_000_000_000_000_000_000_000_000, 1, 5),
-                    _ if name.starts_with("zetta") => (1_000_000_000_000_000_000_000, 1, 5),
-                    _ if name.starts_with("exa") => (1_000_000_000_000_000_000, 1, 3),
-                    _ if name.starts_with("peta") => (1_000_000_000_000_000, 1, 4),
-                    _ if name.starts_with("tera") => (1_000_000_000_000, 1, 4),
-                    _ if name.starts_with("giga") => (1_000_000_000, 1, 4),
-                    _ if name.starts_with("mega") => (1_000_000, 1, 4),
-                    _ if name.starts_with("kilo") => (1_000, 1, 4),
-                    _ if name.starts_with("hecto") => (100, 1, 5),
-                    _ if name.starts_with("deca") => (10, 1, 4),
-                    _ if name.starts_with("yocto") => (1, 1_000_000_000_000_000_000_000_000, 5),
-                    _ if name.starts_with("zepto") => (1, 1_000_000_000_000_000_000_000, 5),
-                    _ if name.starts_with("atto") => (1, 1_000_000_000_000_000_000, 4),
-                    _ if name.starts_with("femto") => (1, 1_000_000_000_000_000, 5),
-                    _ if name.starts_with("pico") => (1, 1_000_000_000_000, 4),
-                    _ if name.starts_with("nano") => (1, 1_000_000_000, 4),
-                    _ if name.starts_with("micro") => (1, 1_000_000, 5),
-                    _ if name.starts_with("milli") => (1, 1_000, 5),
-                    _ if name.starts_with("centi") => (1, 100, 5),
-                    _ if name.starts_with("deci") => (1, 10, 4),
-                    _ if name.starts_with("Y") => (1_000_000_000_000_000_000_000_000, 1, 1),
-                    _ if name.starts_with("Z") => (1_000_000_000_000_000_000_000, 1, 1),
-                    _ if name.starts_with("E") => (1_000_000_000_000_000_000, 1, 1),
-                    _ if name.starts_with("P") => (1_000_000_000_000_000, 1, 1),
-                    _ if name.starts_with("T") => (1_000_000_000_000, 1, 1),
-                    _ if name.starts_with("G") => (1_000_000_000, 1, 1),
-                    _ if name.starts_with("M") => (1_000_000, 1, 1),
-                    _ if name.starts_with("k") => (1_000, 1, 1),
-                    _ if name.starts_with("h") => (100, 1, 1),
-                    _ if name.starts_with("da") => (10, 1, 2),
-                    _ if name.starts_with("y") => (1, 1_000_000_000_000_000_000_000_000, 1),
-                    _ if name.starts_with("z") => (1, 1_000_000_000_000_000_000_000, 1),
-                    _ if name.starts_with("a") => (1, 1_000_000_000_000_000_000, 1),
-                    _ if name.starts_with("f") => (1, 1_000_000_000_000_000, 1),
-                    _ if name.starts_with("p") => (1, 1_000_000_000_000, 1),
-                    _ if name.starts_with("n") => (1, 1_000_000_000, 1),
-                    _ if name.starts_with("u") => (1, 1_000_000, 1),
-                    _ if name.starts_with("m") => (1, 1_000, 1),
-                    _ if name.starts_with("c") => (1, 100, 1),
-                    _ if name.starts_with("d") => (1, 10, 1),
-                    _ => (1, 1, 0),
-                };
-                let prefix = Number::from_i128s(mult, div);
-                if len < name.len() && len != 0 {
-                    if let Some(val) = get_base_unit_for(&name[len..]) {
-                        return Some((val * Quantity::unitless(prefix)).unwrap());
-                    }
-                }
-                return None;
-            }
+            // Temperature
+            "celsius" | "degC" => Some(Quantity::with_offset(
+                Number::one(), Number::from_i64s(27315, 100), Unit::base(BaseUnit::Kelvin)
+            )),
+            "fahrenheit" | "degF" => Some(Quantity::with_offset(
+                Number::from_i64s(5, 9), Number::from_i64s(45967, 180), Unit::base(BaseUnit::Kelvin)
+            )),
+            "rankine" | "degR" => Some(Quantity::new(Number::from_i64s(5, 9), Unit::base(BaseUnit::Kelvin))),
+            "deltaC" => Some(Quantity::difference(Number::one(), Unit::base(BaseUnit::Kelvin))),
+            "deltaF" | "deltaR" => Some(Quantity::difference(Number::from_i64s(5, 9), Unit::base(BaseUnit::Kelvin))),
+            "dalton" | "Da" => Some(Quantity::new(Number::from_i128s(166_053_904_020, (10 as i128).pow(38)), Unit::base(BaseUnit::Kilogram))),
+            _ => self.system_variable_for(name).or_else(|| get_base_unit_for(name)),
         }
     }
 
@@ -317,13 +422,13 @@ impl QuantityContext {
             "asin" => Some(&|v| unitless_function(v, Number::asin)),
             "acos" => Some(&|v| unitless_function(v, Number::acos)),
             "atan" => Some(&|v| unitless_function(v, Number::atan)),
-            "atan2" => Some(&|mut v| {
-                v = check_length(v, 2, 2)?;
+            "atan2" => Some(&|v| {
+                let v = check_length(v, 2, 2)?;
                 if v[0].is_unitless() && v[1].is_unitless() {
                     let num = v[0].coefficient().atan2(v[1].coefficient());
                     return num.nan_to_err().map(|x| Quantity::unitless(x));
                 } else {
-                    return Err(EvalError::UnitError("Function can only be applied to unitless quantity".to_owned()));
+                    return Err(EvalError::new(EvalErrorKind::UnitError, "Function can only be applied to unitless quantity"));
                 }
             }),
             "sinh" => Some(&|v| unitless_function(v, Number::sinh)),
@@ -343,19 +448,34 @@ impl Context<Quantity> for QuantityContext {
     }
 
     fn set_function(&mut self, name: &str, value: Box<ContextFn<Quantity>>) {
-        self.funcs.insert(name.to_owned(), value);
+        self.defs.remove(name);
+        self.funcs.insert(name.to_owned(), Rc::from(value));
     }
 
     fn get_variable(&self, name: &str) -> Option<Quantity> {
         self.vars.get(name)
+            .or_else(|| self.units.get(name))
             .and_then(|n| Some(n.clone()))
             .or_else(|| self.buildin_variable_for(name))
     }
 
-    fn get_function<'a>(&'a self, name: &str) -> Option<&'a ContextFn<Quantity>> {
+    fn get_function(&self, name: &str) -> Option<Rc<ContextFn<Quantity>>> {
         self.funcs.get(name)
-            .and_then(|n| Some(n.as_ref()))
-            .or_else(|| self.buildin_function_for(name))
+            .cloned()
+            .or_else(|| self.buildin_function_for(name).and_then(|f| Some(Rc::new(f) as Rc<ContextFn<Quantity>>)))
+    }
+
+    fn set_definition(&mut self, name: &str, definition: Rc<FunctionDefinition>) {
+        self.funcs.remove(name);
+        self.defs.insert(name.to_owned(), definition);
+    }
+
+    fn get_definition(&self, name: &str) -> Option<Rc<FunctionDefinition>> {
+        return self.defs.get(name).cloned();
+    }
+
+    fn recursion_limit(&self) -> usize {
+        self.recursion_limit
     }
 }
 
@@ -0,0 +1,87 @@
+
+use std::ops::*;
+use num::traits::Pow;
+
+use crate::Number;
+
+use super::{Quantity, Unit, context::{get_base_unit_for, PREFIXES}};
+
+const NAMED_UNITS: [&str; 21] = [
+    "m", "g", "s", "A", "mol", "K", "cd", "bit",
+    "Hz", "N", "Pa", "J", "W", "C", "V", "F", "ohm", "S", "Wb", "T", "H",
+];
+
+// Durations of at least a minute are shown in these rather than in kiloseconds
+const TIME_UNITS: [(&str, i64); 3] = [("d", 86_400), ("h", 3_600), ("min", 60)];
+
+fn complexity(unit: &Unit) -> usize {
+    unit.exponents().filter(|e| !e.is_zero()).map(|e| {
+        if e.is_integer() {
+            e.abs().to_f64() as usize
+        } else {
+            1
+        }
+    }).sum()
+}
+
+fn with_prefix(number: Number) -> (Number, &'static str) {
+    if number.is_zero() {
+        return (number, "");
+    }
+    // Only the symbols for powers of 1000
+    let mut prefixes: Vec<(&str, i64)> = PREFIXES.iter()
+        .filter(|(prefix, base, exp)| prefix.len() == 1 && *base == 10 && exp % 3 == 0)
+        .map(|(prefix, _, exp)| (*prefix, *exp))
+        .collect();
+    prefixes.push(("", 0));
+    prefixes.sort_by_key(|(_, exp)| -exp);
+    for (prefix, exp) in prefixes {
+        let scale = Number::from_i64(10).pow(Number::from_i64(exp)).unwrap();
+        if number.abs() >= scale {
+            return (number.div(scale).unwrap(), prefix);
+        }
+    }
+    return (number, "");
+}
+
+impl Quantity {
+    pub fn best_unit(&self) -> (Number, String) {
+        let mut best = None;
+        let mut best_complexity = complexity(self.unit());
+        for name in NAMED_UNITS {
+            let named = get_base_unit_for(name).unwrap();
+            let rest = self.unit().clone().div(named.unit().clone());
+            if complexity(&rest) + 1 < best_complexity || (rest.is_empty() && best_complexity <= 1) {
+                best_complexity = complexity(&rest) + 1;
+                best = Some((name, named, rest));
+            }
+        }
+        if let Some((name, named, rest)) = best {
+            if let Ok(number) = self.coefficient().clone().div(named.coefficient().clone()) {
+                if name == "s" && rest.is_empty() {
+                    for (unit, seconds) in TIME_UNITS {
+                        if number.abs() >= Number::from_i64(seconds) {
+                            return (number.div(Number::from_i64(seconds)).unwrap(), unit.to_owned());
+                        }
+                    }
+                }
+                let (number, prefix) = with_prefix(number);
+                if rest.is_empty() {
+                    return (number, format!("{}{}", prefix, name));
+                } else {
+                    return (number, format!("{}{} {}", prefix, name, rest.to_string()));
+                }
+            }
+        }
+        return (self.coefficient().clone(), self.unit().to_string());
+    }
+
+    pub fn to_best_string(&self) -> String {
+        if self.is_unitless() {
+            self.coefficient().to_string()
+        } else {
+            let (number, unit) = self.best_unit();
+            format!("{} {}", number.to_string(), unit)
+        }
+    }
+}
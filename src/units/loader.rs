@@ -0,0 +1,89 @@
+
+use std::fmt;
+use std::error::Error;
+use std::io::{BufRead, BufReader, Read};
+
+use crate::{Expr, Number, Quantity, QuantityContext, Unit};
+
+#[derive(Debug)]
+pub struct LoadError {
+    message: String,
+    line: usize,
+}
+
+impl LoadError {
+    fn at(line: usize, msg: &str) -> LoadError {
+        return LoadError { message: msg.to_owned(), line };
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Error on line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for LoadError {}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    if let Some(c) = chars.next() {
+        return (c.is_alphabetic() || c == '_') && chars.all(|c| c.is_alphanumeric() || c == '_');
+    } else {
+        return false;
+    }
+}
+
+fn parse_definition(line: &str) -> Result<Option<(Vec<String>, Option<Expr>)>, String> {
+    let line = line.split('#').next().unwrap().trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    if let Some((names, expr)) = line.split_once('=') {
+        let mut res = Vec::new();
+        for name in names.split(',') {
+            let name = name.trim();
+            if !is_identifier(name) {
+                return Err(format!("Invalid unit name '{}'", name));
+            }
+            res.push(name.to_owned());
+        }
+        if expr.trim() == "!" {
+            return Ok(Some((res, None)));
+        }
+        let expr = Expr::parse(expr).map_err(|err| err.to_string())?;
+        return Ok(Some((res, Some(expr))));
+    } else {
+        return Err("Expected '=' after the unit names".to_owned());
+    }
+}
+
+impl QuantityContext {
+    pub fn load_units<R: Read>(&mut self, reader: R) -> Result<(), LoadError> {
+        for (i, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line.map_err(|err| LoadError::at(i + 1, &err.to_string()))?;
+            if let Some((names, expr)) = parse_definition(&line).map_err(|err| LoadError::at(i + 1, &err))? {
+                let value = if let Some(expr) = expr {
+                    expr.eval_in(self).map_err(|err| LoadError::at(i + 1, &err.to_string()))?
+                } else {
+                    for name in &names {
+                        self.check_unused(name).map_err(|err| LoadError::at(i + 1, &err.to_string()))?;
+                    }
+                    Quantity::new(Number::one(), Unit::dimension(&names[0]))
+                };
+                for name in names {
+                    self.set_unit(&name, value.clone());
+                }
+            }
+        }
+        return Ok(());
+    }
+}
@@ -2,9 +2,14 @@
 mod unit;
 mod quantity;
 mod context;
+mod loader;
+mod format;
 
 pub use unit::Unit;
 pub use unit::BaseUnit;
 pub use context::QuantityContext;
+pub use context::UnitSystem;
 pub use quantity::Quantity;
+pub use quantity::Origin;
+pub use loader::LoadError;
 
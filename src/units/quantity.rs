@@ -3,23 +3,60 @@ use std::ops::*;
 use std::str::FromStr;
 use num::traits::Pow;
 
-use crate::{Number, Value, QuantityContext, EvalError, Expr};
+use crate::{Number, Value, QuantityContext, EvalError, EvalErrorKind, Expr};
 
 use super::Unit;
 
+#[derive(PartialEq, Clone, Debug)]
+pub enum Origin {
+    // Quantities on purely multiplicative scales
+    Relative,
+    // An explicit difference of absolute quantities, e.g. deltaC
+    Difference,
+    // A point on an absolute scale, e.g. a temperature reading
+    Absolute,
+    // An affine unit (e.g. degC) whose zero point lies at the given offset
+    Offset(Number),
+}
+
 #[derive(PartialEq, Clone, Debug)]
 pub struct Quantity {
     number: Number,
     unit: Unit,
+    origin: Origin,
 }
 
 impl Quantity {
     pub fn new(number: Number, unit: Unit) -> Quantity {
-        Quantity { number, unit }
+        Quantity { number, unit, origin: Origin::Relative }
     }
 
     pub fn unitless(number: Number) -> Quantity {
-        Quantity { number, unit: Unit::empty() }
+        Quantity { number, unit: Unit::empty(), origin: Origin::Relative }
+    }
+
+    pub fn absolute(number: Number, unit: Unit) -> Quantity {
+        Quantity { number, unit, origin: Origin::Absolute }
+    }
+
+    pub fn with_offset(number: Number, offset: Number, unit: Unit) -> Quantity {
+        Quantity { number, unit, origin: Origin::Offset(offset) }
+    }
+
+    pub fn difference(number: Number, unit: Unit) -> Quantity {
+        Quantity { number, unit, origin: Origin::Difference }
+    }
+
+    pub fn is_absolute(&self) -> bool {
+        matches!(self.origin, Origin::Absolute | Origin::Offset(_))
+    }
+
+    fn is_scalar(&self) -> bool {
+        self.is_unitless() && self.origin == Origin::Relative
+    }
+
+    pub fn origin(&self) -> &Origin {
+        &self.origin
     }
 
     pub fn is_unitless(&self) -> bool {
@@ -61,12 +98,37 @@ impl Quantity {
     pub fn nan_to_err(self) -> Result<Quantity, EvalError> {
         if let Quantity { number: Number::Float(f), .. } = self {
             if f.is_nan() {
-                return Err(EvalError::MathError("NaN".to_owned()));
+                return Err(EvalError::new(EvalErrorKind::MathError, "NaN"));
             }
         }
         return Ok(self);
     }
 
+    fn resolve_offset(self) -> Result<Quantity, EvalError> {
+        if let Origin::Offset(offset) = self.origin {
+            return Ok(Quantity::absolute((self.number + offset)?, self.unit));
+        } else {
+            return Ok(self);
+        }
+    }
+
+    // A plain quantity such as 300 K is read as a point on the absolute scale when it meets an absolute one
+    fn reading_for(self, other: &Quantity) -> Quantity {
+        if self.origin == Origin::Relative && !self.is_unitless() && other.is_absolute() {
+            return Quantity::absolute(self.number, self.unit);
+        } else {
+            return self;
+        }
+    }
+
+    fn reject_absolute(&self, operation: &str) -> Result<(), EvalError> {
+        if self.origin == Origin::Absolute {
+            return Err(EvalError::new(EvalErrorKind::UnitError, &format!("Cannot {} an absolute quantity", operation)));
+        } else {
+            return Ok(());
+        }
+    }
+
     pub fn convert_to(&self, unit: &str) -> Option<Number> {
         self.convert_to_in(unit, &Self::default_context())
     }
@@ -74,10 +136,8 @@ impl Quantity {
     pub fn convert_to_in(&self, unit: &str, context: &QuantityContext) -> Option<Number> {
         if let Ok(expr) = Expr::parse(unit) {
             if let Ok(res) = expr.eval_in(context) {
-                if res.unit == self.unit {
-                    if let Ok(conv) = self.number.clone().div(res.number) {
-                        return Some(conv);
-                    }
+                if let Ok(conv) = self.clone().convert(res) {
+                    return Some(conv.number);
                 }
             }
         }
@@ -87,11 +147,12 @@ impl Quantity {
 
 impl PartialOrd for Quantity {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self.unit == other.unit {
-            self.number.partial_cmp(&other.number)
-        } else {
-            None
+        let (lhs, rhs) = (self.clone().reading_for(other), other.clone().reading_for(self));
+        if lhs.unit != rhs.unit || lhs.is_absolute() != rhs.is_absolute() {
+            return None;
         }
+        let (lhs, rhs) = (lhs.resolve_offset().ok()?, rhs.resolve_offset().ok()?);
+        return lhs.number.partial_cmp(&rhs.number);
     }
 }
 
@@ -117,7 +178,8 @@ impl Neg for Quantity {
     type Output = Result<Quantity, EvalError>;
 
     fn neg(mut self) -> Self::Output {
-        self.number = self.number.neg()?;
+        self.reject_absolute("negate")?;
+        self.number = (-self.number)?;
         return Ok(self);
     }
 }
@@ -127,9 +189,18 @@ impl Add for Quantity {
 
     fn add(self, rhs: Self) -> Self::Output {
         if self.unit != rhs.unit {
-            return Err(EvalError::UnitError(format!("Cannot add {} to {}", self.unit.to_string(), rhs.unit.to_string())));
+            return Err(EvalError::new(EvalErrorKind::UnitError, &format!("Cannot add {} to {}", self.unit.to_string(), rhs.unit.to_string())));
         } else {
-            return Ok(Quantity { number: self.number.add(rhs.number)?, unit: self.unit });
+            let (lhs, rhs) = (self.resolve_offset()?, rhs.resolve_offset()?);
+            let origin = match (lhs.origin, rhs.origin) {
+                (Origin::Absolute, Origin::Absolute) => {
+                    return Err(EvalError::new(EvalErrorKind::UnitError, "Cannot add two absolute quantities"));
+                },
+                (Origin::Absolute, _) | (_, Origin::Absolute) => Origin::Absolute,
+                (Origin::Difference, Origin::Difference) => Origin::Difference,
+                _ => Origin::Relative,
+            };
+            return Ok(Quantity { number: (lhs.number + rhs.number)?, unit: lhs.unit, origin });
         }
     }
 }
@@ -139,9 +210,19 @@ impl Sub for Quantity {
 
     fn sub(self, rhs: Self) -> Self::Output {
         if self.unit != rhs.unit {
-            return Err(EvalError::UnitError(format!("Cannot subtract {} to {}", self.unit.to_string(), rhs.unit.to_string())));
+            return Err(EvalError::new(EvalErrorKind::UnitError, &format!("Cannot subtract {} to {}", self.unit.to_string(), rhs.unit.to_string())));
         } else {
-            return Ok(Quantity { number: self.number.sub(rhs.number)?, unit: self.unit });
+            let lhs = self.reading_for(&rhs);
+            let (lhs, rhs) = (lhs.resolve_offset()?, rhs.resolve_offset()?);
+            let origin = match (lhs.origin, rhs.origin) {
+                (Origin::Absolute, Origin::Absolute) | (Origin::Difference, Origin::Difference) => Origin::Difference,
+                (Origin::Absolute, _) => Origin::Absolute,
+                (_, Origin::Absolute) => {
+                    return Err(EvalError::new(EvalErrorKind::UnitError, "Cannot subtract an absolute quantity from a difference"));
+                },
+                _ => Origin::Relative,
+            };
+            return Ok(Quantity { number: (lhs.number - rhs.number)?, unit: lhs.unit, origin });
         }
     }
 }
@@ -150,7 +231,21 @@ impl Mul for Quantity {
     type Output = Result<Quantity, EvalError>;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        return Ok(Quantity { number: self.number.mul(rhs.number)?, unit: self.unit.mul(rhs.unit) });
+        self.reject_absolute("multiply")?;
+        rhs.reject_absolute("multiply")?;
+        match (self, rhs) {
+            (scalar, Quantity { number, unit, origin: Origin::Offset(offset) })
+            | (Quantity { number, unit, origin: Origin::Offset(offset) }, scalar) if scalar.is_scalar() => {
+                return Ok(Quantity::absolute(((scalar.number * number)? + offset)?, unit));
+            },
+            (scalar, Quantity { number, unit, origin: Origin::Difference })
+            | (Quantity { number, unit, origin: Origin::Difference }, scalar) if scalar.is_scalar() => {
+                return Ok(Quantity::difference((scalar.number * number)?, unit));
+            },
+            (lhs, rhs) => {
+                return Ok(Quantity::new((lhs.number * rhs.number)?, lhs.unit.mul(rhs.unit)));
+            },
+        }
     }
 }
 
@@ -158,7 +253,13 @@ impl Div for Quantity {
     type Output = Result<Quantity, EvalError>;
 
     fn div(self, rhs: Self) -> Self::Output {
-        return Ok(Quantity { number: self.number.div(rhs.number)?, unit: self.unit.div(rhs.unit) });
+        self.reject_absolute("divide")?;
+        rhs.reject_absolute("divide by")?;
+        if self.origin == Origin::Difference && rhs.is_scalar() {
+            return Ok(Quantity::difference((self.number / rhs.number)?, self.unit));
+        } else {
+            return Ok(Quantity::new((self.number / rhs.number)?, self.unit.div(rhs.unit)));
+        }
     }
 }
 
@@ -166,19 +267,152 @@ impl Pow<Quantity> for Quantity {
     type Output = Result<Quantity, EvalError>;
 
     fn pow(self, rhs: Quantity) -> Self::Output {
+        self.reject_absolute("raise")?;
         if !rhs.unit.is_empty() {
-            return Err(EvalError::UnitError(format!("Cannot take power with exponent of {}", rhs.unit.to_string())));
+            return Err(EvalError::new(EvalErrorKind::UnitError, &format!("Cannot take power with exponent of {}", rhs.unit.to_string())));
         } else {
-            return Ok(Quantity { number: self.number.pow(rhs.number.clone())?, unit: self.unit.pow(rhs.number) });
+            return Ok(Quantity::new(Pow::pow(self.number, rhs.number.clone())?, self.unit.pow(rhs.number)));
         }
     }
 }
 
+fn unitless_operation(
+    lhs: Quantity, rhs: Quantity, f: fn(Number, Number) -> Result<Number, EvalError>
+) -> Result<Quantity, EvalError> {
+    if !lhs.is_unitless() || !rhs.is_unitless() {
+        return Err(EvalError::new(EvalErrorKind::UnitError, "Bitwise operators can only be applied to unitless quantities"));
+    } else {
+        return Ok(Quantity::unitless(f(lhs.number, rhs.number)?));
+    }
+}
+
 impl Value for Quantity {
     type DefaultContext = QuantityContext;
 
     fn default_context() -> Self::DefaultContext {
         QuantityContext::new()
     }
+
+    fn parse_literal(literal: &str) -> Result<Self, EvalError> {
+        Quantity::from_str(literal)
+    }
+
+    fn add(self, other: Self) -> Result<Self, EvalError> {
+        self + other
+    }
+
+    fn sub(self, other: Self) -> Result<Self, EvalError> {
+        self - other
+    }
+
+    fn mul(self, other: Self) -> Result<Self, EvalError> {
+        self * other
+    }
+
+    fn div(self, other: Self) -> Result<Self, EvalError> {
+        self / other
+    }
+
+    fn pow(self, other: Self) -> Result<Self, EvalError> {
+        Pow::pow(self, other)
+    }
+
+    fn neg(self) -> Result<Self, EvalError> {
+        -self
+    }
+
+    fn modulo(self, other: Self) -> Result<Self, EvalError> {
+        if self.unit != other.unit {
+            return Err(EvalError::new(
+                EvalErrorKind::UnitError,
+                &format!("Cannot take {} modulo {}", self.unit.to_string(), other.unit.to_string())
+            ));
+        }
+        let (lhs, rhs) = (self.resolve_offset()?, other.resolve_offset()?);
+        return Ok(Quantity::new(Number::modulo(&lhs.number, &rhs.number)?, lhs.unit));
+    }
+
+    fn floor_div(self, other: Self) -> Result<Self, EvalError> {
+        if self.unit != other.unit {
+            return Err(EvalError::new(
+                EvalErrorKind::UnitError,
+                &format!("Cannot floor divide {} by {}", self.unit.to_string(), other.unit.to_string())
+            ));
+        }
+        let (lhs, rhs) = (self.resolve_offset()?, other.resolve_offset()?);
+        return Ok(Quantity::new(Value::floor_div(lhs.number, rhs.number)?, lhs.unit.div(rhs.unit)));
+    }
+
+    fn bit_and(self, other: Self) -> Result<Self, EvalError> {
+        unitless_operation(self, other, Value::bit_and)
+    }
+
+    fn bit_or(self, other: Self) -> Result<Self, EvalError> {
+        unitless_operation(self, other, Value::bit_or)
+    }
+
+    fn bit_xor(self, other: Self) -> Result<Self, EvalError> {
+        unitless_operation(self, other, Value::bit_xor)
+    }
+
+    fn shift_left(self, other: Self) -> Result<Self, EvalError> {
+        unitless_operation(self, other, Value::shift_left)
+    }
+
+    fn shift_right(self, other: Self) -> Result<Self, EvalError> {
+        unitless_operation(self, other, Value::shift_right)
+    }
+
+    fn bit_not(self) -> Result<Self, EvalError> {
+        unitless_operation(self, Quantity::unitless(Number::zero()), |x, _| Value::bit_not(x))
+    }
+
+    fn from_bool(value: bool) -> Self {
+        Quantity::unitless(Number::from_bool(value))
+    }
+
+    fn to_bool(&self) -> Result<bool, EvalError> {
+        if self.is_unitless() {
+            return self.number.to_bool();
+        } else {
+            return Err(EvalError::new(
+                EvalErrorKind::UnitError, &format!("Cannot use {} as a truth value", self.unit.to_string())
+            ));
+        }
+    }
+
+    fn convert(self, target: Self) -> Result<Self, EvalError> {
+        if self.unit != target.unit {
+            return Err(EvalError::new(
+                EvalErrorKind::UnitError,
+                &format!("Cannot convert {} to {}", self.unit.to_string(), target.unit.to_string())
+            ));
+        }
+        let source = self.reading_for(&target).resolve_offset()?;
+        if let Origin::Offset(offset) = target.origin {
+            if source.origin != Origin::Absolute {
+                return Err(EvalError::new(
+                    EvalErrorKind::UnitError, "Only absolute quantities can be converted to a scale with an offset"
+                ));
+            }
+            return Ok(Quantity::unitless(((source.number - offset)? / target.number)?));
+        } else {
+            return Ok(Quantity::unitless((source.number / target.number)?));
+        }
+    }
+
+    fn compare(&self, other: &Self) -> Result<Option<std::cmp::Ordering>, EvalError> {
+        let difference = self.origin == Origin::Difference || other.origin == Origin::Difference;
+        if self.unit == other.unit && self.is_absolute() != other.is_absolute() && difference {
+            return Err(EvalError::new(EvalErrorKind::UnitError, "Cannot compare an absolute quantity to a difference"));
+        } else if self.unit == other.unit {
+            return Ok(self.partial_cmp(other));
+        } else {
+            return Err(EvalError::new(
+                EvalErrorKind::UnitError,
+                &format!("Cannot compare {} to {}", self.unit.to_string(), other.unit.to_string())
+            ));
+        }
+    }
 }
 
@@ -1,20 +1,22 @@
 
 use std::ops::*;
+use std::collections::BTreeMap;
 use num::traits::Pow;
 
 use crate::Number;
 
-const BASE_UNIT_COUNT: usize = 7;
+const BASE_UNIT_COUNT: usize = 8;
 
 #[derive(Debug, Clone)]
 pub enum BaseUnit {
-    Gram = 0,
+    Kilogram = 0,
     Meter,
     Second,
     Ampere,
     Mole,
     Kelvin,
     Candela,
+    Bit,
 }
 
 impl TryFrom<usize> for BaseUnit {
@@ -24,11 +26,12 @@ impl TryFrom<usize> for BaseUnit {
         match v {
             x if x == BaseUnit::Second as usize => Ok(BaseUnit::Second),
             x if x == BaseUnit::Meter as usize => Ok(BaseUnit::Meter),
-            x if x == BaseUnit::Gram as usize => Ok(BaseUnit::Gram),
+            x if x == BaseUnit::Kilogram as usize => Ok(BaseUnit::Kilogram),
             x if x == BaseUnit::Ampere as usize => Ok(BaseUnit::Ampere),
             x if x == BaseUnit::Mole as usize => Ok(BaseUnit::Mole),
             x if x == BaseUnit::Kelvin as usize => Ok(BaseUnit::Kelvin),
             x if x == BaseUnit::Candela as usize => Ok(BaseUnit::Candela),
+            x if x == BaseUnit::Bit as usize => Ok(BaseUnit::Bit),
             _ => Err(()),
         }
     }
@@ -39,11 +42,12 @@ impl BaseUnit {
         match self {
             BaseUnit::Second => "s",
             BaseUnit::Meter => "m",
-            BaseUnit::Gram => "g",
+            BaseUnit::Kilogram => "kg",
             BaseUnit::Ampere => "A",
             BaseUnit::Mole => "mol",
             BaseUnit::Kelvin => "K",
             BaseUnit::Candela => "cd",
+            BaseUnit::Bit => "bit",
         }
     }
 }
@@ -51,48 +55,73 @@ impl BaseUnit {
 #[derive(PartialEq, Clone, Debug)]
 pub struct Unit {
     units: Vec<Number>,
+    custom: BTreeMap<String, Number>,
 }
 
 impl Unit {
     pub fn empty() -> Unit {
-        Unit { units: vec![Number::zero(); BASE_UNIT_COUNT] }
+        Unit { units: vec![Number::zero(); BASE_UNIT_COUNT], custom: BTreeMap::new() }
     }
 
     pub fn base(unit: BaseUnit) -> Unit {
         let mut units = vec![Number::zero(); BASE_UNIT_COUNT];
         units[unit as usize] = Number::one();
-        Unit { units }
+        Unit { units, custom: BTreeMap::new() }
+    }
+
+    pub fn dimension(symbol: &str) -> Unit {
+        let mut custom = BTreeMap::new();
+        custom.insert(symbol.to_owned(), Number::one());
+        Unit { units: vec![Number::zero(); BASE_UNIT_COUNT], custom }
     }
 
     pub fn is_empty(&self) -> bool {
-        self.units.iter().all(|n| n.is_zero())
+        self.units.iter().all(|n| n.is_zero()) && self.custom.is_empty()
+    }
+
+    pub fn exponents(&self) -> impl Iterator<Item = &Number> {
+        self.units.iter().chain(self.custom.values())
+    }
+}
+
+fn format_factor(symbol: &str, v: &Number) -> String {
+    if v == &Number::one() {
+        symbol.to_owned()
+    } else if v.is_integer() || !v.is_rational() {
+        format!("{}^{}", symbol, v.to_string())
+    } else {
+        format!("{}^({})", symbol, v.to_string())
     }
 }
 
 impl ToString for Unit {
     fn to_string(&self) -> String {
-        let mut ret = String::new();
-        let mut first = true;
+        let mut factors = Vec::new();
         for (i, v) in self.units.iter().enumerate() {
             if !v.is_zero() {
-                if !first {
-                    ret.push(' ');
-                }
-                let symbol = BaseUnit::try_from(i).unwrap().symbol();
-                if v == &Number::one() {
-                    ret.push_str(symbol);
-                } else if v.is_integer() || !v.is_rational() {
-                    ret.push_str(&format!("{}^{}", symbol, v.to_string()));
-                } else {
-                    ret.push_str(&format!("{}^({})", symbol, v.to_string()));
-                }
-                first = false;
+                factors.push(format_factor(BaseUnit::try_from(i).unwrap().symbol(), v));
             }
         }
-        return ret;
+        for (symbol, v) in &self.custom {
+            factors.push(format_factor(symbol, v));
+        }
+        return factors.join(" ");
     }
 }
 
+fn merge_custom<F>(mut lhs: BTreeMap<String, Number>, rhs: BTreeMap<String, Number>, op: F) -> BTreeMap<String, Number>
+where F: Fn(Number, Number) -> Number
+{
+    for (symbol, r) in rhs {
+        let l = lhs.remove(&symbol).unwrap_or(Number::zero());
+        let v = op(l, r);
+        if !v.is_zero() {
+            lhs.insert(symbol, v);
+        }
+    }
+    return lhs;
+}
+
 impl Mul for Unit {
     type Output = Unit;
 
@@ -101,7 +130,8 @@ impl Mul for Unit {
         for (l, r) in self.units.into_iter().zip(rhs.units.into_iter()) {
             units.push((l + r).unwrap());
         }
-        Unit { units }
+        let custom = merge_custom(self.custom, rhs.custom, |l, r| (l + r).unwrap());
+        Unit { units, custom }
     }
 }
 
@@ -113,7 +143,8 @@ impl Div for Unit {
         for (l, r) in self.units.into_iter().zip(rhs.units.into_iter()) {
             units.push((l - r).unwrap());
         }
-        Unit { units }
+        let custom = merge_custom(self.custom, rhs.custom, |l, r| (l - r).unwrap());
+        Unit { units, custom }
     }
 }
 
@@ -125,7 +156,12 @@ impl Pow<Number> for Unit {
         for l in self.units {
             units.push((l * rhs.clone()).unwrap());
         }
-        Unit { units }
+        let mut custom = BTreeMap::new();
+        if !rhs.is_zero() {
+            for (symbol, l) in self.custom {
+                custom.insert(symbol, (l * rhs.clone()).unwrap());
+            }
+        }
+        Unit { units, custom }
     }
 }
-
@@ -0,0 +1,62 @@
+
+use std::str::FromStr;
+
+use matheval::{Complex, Number, Value};
+
+fn complex(re: i64, im: i64) -> Complex {
+    Complex::new(Number::from_i64(re), Number::from_i64(im))
+}
+
+#[test]
+fn format() {
+    assert_eq!("3 + 4i", complex(3, 4).to_string());
+    assert_eq!("3 - 4i", complex(3, -4).to_string());
+    assert_eq!("-3 + i", complex(-3, 1).to_string());
+    assert_eq!("-i", complex(0, -1).to_string());
+    assert_eq!("2i", complex(0, 2).to_string());
+    assert_eq!("5", complex(5, 0).to_string());
+    assert_eq!("1 + 1/2 i", Complex::new(Number::one(), Number::from_i64s(1, 2)).to_string());
+    let two = Number::from_i64(2);
+    assert_eq!("sqrt(2) i", Complex::new(Number::zero(), two.sqrt()).to_string());
+    assert_eq!("ln(2) + pi i", Complex::new(two.ln(), Number::pi()).to_string());
+    let surd = (Number::one() + two.sqrt()).unwrap();
+    assert_eq!("1 - (1 + sqrt(2)) i", Complex::new(Number::one(), (-surd).unwrap()).to_string());
+}
+
+#[test]
+fn parse() {
+    assert_eq!(complex(255, 0), Complex::from_str("0xff").expect("Failed parsing number"));
+    assert!(Complex::from_str("4i").is_err());
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(complex(4, 3), (complex(1, 2) + complex(3, 1)).unwrap());
+    assert_eq!(complex(-2, 1), (complex(1, 2) - complex(3, 1)).unwrap());
+    assert_eq!(complex(5, 5), (complex(1, 2) * complex(3, -1)).unwrap());
+    assert_eq!(
+        Complex::new(Number::from_i64s(1, 10), Number::from_i64s(7, 10)),
+        (complex(1, 2) / complex(3, -1)).unwrap()
+    );
+    assert!((complex(1, 2) / complex(0, 0)).is_err());
+}
+
+#[test]
+fn power() {
+    assert_eq!(complex(-1, 0), complex(0, 1).pow(complex(2, 0)).unwrap());
+    assert_eq!(Complex::new(Number::zero(), Number::from_i64s(-1, 2)), complex(1, 1).pow(complex(-2, 0)).unwrap());
+    assert_eq!(complex(8, 0), complex(2, 0).pow(complex(3, 0)).unwrap());
+    assert!(complex(0, 0).pow(complex(0, 0)).is_err());
+    let root = complex(-8, 0).pow(Complex::real(Number::from_i64s(1, 3))).unwrap();
+    assert!((root.re().to_f64() - 1.0).abs() < 1e-12);
+    assert!((root.im().to_f64() - 3f64.sqrt()).abs() < 1e-12);
+}
+
+#[test]
+fn comparison() {
+    assert_eq!(complex(1, 0), complex(1, 2).equal(complex(1, 2)).unwrap());
+    assert_eq!(complex(1, 0), complex(1, 0).less(complex(2, 0)).unwrap());
+    assert!(complex(1, 1).less(complex(2, 0)).is_err());
+    assert!(complex(0, 1).to_bool().unwrap());
+    assert!(!complex(0, 0).to_bool().unwrap());
+}
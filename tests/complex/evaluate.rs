@@ -0,0 +1,71 @@
+
+use matheval::{Expr, Complex, ComplexContext, Context, EvalErrorKind};
+
+fn eval(source: &str) -> Complex {
+    Expr::parse(source).expect("Failed parsing expression")
+        .eval::<Complex>().expect("Evaluation failed")
+}
+
+fn assert_close(source: &str, re: f64, im: f64) {
+    let value = eval(source);
+    assert!(
+        (value.re().to_f64() - re).abs() < 1e-12 && (value.im().to_f64() - im).abs() < 1e-12,
+        "{}: expected {} + {}i, got {}", source, re, im, value.to_string()
+    );
+}
+
+#[test]
+fn imaginary_unit() {
+    assert_eq!("3 + 4i", eval("3 + 4i").to_string());
+    assert_eq!("-1", eval("i^2").to_string());
+    assert_eq!("-1/2 i", eval("1 / (1 + i)^2").to_string());
+}
+
+#[test]
+fn exact_functions() {
+    assert_eq!("2i", eval("sqrt(-4)").to_string());
+    assert_eq!("5", eval("abs(3 + 4i)").to_string());
+    assert_eq!("1 - i", eval("conj(1 + i)").to_string());
+    assert_eq!("4", eval("re(3 - 2i) + im(3 + 0i) + floor(0.5 + 1.5i) * -i").to_string());
+    assert_eq!("-2", eval("cbrt(-8)").to_string());
+    assert_eq!("0", eval("sin(0)").to_string());
+}
+
+#[test]
+fn complex_plane() {
+    let pi = std::f64::consts::PI;
+    assert_close("ln(-1)", 0.0, pi);
+    assert_close("e^(i pi)", -1.0, 0.0);
+    assert_close("log(-100)", 2.0, pi / 10f64.ln());
+    assert_close("sin(i)", 0.0, 1f64.sinh());
+    assert_close("cos(i)", 1f64.cosh(), 0.0);
+    assert_close("sinh(i pi / 2)", 0.0, 1.0);
+    assert_close("sin(asin(2 + i))", 2.0, 1.0);
+    assert_close("cos(acos(2 + i))", 2.0, 1.0);
+    assert_close("tan(atan(2 + i))", 2.0, 1.0);
+    assert_close("sinh(asinh(2 + i))", 2.0, 1.0);
+    assert_close("cosh(acosh(2 + i))", 2.0, 1.0);
+    assert_close("tanh(atanh(2 + i))", 2.0, 1.0);
+    assert_close("acosh(0)", 0.0, pi / 2.0);
+    assert_close("arg(-1)", pi, 0.0);
+    assert_close("sign(3 + 4i)", 0.6, 0.8);
+}
+
+#[test]
+fn real_only_functions() {
+    let error = Expr::parse("max(1, i)").unwrap().eval::<Complex>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::MathError, error.kind());
+    let error = Expr::parse("i > 0").unwrap().eval::<Complex>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::MathError, error.kind());
+    assert_eq!("3", eval("max(1, 3, 2)").to_string());
+}
+
+#[test]
+fn definitions() {
+    let mut context = ComplexContext::new();
+    let program = Expr::parse_program("w = e^(2 pi i / 3)\nf(z) = z^3").unwrap();
+    Expr::execute_program_in(&program, &mut context).expect("Evaluation failed");
+    let value = Expr::parse("f(w)").unwrap().eval_in::<Complex, _>(&context).expect("Evaluation failed");
+    assert!((value.re().to_f64() - 1.0).abs() < 1e-12 && value.im().to_f64().abs() < 1e-12);
+    assert!(context.get_variable("i").is_some());
+}
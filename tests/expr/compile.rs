@@ -0,0 +1,100 @@
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use matheval::{Expr, Number, NumberContext, Quantity, QuantityContext, Context, EvalErrorKind, CompiledExpr};
+
+fn assert_matches_eval(source: &str, points: &[(i64, i64)]) {
+    let expr = Expr::parse(source).expect("Failed to parse expression");
+    let mut context = NumberContext::new();
+    let compiled = expr.compile(&context, &["x", "y"]).expect("Failed to compile expression");
+    for &(x, y) in points {
+        context.set_variable("x", Number::from_i64(x));
+        context.set_variable("y", Number::from_i64(y));
+        let expected = expr.eval_in(&context).expect("Evaluation failed");
+        let actual = compiled.eval(&[Number::from_i64(x), Number::from_i64(y)]).expect("Evaluation failed");
+        assert_eq!(expected.to_string(), actual.to_string(), "{} at x = {}, y = {}", source, x, y);
+    }
+}
+
+#[test]
+fn arithmetic() {
+    assert_matches_eval("3x^2 - 2x y + 7 / y", &[(0, 1), (2, -3), (5, 7)]);
+    assert_matches_eval("-(x + y) * (x - y) / 2", &[(0, 1), (2, -3), (5, 7)]);
+}
+
+#[test]
+fn operators() {
+    assert_matches_eval("x < y && !(x == 0) || y >= 5", &[(0, 1), (2, -3), (5, 7)]);
+    assert_matches_eval("x > y ? x : y", &[(0, 1), (2, -3), (5, 7)]);
+}
+
+#[test]
+fn functions() {
+    assert_matches_eval("max(x, y, 3) + abs(x - y) + floor(x / y)", &[(0, 1), (2, -3), (5, 7)]);
+    assert_matches_eval("sum(i, x, y, i^2) + prod(x, 1, 3, x + y)", &[(0, 1), (2, -3), (5, 7)]);
+}
+
+#[test]
+fn context_definitions() {
+    let mut context = NumberContext::new();
+    Expr::parse_program("a = 3\nf(t) = a t + 1").unwrap().iter()
+        .try_for_each(|s| s.execute_in::<Number, _>(&mut context).map(|_| ()))
+        .expect("Evaluation failed");
+    let compiled = Expr::parse("f(x) - a").unwrap()
+        .compile(&context, &["x"]).expect("Failed to compile expression");
+    assert_eq!(&["x".to_owned()], compiled.params());
+    assert_eq!("10", compiled.eval(&[Number::from_i64(4)]).expect("Evaluation failed").to_string());
+    context.set_variable("a", Number::from_i64(100));
+    assert_eq!("10", compiled.eval(&[Number::from_i64(4)]).expect("Evaluation failed").to_string());
+}
+
+#[test]
+fn reentrant() {
+    for (source, expected) in [("x > 0 ? again(x - 1) + x : 0", "15"), ("sum(i, 1, x, again(i - 1)) + 1", "32")] {
+        let compiled: Rc<RefCell<Option<CompiledExpr<Number>>>> = Rc::new(RefCell::new(None));
+        let mut context = NumberContext::new();
+        let inner = compiled.clone();
+        context.set_function("again", Box::new(move |args| inner.borrow().as_ref().unwrap().eval(args)));
+        let expr = Expr::parse(source).unwrap().compile(&context, &["x"]).expect("Failed to compile expression");
+        *compiled.borrow_mut() = Some(expr);
+        let result = compiled.borrow().as_ref().unwrap().eval(&[Number::from_i64(5)]).expect("Evaluation failed");
+        assert_eq!(expected, result.to_string(), "{}", source);
+    }
+}
+
+#[test]
+fn quantities() {
+    let context = QuantityContext::new();
+    let compiled = Expr::parse("x * 2 m to cm").unwrap()
+        .compile(&context, &["x"]).expect("Failed to compile expression");
+    let result = compiled.eval(&["3".parse::<Quantity>().unwrap()]).expect("Evaluation failed");
+    assert_eq!("600", result.to_string());
+}
+
+#[test]
+fn errors() {
+    let context = NumberContext::new();
+    let error = Expr::parse("x + z").unwrap()
+        .compile::<Number>(&context, &["x"]).err().expect("Compilation should fail");
+    assert_eq!(EvalErrorKind::UnknownVariable, error.kind());
+    assert_eq!(Some("z"), error.identifier());
+    assert_eq!("z", error.expr().unwrap().to_string());
+    let error = Expr::parse("foo(x)").unwrap()
+        .compile::<Number>(&context, &["x"]).err().expect("Compilation should fail");
+    assert_eq!(EvalErrorKind::UnknownFunction, error.kind());
+    let error = Expr::parse("y = x").unwrap()
+        .compile::<Number>(&context, &["x"]).err().expect("Compilation should fail");
+    assert_eq!(EvalErrorKind::NotSupported, error.kind());
+    let compiled = Expr::parse("1 + sqrt(x / y)").unwrap()
+        .compile::<Number>(&context, &["x", "y"]).expect("Failed to compile expression");
+    let error = compiled.eval(&[Number::from_i64(1)]).expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::ArgumentMismatch, error.kind());
+    let error = compiled.eval(&[Number::from_i64(1), Number::from_i64(0)]).expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::MathError, error.kind());
+    assert_eq!("x / y", error.expr().unwrap().to_string());
+    let compiled = Expr::parse("if(x, 1, 2) + sqrt(-x)").unwrap()
+        .compile::<Number>(&context, &["x"]).expect("Failed to compile expression");
+    let error = compiled.eval(&[Number::from_i64(1)]).expect_err("Evaluation should fail");
+    assert_eq!("sqrt(-x)", error.expr().unwrap().to_string());
+}
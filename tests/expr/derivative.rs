@@ -0,0 +1,98 @@
+
+use matheval::{Expr, Number, NumberContext, Context, EvalErrorKind};
+
+fn assert_derivative(source: &str, points: &[f64]) {
+    let expr = Expr::parse(source).expect("Failed to parse expression");
+    let derivative = expr.derivative("x").expect("Failed to differentiate expression");
+    for &x in points {
+        let mut context = NumberContext::new();
+        context.set_variable("x", Number::Float(x));
+        let actual = derivative.eval_in(&context).expect("Evaluation failed").to_f64();
+        let h = 1e-6;
+        context.set_variable("x", Number::Float(x + h));
+        let upper = expr.eval_in(&context).expect("Evaluation failed").to_f64();
+        context.set_variable("x", Number::Float(x - h));
+        let lower = expr.eval_in(&context).expect("Evaluation failed").to_f64();
+        let expected = (upper - lower) / (2.0 * h);
+        assert!(
+            (actual - expected).abs() <= 1e-5 * expected.abs().max(1.0),
+            "d/dx {} at {}: expected {}, got {}", source, x, expected, actual
+        );
+    }
+}
+
+#[test]
+fn constant() {
+    assert_eq!(Expr::Literal("0".to_owned()), Expr::parse("5 y").unwrap().derivative("x").unwrap());
+    assert_eq!(Expr::Literal("1".to_owned()), Expr::parse("x").unwrap().derivative("x").unwrap());
+}
+
+#[test]
+fn arithmetic() {
+    assert_derivative("3x^2 - 2x + 7", &[-2.0, 0.5, 3.0]);
+    assert_derivative("(x + 1) * (x - 1) / (x^2 + 1)", &[-1.5, 0.0, 2.0]);
+    assert_derivative("-x^-2", &[0.5, 2.0]);
+    assert_derivative("2^x + x^x", &[0.5, 1.5]);
+}
+
+#[test]
+fn powers_at_zero() {
+    let mut context = NumberContext::new();
+    context.set_variable("x", Number::zero());
+    for (source, expected) in [("x^1", Number::one()), ("x^0 + x", Number::one()), ("3 x^2", Number::zero())] {
+        let derivative = Expr::parse(source).unwrap().derivative("x").unwrap();
+        assert_eq!(expected, derivative.eval_in(&context).expect(source), "{}", source);
+    }
+    let derivative = Expr::parse("0^x").unwrap().derivative("x").unwrap();
+    assert!(derivative.eval_in(&context).is_err());
+    context.set_variable("x", Number::one());
+    assert_eq!(Number::zero(), derivative.eval_in(&context).expect("Evaluation failed"));
+    assert_derivative("2^x + 0^(x + 1)", &[0.0, 0.5]);
+}
+
+#[test]
+fn functions() {
+    for f in ["sqrt", "cbrt", "ln", "log", "sin", "cos", "tan", "atan", "sinh", "cosh", "tanh", "asinh"] {
+        assert_derivative(&format!("{}(x^2 + 1)", f), &[-0.7, 0.3, 1.1]);
+    }
+    for f in ["asin", "acos", "atanh"] {
+        assert_derivative(&format!("{}(x / 2)", f), &[-0.7, 0.3, 1.1]);
+    }
+    assert_derivative("acosh(x + 2)", &[-0.7, 0.3, 1.1]);
+    assert_derivative("abs(x) + floor(x) + fract(x) + sign(x)", &[-0.7, 0.3, 1.1]);
+    assert_derivative("atan2(x^2, x + 3)", &[-0.7, 0.3, 1.1]);
+    assert_derivative("min(x^2, 1, x) + max(x, 2 - x)", &[-0.7, 0.3, 1.1]);
+}
+
+#[test]
+fn conditional() {
+    assert_derivative("x < 0 ? -x^3 : x^2", &[-1.0, 1.0]);
+}
+
+#[test]
+fn forms() {
+    assert_derivative("sum(i, 1, 3, x^i)", &[0.5, 2.0]);
+    assert_derivative("integrate(t, 0, 1, sin(x t))", &[0.5, 2.0]);
+    let derivative = Expr::parse("sum(x, 1, 3, x^2)").unwrap().derivative("x").unwrap();
+    assert_eq!(Expr::Literal("0".to_owned()), derivative);
+}
+
+#[test]
+fn unknown_function() {
+    let error = Expr::parse("f(x) + 2").unwrap().derivative("x").expect_err("Differentiation should fail");
+    assert_eq!(EvalErrorKind::UnknownFunction, error.kind());
+    assert_eq!(Some("f"), error.identifier());
+    assert_eq!(
+        Expr::Literal("0".to_owned()),
+        Expr::parse("f(y)").unwrap().derivative("x").expect("Failed to differentiate expression")
+    );
+}
+
+#[test]
+fn integer_operators() {
+    assert_derivative("x^2 % 3", &[0.5, 2.0]);
+    assert_derivative("5 % x", &[1.5, 2.2]);
+    assert_derivative("x // 2 + x", &[0.5, 3.0]);
+    let error = Expr::parse("x & 1").unwrap().derivative("x").expect_err("Differentiation should fail");
+    assert_eq!(EvalErrorKind::NotSupported, error.kind());
+}
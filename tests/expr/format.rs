@@ -16,6 +16,11 @@ fn neg() {
     assert_eq!("-a1", Expr::Neg(Box::new(Expr::Variable("a1".to_owned()))).to_string());
 }
 
+#[test]
+fn not() {
+    assert_eq!("!a1", Expr::Not(Box::new(Expr::Variable("a1".to_owned()))).to_string());
+}
+
 #[test]
 fn add() {
     assert_eq!("a1 + a2", Expr::Add(
@@ -237,3 +242,155 @@ fn pow_no_parens() {
     ).to_string());
 }
 
+
+#[test]
+fn comparison() {
+    assert_eq!("a1 >= a2", Expr::GreaterEqual(
+        Box::new(Expr::Variable("a1".to_owned())),
+        Box::new(Expr::Variable("a2".to_owned()))
+    ).to_string());
+    assert_eq!("a1 == a2", Expr::Equal(
+        Box::new(Expr::Variable("a1".to_owned())),
+        Box::new(Expr::Variable("a2".to_owned()))
+    ).to_string());
+}
+
+#[test]
+fn boolean_parens() {
+    assert_eq!("(a1 || a2) && !(a3 < a4)", Expr::And(
+        Box::new(Expr::Or(
+            Box::new(Expr::Variable("a1".to_owned())),
+            Box::new(Expr::Variable("a2".to_owned()))
+        )),
+        Box::new(Expr::Not(Box::new(Expr::Less(
+            Box::new(Expr::Variable("a3".to_owned())),
+            Box::new(Expr::Variable("a4".to_owned()))
+        ))))
+    ).to_string());
+}
+
+#[test]
+fn boolean_round_trip() {
+    for source in ["a1 < a2 + 1 || !a3 && a4 != a5", "a1 > a2 == (a3 <= a4)", "!(a1 || a2) >= -a3"] {
+        let parsed = Expr::parse(source).expect("Failed to parse expression");
+        assert_eq!(source, parsed.to_string());
+        assert_eq!(parsed, Expr::parse(&parsed.to_string()).expect("Failed to parse formatted expression"));
+    }
+}
+
+#[test]
+fn conditional() {
+    assert_eq!("a1 < a2 ? a3 : a4 ? a5 : a6", Expr::If(
+        Box::new(Expr::Less(
+            Box::new(Expr::Variable("a1".to_owned())),
+            Box::new(Expr::Variable("a2".to_owned()))
+        )),
+        Box::new(Expr::Variable("a3".to_owned())),
+        Box::new(Expr::If(
+            Box::new(Expr::Variable("a4".to_owned())),
+            Box::new(Expr::Variable("a5".to_owned())),
+            Box::new(Expr::Variable("a6".to_owned()))
+        ))
+    ).to_string());
+    assert_eq!("(a1 ? a2 : a3) ? a4 : a5 + (a6 ? a7 : a8)", Expr::If(
+        Box::new(Expr::If(
+            Box::new(Expr::Variable("a1".to_owned())),
+            Box::new(Expr::Variable("a2".to_owned())),
+            Box::new(Expr::Variable("a3".to_owned()))
+        )),
+        Box::new(Expr::Variable("a4".to_owned())),
+        Box::new(Expr::Add(
+            Box::new(Expr::Variable("a5".to_owned())),
+            Box::new(Expr::If(
+                Box::new(Expr::Variable("a6".to_owned())),
+                Box::new(Expr::Variable("a7".to_owned())),
+                Box::new(Expr::Variable("a8".to_owned()))
+            ))
+        ))
+    ).to_string());
+}
+
+#[test]
+fn assignment() {
+    assert_eq!("v = a1 ? a2 : a3", Expr::Assign(
+        "v".to_owned(),
+        Box::new(Expr::If(
+            Box::new(Expr::Variable("a1".to_owned())),
+            Box::new(Expr::Variable("a2".to_owned())),
+            Box::new(Expr::Variable("a3".to_owned()))
+        ))
+    ).to_string());
+}
+
+#[test]
+fn function_definition() {
+    assert_eq!("f(x, y) = x * y", Expr::Define(
+        "f".to_owned(),
+        vec!["x".to_owned(), "y".to_owned()],
+        Box::new(Expr::Mul(
+            Box::new(Expr::Variable("x".to_owned())),
+            Box::new(Expr::Variable("y".to_owned()))
+        ))
+    ).to_string());
+}
+
+#[test]
+fn conversion() {
+    assert_eq!("a1 * a2 to a3 / a4", Expr::Convert(
+        Box::new(Expr::Mul(
+            Box::new(Expr::Variable("a1".to_owned())),
+            Box::new(Expr::Variable("a2".to_owned()))
+        )),
+        Box::new(Expr::Div(
+            Box::new(Expr::Variable("a3".to_owned())),
+            Box::new(Expr::Variable("a4".to_owned()))
+        ))
+    ).to_string());
+}
+
+#[test]
+fn plus_minus() {
+    assert_eq!("a1 ± a2 * a3", Expr::Mul(
+        Box::new(Expr::PlusMinus(
+            Box::new(Expr::Variable("a1".to_owned())),
+            Box::new(Expr::Variable("a2".to_owned()))
+        )),
+        Box::new(Expr::Variable("a3".to_owned()))
+    ).to_string());
+    assert_eq!("(a1 + a2) ± a3", Expr::PlusMinus(
+        Box::new(Expr::Add(
+            Box::new(Expr::Variable("a1".to_owned())),
+            Box::new(Expr::Variable("a2".to_owned()))
+        )),
+        Box::new(Expr::Variable("a3".to_owned()))
+    ).to_string());
+}
+
+#[test]
+fn integer_operators() {
+    assert_eq!("(a1 | a2) & a3 xor a4 << a5 % a6", Expr::BitXor(
+        Box::new(Expr::BitAnd(
+            Box::new(Expr::BitOr(
+                Box::new(Expr::Variable("a1".to_owned())),
+                Box::new(Expr::Variable("a2".to_owned()))
+            )),
+            Box::new(Expr::Variable("a3".to_owned()))
+        )),
+        Box::new(Expr::ShiftLeft(
+            Box::new(Expr::Variable("a4".to_owned())),
+            Box::new(Expr::Mod(
+                Box::new(Expr::Variable("a5".to_owned())),
+                Box::new(Expr::Variable("a6".to_owned()))
+            ))
+        ))
+    ).to_string());
+    assert_eq!("~(a1 + a2) // a3", Expr::FloorDiv(
+        Box::new(Expr::BitNot(
+            Box::new(Expr::Add(
+                Box::new(Expr::Variable("a1".to_owned())),
+                Box::new(Expr::Variable("a2".to_owned()))
+            ))
+        )),
+        Box::new(Expr::Variable("a3".to_owned()))
+    ).to_string());
+}
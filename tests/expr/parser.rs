@@ -416,6 +416,169 @@ fn precedence_add_mul_pow() {
     ), parsed);
 }
 
+#[test]
+fn simple_comparison() {
+    let parsed = Expr::parse("a1 <= a2")
+        .expect("Failed to parse expression");
+    assert_eq!(Expr::LessEqual(
+        Box::new(Expr::Variable("a1".to_owned())),
+        Box::new(Expr::Variable("a2".to_owned()))
+    ), parsed);
+    let parsed = Expr::parse("a1 != a2")
+        .expect("Failed to parse expression");
+    assert_eq!(Expr::NotEqual(
+        Box::new(Expr::Variable("a1".to_owned())),
+        Box::new(Expr::Variable("a2".to_owned()))
+    ), parsed);
+}
+
+#[test]
+fn simple_not() {
+    let parsed = Expr::parse("!a1")
+        .expect("Failed to parse expression");
+    assert_eq!(Expr::Not(Box::new(Expr::Variable("a1".to_owned()))), parsed);
+}
+
+#[test]
+fn precedence_boolean() {
+    let parsed = Expr::parse("a1 || a2 + a3 < a4 && !a5 == a6")
+        .expect("Failed to parse expression");
+    assert_eq!(Expr::Or(
+        Box::new(Expr::Variable("a1".to_owned())),
+        Box::new(Expr::And(
+            Box::new(Expr::Less(
+                Box::new(Expr::Add(
+                    Box::new(Expr::Variable("a2".to_owned())),
+                    Box::new(Expr::Variable("a3".to_owned()))
+                )),
+                Box::new(Expr::Variable("a4".to_owned()))
+            )),
+            Box::new(Expr::Equal(
+                Box::new(Expr::Not(Box::new(Expr::Variable("a5".to_owned())))),
+                Box::new(Expr::Variable("a6".to_owned()))
+            ))
+        ))
+    ), parsed);
+}
+
+#[test]
+fn conditional() {
+    let parsed = Expr::parse("a1 ? a2 : a3 ? a4 : a5")
+        .expect("Failed to parse expression");
+    assert_eq!(Expr::If(
+        Box::new(Expr::Variable("a1".to_owned())),
+        Box::new(Expr::Variable("a2".to_owned())),
+        Box::new(Expr::If(
+            Box::new(Expr::Variable("a3".to_owned())),
+            Box::new(Expr::Variable("a4".to_owned())),
+            Box::new(Expr::Variable("a5".to_owned()))
+        ))
+    ), parsed);
+    let parsed = Expr::parse("if(a1 || a2, a3, a4)")
+        .expect("Failed to parse expression");
+    assert_eq!(Expr::If(
+        Box::new(Expr::Or(
+            Box::new(Expr::Variable("a1".to_owned())),
+            Box::new(Expr::Variable("a2".to_owned()))
+        )),
+        Box::new(Expr::Variable("a3".to_owned())),
+        Box::new(Expr::Variable("a4".to_owned()))
+    ), parsed);
+}
+
+#[test]
+fn error_conditional() {
+    let error = Expr::parse("a1 ? a2 a3").expect_err("Missing ':' in conditional");
+    assert!(error.expected().contains(&"':'".to_owned()));
+    assert_eq!("end of input", error.found());
+}
+
+#[test]
+fn assignment() {
+    let parsed = Expr::parse("v = 3 m / s")
+        .expect("Failed to parse assignment");
+    assert_eq!(Expr::Assign(
+        "v".to_owned(),
+        Box::new(Expr::Div(
+            Box::new(Expr::Mul(
+                Box::new(Expr::Literal("3".to_owned())),
+                Box::new(Expr::Variable("m".to_owned()))
+            )),
+            Box::new(Expr::Variable("s".to_owned()))
+        ))
+    ), parsed);
+}
+
+#[test]
+fn function_definition() {
+    let parsed = Expr::parse("f(x, y) = x^2 + y")
+        .expect("Failed to parse function definition");
+    assert_eq!(Expr::Define(
+        "f".to_owned(),
+        vec!["x".to_owned(), "y".to_owned()],
+        Box::new(Expr::Add(
+            Box::new(Expr::Pow(
+                Box::new(Expr::Variable("x".to_owned())),
+                Box::new(Expr::Literal("2".to_owned()))
+            )),
+            Box::new(Expr::Variable("y".to_owned()))
+        ))
+    ), parsed);
+}
+
+#[test]
+fn error_assignment() {
+    let error = Expr::parse("2 x = 5").expect_err("Cannot assign to a product");
+    assert_eq!(4, error.position());
+    assert_eq!("Expected a variable or function to assign to", error.message());
+    let error = Expr::parse("f(x, 2) = x").expect_err("Parameters must be variables");
+    assert_eq!(5, error.position());
+    assert_eq!(5..6, error.span());
+    let error = Expr::parse("f(x, 2 y) = x").expect_err("Parameters must be variables");
+    assert_eq!(5..8, error.span());
+    let error = Expr::parse("f(x, g(y, 1)) = x").expect_err("Parameters must be variables");
+    assert_eq!(5..12, error.span());
+    let error = Expr::parse("f(x, x) = x").expect_err("Parameters must be distinct");
+    assert_eq!(5..6, error.span());
+    assert!(Expr::parse("a = b = c").is_err());
+    assert!(Expr::parse("(a = b)").is_err());
+}
+
+#[test]
+fn conversion() {
+    let parsed = Expr::parse("5 m to ft")
+        .expect("Failed to parse conversion");
+    assert_eq!(Expr::Convert(
+        Box::new(Expr::Mul(
+            Box::new(Expr::Literal("5".to_owned())),
+            Box::new(Expr::Variable("m".to_owned()))
+        )),
+        Box::new(Expr::Variable("ft".to_owned()))
+    ), parsed);
+}
+
+#[test]
+fn program() {
+    let parsed = Expr::parse_program("a = 1; b = (a\n + 2)\n\n;a + b;")
+        .expect("Failed to parse program");
+    assert_eq!(vec![
+        Expr::parse("a = 1").unwrap(),
+        Expr::parse("b = a + 2").unwrap(),
+        Expr::parse("a + b").unwrap(),
+    ], parsed);
+    assert!(Expr::parse_program("\n ; \n").expect("Failed to parse empty program").is_empty());
+}
+
+#[test]
+fn error_program() {
+    let error = Expr::parse_program("a = 1\nb = a +\n2").expect_err("Statement ends after operator");
+    assert_eq!(13, error.position());
+    assert_eq!("new line", error.found());
+    let error = Expr::parse_program("a = 1; b)").expect_err("Unmatched closing bracket");
+    assert_eq!(8, error.position());
+    assert!(Expr::parse("a = 1; b = 2").is_err());
+}
+
 #[test]
 fn error() {
     assert!(Expr::parse("").is_err(), "Empty expressions are illegal");
@@ -440,3 +603,198 @@ fn error() {
     assert!(Expr::parse("a + (b * (c *) 2)").is_err(), "Misplaced parens");
 }
 
+
+#[test]
+fn error_span() {
+    let error = Expr::parse("a + b) * 2").expect_err("Unexpected closing paren ')'");
+    assert_eq!(5, error.position());
+    assert_eq!(5..6, error.span());
+    assert_eq!("')'", error.found());
+    let error = Expr::parse("2 * foo bar@ + 1").expect_err("Unexpected character '@'");
+    assert_eq!(11..12, error.span());
+    assert_eq!("unknown character '@'", error.found());
+    let error = Expr::parse("a + ").expect_err("Missing second '+' operand");
+    assert_eq!(3..3, error.span());
+    assert_eq!("end of input", error.found());
+}
+
+#[test]
+fn error_byte_span() {
+    let error = Expr::parse("äöü + ) * 2").expect_err("Unexpected closing paren ')'");
+    assert_eq!(6..7, error.span());
+    assert_eq!(9..10, error.byte_span());
+}
+
+#[test]
+fn error_expected() {
+    let error = Expr::parse("sin(a + b, 2").expect_err("Missing closing paren ')' for function");
+    assert_eq!(["','", "')'"], error.expected());
+    assert_eq!("end of input", error.found());
+    let error = Expr::parse("a * / b").expect_err("Not a unary operator '/'");
+    assert!(error.expected().contains(&"identifier".to_owned()));
+    assert_eq!("'/'", error.found());
+}
+
+#[test]
+fn error_mismatched_brackets() {
+    let error = Expr::parse("2 * [3 + 4)").expect_err("Mismatched brackets");
+    assert_eq!(10..11, error.span());
+    assert_eq!(Some(4..5), error.related_span());
+    assert_eq!(["']'"], error.expected());
+    let error = Expr::parse("1 + (2 * 3").expect_err("Missing closing paren ')'");
+    assert_eq!(10..10, error.span());
+    assert_eq!(Some(4..5), error.related_span());
+}
+
+#[test]
+fn error_render() {
+    let error = Expr::parse("1 + (2 * 3").expect_err("Missing closing paren ')'");
+    assert_eq!(
+        "Error at position 10: Expected matching closing bracket (expected ')', found end of input)\n1 + (2 * 3\n    ^     ^",
+        error.render("1 + (2 * 3")
+    );
+    let error = Expr::parse("2 * foo + 1 12 $").expect_err("Unexpected character '$'");
+    assert!(error.render("2 * foo + 1 12 $").ends_with("2 * foo + 1 12 $\n               ^"));
+    let error = Expr::parse("1 +\n2 + * 3").expect_err("Not a unary operator '*'");
+    assert!(error.render("1 +\n2 + * 3").ends_with("\n2 + * 3\n    ^"));
+    let error: Box<dyn std::error::Error> = Box::new(error);
+    assert!(error.to_string().starts_with("Error at position 8: Expected an expression"));
+}
+
+#[test]
+fn other_brackets() {
+    let parsed = Expr::parse("[a + b] * {c}").expect("Failed to parse expression");
+    assert_eq!(Expr::Mul(
+        Box::new(Expr::Add(
+            Box::new(Expr::Variable("a".to_owned())),
+            Box::new(Expr::Variable("b".to_owned()))
+        )),
+        Box::new(Expr::Variable("c".to_owned())),
+    ), parsed);
+}
+
+#[test]
+fn plus_minus() {
+    let expected = Expr::Mul(
+        Box::new(Expr::PlusMinus(
+            Box::new(Expr::Literal("9.81".to_owned())),
+            Box::new(Expr::Literal("0.02".to_owned()))
+        )),
+        Box::new(Expr::Variable("m".to_owned())),
+    );
+    assert_eq!(expected, Expr::parse("9.81 ± 0.02 m").expect("Failed to parse uncertain literal"));
+    assert_eq!(expected, Expr::parse("9.81 +- 0.02 m").expect("Failed to parse uncertain literal"));
+    let parsed = Expr::parse("2 ± 1^2").expect("Failed to parse uncertain literal");
+    assert_eq!(Expr::PlusMinus(
+        Box::new(Expr::Literal("2".to_owned())),
+        Box::new(Expr::Pow(
+            Box::new(Expr::Literal("1".to_owned())),
+            Box::new(Expr::Literal("2".to_owned()))
+        ))
+    ), parsed);
+    assert_eq!(
+        Expr::Add(
+            Box::new(Expr::Literal("1".to_owned())),
+            Box::new(Expr::Neg(Box::new(Expr::Literal("2".to_owned()))))
+        ),
+        Expr::parse("1+-2").expect("Failed to parse sum with negative operand")
+    );
+    assert_eq!(
+        Expr::Mul(
+            Box::new(Expr::Literal("2".to_owned())),
+            Box::new(Expr::Neg(Box::new(Expr::Literal("3".to_owned()))))
+        ),
+        Expr::parse("2*+-3").expect("Failed to parse product with signed operand")
+    );
+}
+
+#[test]
+fn factorial() {
+    let factorial = |e| Expr::Function("factorial".to_owned(), vec![e]);
+    assert_eq!(
+        factorial(Expr::Literal("5".to_owned())),
+        Expr::parse("5!").expect("Failed to parse factorial")
+    );
+    assert_eq!(
+        Expr::Pow(
+            Box::new(Expr::Literal("2".to_owned())),
+            Box::new(factorial(factorial(Expr::Variable("n".to_owned()))))
+        ),
+        Expr::parse("2^n!!").expect("Failed to parse factorial")
+    );
+    assert_eq!(
+        Expr::Mul(
+            Box::new(factorial(Expr::Literal("3".to_owned()))),
+            Box::new(Expr::Variable("x".to_owned()))
+        ),
+        Expr::parse("3! x").expect("Failed to parse factorial")
+    );
+    assert_eq!(
+        Expr::NotEqual(
+            Box::new(Expr::Variable("a".to_owned())),
+            Box::new(Expr::Variable("b".to_owned()))
+        ),
+        Expr::parse("a != b").expect("Failed to parse comparison")
+    );
+}
+
+#[test]
+fn integer_operators() {
+    let var = |name: &str| Box::new(Expr::Variable(name.to_owned()));
+    assert_eq!(
+        Expr::BitOr(
+            var("a"),
+            Box::new(Expr::BitXor(
+                var("b"),
+                Box::new(Expr::BitAnd(
+                    var("c"),
+                    Box::new(Expr::ShiftLeft(
+                        var("d"),
+                        Box::new(Expr::Add(var("e"), var("f")))
+                    ))
+                ))
+            ))
+        ),
+        Expr::parse("a | b xor c & d << e + f").expect("Failed to parse bitwise operators")
+    );
+    assert_eq!(
+        Expr::Add(
+            Box::new(Expr::Mod(var("a"), var("b"))),
+            Box::new(Expr::FloorDiv(Box::new(Expr::Mul(var("c"), var("d"))), var("e")))
+        ),
+        Expr::parse("a % b + c d // e").expect("Failed to parse modulo")
+    );
+    assert_eq!(
+        Expr::Mul(
+            Box::new(Expr::Mod(var("a"), Box::new(Expr::Mul(var("b"), var("c"))))),
+            var("d")
+        ),
+        Expr::parse("a % b c * d").expect("Failed to parse modulo")
+    );
+    assert_eq!(
+        Expr::ShiftRight(Box::new(Expr::BitNot(var("a"))), var("b")),
+        Expr::parse("~a >> b").expect("Failed to parse complement")
+    );
+    assert_eq!(
+        Expr::And(Box::new(Expr::BitAnd(var("a"), var("b"))), var("c")),
+        Expr::parse("a & b && c").expect("Failed to parse bitwise and")
+    );
+    assert_eq!(
+        Expr::Less(Box::new(Expr::BitOr(var("a"), var("b"))), var("c")),
+        Expr::parse("a | b < c").expect("Failed to parse bitwise or")
+    );
+}
+
+#[test]
+fn implicit_multiplication() {
+    let var = |name: &str| Box::new(Expr::Variable(name.to_owned()));
+    let lit = |value: &str| Box::new(Expr::Literal(value.to_owned()));
+    assert_eq!(
+        Expr::Mul(Box::new(Expr::Div(Box::new(Expr::Mul(lit("6"), var("m"))), lit("2"))), var("s")),
+        Expr::parse("6 m / 2 s").expect("Failed to parse implicit multiplication")
+    );
+    assert_eq!(
+        Expr::Mul(Box::new(Expr::Div(lit("1"), lit("72"))), var("in")),
+        Expr::parse("1/72 in").expect("Failed to parse fractional coefficient")
+    );
+}
@@ -0,0 +1,137 @@
+
+use matheval::{Expr, Number, NumberContext, Quantity, QuantityContext, Context};
+
+fn simplified(source: &str) -> String {
+    Expr::parse(source).expect("Failed to parse expression").simplify().to_string()
+}
+
+fn assert_same_value(source: &str) {
+    let expr = Expr::parse(source).expect("Failed to parse expression");
+    let simple = expr.simplify();
+    for (x, y) in [
+        (Number::from_i64s(3, 7), Number::from_i64(-2)), (Number::from_i64(5), Number::from_i64s(1, 2)),
+        (Number::from_i64(0), Number::from_i64(0)), (Number::from_i64(-1), Number::from_i64(-3)),
+    ] {
+        let mut context = NumberContext::new();
+        context.set_variable("x", x);
+        context.set_variable("y", y);
+        match (expr.eval_in(&context), simple.eval_in(&context)) {
+            (Err(_), Err(_)) => {},
+            (Ok(expected), Ok(actual)) => {
+                if expected.is_rational() {
+                    assert_eq!(expected, actual, "{} simplified to {}", source, simple.to_string());
+                } else {
+                    let (e, a) = (expected.to_f64(), actual.to_f64());
+                    let close = e == a || (e - a).abs() <= 1e-12 * e.abs().max(1.0) || (e.is_nan() && a.is_nan());
+                    assert!(close, "{} simplified to {}", source, simple.to_string());
+                }
+            },
+            (expected, actual) => {
+                panic!("{} simplified to {}: {:?} became {:?}", source, simple.to_string(), expected, actual);
+            },
+        }
+    }
+}
+
+fn assert_same_quantity(source: &str) {
+    let expr = Expr::parse(source).expect("Failed to parse expression");
+    let simple = expr.simplify();
+    for y in [None, Some("3 m"), Some("4 s")] {
+        let mut context = QuantityContext::new();
+        context.set_variable("x", Expr::parse("2 m").unwrap().eval::<Quantity>().unwrap());
+        if let Some(y) = y {
+            context.set_variable("y", Expr::parse(y).unwrap().eval::<Quantity>().unwrap());
+        }
+        match (expr.eval_in(&context), simple.eval_in(&context)) {
+            (Ok(expected), Ok(actual)) => assert_eq!(expected, actual, "{} simplified to {}", source, simple.to_string()),
+            (Err(expected), Err(actual)) => {
+                assert_eq!(expected.kind(), actual.kind(), "{} simplified to {}", source, simple.to_string());
+            },
+            (expected, actual) => {
+                panic!("{} simplified to {}: {:?} became {:?}", source, simple.to_string(), expected, actual);
+            },
+        }
+    }
+}
+
+#[test]
+fn constant_folding() {
+    assert_eq!("7", simplified("1 + 2 * 3"));
+    assert_eq!("1 / 3", simplified("2 / 6"));
+    assert_eq!("-5 / 4", simplified("0.25 - 1.5"));
+    assert_eq!("1 / 8", simplified("2^-3"));
+    assert_eq!("2^(1 / 2)", simplified("2^0.5"));
+    assert_eq!("1", simplified("3 > 2 && 1"));
+}
+
+#[test]
+fn identities() {
+    assert_eq!("x + 0 * y + 0", simplified("0 * y + x + 0"));
+    assert_eq!("0", simplified("0 * 5 + 3 - 3"));
+    assert_eq!("x", simplified("1 * x^1 / 1"));
+    assert_eq!("x", simplified("--x"));
+    assert_eq!("1", simplified("2^0"));
+    assert_eq!("x^0", simplified("x^0"));
+    assert_eq!("1^x", simplified("1^x"));
+    assert_eq!("1", simplified("1^3"));
+    assert_eq!("-x + 0", simplified("0 - x"));
+    assert_eq!("sin(x)", simplified("sin(x * 1)"));
+    assert_eq!("sin(x + 0)", simplified("sin(x + 0)"));
+}
+
+#[test]
+fn like_terms() {
+    assert_eq!("0 * x", simplified("x - x"));
+    assert_eq!("3 * x", simplified("x + 2x"));
+    assert_eq!("x^2", simplified("x * x"));
+    assert_eq!("x^2 * y / 2", simplified("x * y * x / 2"));
+    assert_eq!("x - y", simplified("2x - y - x"));
+    assert_eq!("1 / x", simplified("x / x^2"));
+}
+
+#[test]
+fn canonical_order() {
+    assert_eq!(simplified("y + x"), simplified("x + y"));
+    assert_eq!(simplified("y * x * 2"), simplified("2 * x * y"));
+    assert_eq!(simplified("sin(x) + x^2 + 1"), simplified("1 + x^2 + sin(x)"));
+}
+
+#[test]
+fn derivative() {
+    let derivative = Expr::parse("x^3 + 2x").unwrap().derivative("x").unwrap();
+    assert_eq!("3 * x^2 + 2", derivative.simplify().to_string());
+    let derivative = Expr::parse("sin(x^2)").unwrap().derivative("x").unwrap();
+    assert_eq!("2 * cos(x^2) * x", derivative.simplify().to_string());
+}
+
+#[test]
+fn preserves_value() {
+    for source in [
+        "1 + 2 * 3 - x / 4", "x * y - y * x + 2", "(x + 1)^2 * (x + 1)^-1", "x^2 * x^-3 * y",
+        "-(-(x - y))", "x / (2 * y) + 3 / (6 * y) * x", "2^x * 2^x", "(x * y)^3 / y^2",
+        "sin(x)^2 + cos(x)^2 + 0 * x", "x > y ? x - 0 : y * 1", "sqrt(x^2 + 2 x + 1) + ln(1 * x^2)",
+        "x / x", "0^0", "x^0 * y", "x^0.5 * x^0.5", "(x^0.5)^2", "(x^-1)^-1", "0 * (1 / x)", "y / x - y / x",
+        "x^-0.5 * x", "x^-1 * x^1.5",
+    ] {
+        assert_same_value(source);
+    }
+}
+
+#[test]
+fn preserves_units() {
+    for source in [
+        "x + 0", "0 * x", "x - x", "x * y - y * x", "x + 1 - 1", "2x - x", "x * y / y", "1^x", "x^0",
+        "(x + y) - y", "0 * x + y", "x / 2 + x / 2", "3 + 0 * x",
+    ] {
+        assert_same_quantity(source);
+    }
+}
+
+#[test]
+fn integer_operators() {
+    assert_eq!("5", simplified("17 % 6 + 12 & 7 xor 4"));
+    assert_eq!("-2", simplified("~(3 // 2)"));
+    assert_eq!("x % 3", simplified("x % (1 + 2)"));
+    assert_same_value("(x + 1) % y");
+    assert_same_value("x // y");
+}
@@ -0,0 +1,32 @@
+
+use matheval::{F64Context, Context};
+
+#[test]
+fn has_constants() {
+    let cnxt = F64Context::new();
+    assert_eq!(Some(std::f64::consts::PI), cnxt.get_variable("pi"));
+    assert_eq!(Some(std::f64::consts::E), cnxt.get_variable("e"));
+}
+
+#[test]
+fn has_number_context_functions() {
+    let cnxt = F64Context::new();
+    for name in [
+        "floor", "ceil", "round", "trunc", "fract", "abs", "sign", "sqrt", "cbrt", "ln", "log",
+        "sin", "cos", "tan", "asin", "acos", "atan", "atan2", "sinh", "cosh", "tanh",
+        "asinh", "acosh", "atanh", "min", "max",
+    ] {
+        cnxt.get_function(name).unwrap_or_else(|| panic!("Context doesn't contain {}", name));
+    }
+}
+
+#[test]
+fn argument_count() {
+    let cnxt = F64Context::new();
+    let sin = cnxt.get_function("sin").unwrap();
+    assert!(sin(&[]).is_err());
+    assert!(sin(&[1.0, 2.0]).is_err());
+    let max = cnxt.get_function("max").unwrap();
+    assert!(max(&[]).is_err());
+    assert_eq!(5.0, max(&[1.0, 5.0, -2.0]).expect("Evaluation failed"));
+}
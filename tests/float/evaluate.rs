@@ -0,0 +1,60 @@
+
+use matheval::{Expr, F64Context, Context, EvalErrorKind};
+
+fn eval(source: &str) -> f64 {
+    Expr::parse(source).expect("Failed parsing expression")
+        .eval::<f64>().expect("Evaluation failed")
+}
+
+#[test]
+fn literals() {
+    assert_eq!(9.0, eval("0b1001"));
+    assert_eq!(5135.0, eval("0o12017"));
+    assert_eq!(255.5, eval("0xff.8"));
+    assert_eq!(1.5e-3, eval("1.5e-3"));
+    let error = Expr::Literal("12z".to_owned()).eval::<f64>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::InvalidLiteral, error.kind());
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(7.0, eval("1 + 2 * 3"));
+    assert_eq!(0.75, eval("3 / 4"));
+    assert_eq!(-8.0, eval("-2^3"));
+    assert_eq!(f64::INFINITY, eval("1 / 0"));
+    assert!(eval("0 / 0").is_nan());
+}
+
+#[test]
+fn operators() {
+    assert_eq!(1.0, eval("1 < 2 && !(3 == 4)"));
+    assert_eq!(0.0, eval("0 / 0 == 0 / 0"));
+    assert_eq!(2.0, eval("1 > 2 ? 1 : 2"));
+}
+
+#[test]
+fn functions() {
+    assert_eq!(2.0, eval("sqrt(4)"));
+    assert_eq!(3.0, eval("log(1000)"));
+    assert_eq!(-1.0, eval("sign(-3) * abs(-1)"));
+    assert_eq!(3.0, eval("max(1, 3, 2)"));
+    assert!((eval("sin(pi / 6)") - 0.5).abs() < 1e-12);
+    assert!((eval("integrate(x, 0, 1, x^2)") - 1.0 / 3.0).abs() < 1e-12);
+}
+
+#[test]
+fn definitions() {
+    let mut context = F64Context::new();
+    let program = Expr::parse_program("k = 2\nf(x) = k x + 1").unwrap();
+    Expr::execute_program_in(&program, &mut context).expect("Evaluation failed");
+    assert_eq!(Some(2.0), context.get_variable("k"));
+    assert_eq!(7.0, Expr::parse("f(3)").unwrap().eval_in(&context).expect("Evaluation failed"));
+}
+
+#[test]
+fn compiled() {
+    let context = F64Context::new();
+    let compiled = Expr::parse("x^2 + sin(y) * 0x10").unwrap()
+        .compile(&context, &["x", "y"]).expect("Failed to compile expression");
+    assert_eq!(9.0, compiled.eval(&[3.0, 0.0]).expect("Evaluation failed"));
+}
@@ -0,0 +1,80 @@
+
+use matheval::{Expr, Interval, IntervalContext, Context, Number, F64Context, EvalErrorKind};
+
+fn eval(source: &str) -> Interval {
+    Expr::parse(source).expect("Failed parsing expression")
+        .eval::<Interval>().expect("Evaluation failed")
+}
+
+fn assert_encloses(source: &str, lo: f64, hi: f64) {
+    let expr = Expr::parse(source).expect("Failed parsing expression");
+    let mut context = IntervalContext::new();
+    context.set_variable("x", Interval::new(Number::Float(lo), Number::Float(hi)).unwrap());
+    let enclosure = expr.eval_in::<Interval, _>(&context).expect("Evaluation failed");
+    let mut points = F64Context::new();
+    for i in 0..=64 {
+        let x = lo + (hi - lo) * i as f64 / 64.0;
+        points.set_variable("x", x);
+        let y = expr.eval_in::<f64, _>(&points).expect("Evaluation failed");
+        assert!(
+            enclosure.lower().to_f64() <= y && y <= enclosure.upper().to_f64(),
+            "{} at x = {}: {} not in {}", source, x, y, enclosure.to_string()
+        );
+    }
+}
+
+#[test]
+fn exact_endpoints() {
+    assert_eq!("[4/3, 7/3]", eval("interval(1, 2) + 1/3").to_string());
+    assert_eq!("[0, 4]", eval("interval(-1, 2)^2").to_string());
+    assert_eq!("[2, 3]", eval("sqrt(interval(4, 9))").to_string());
+    assert_eq!("3/2", eval("mid(interval(1, 2))").to_string());
+    assert_eq!("3", eval("width(interval(1, 2)^2)").to_string());
+    assert_eq!("[0, 3]", eval("abs(interval(-3, 1))").to_string());
+    assert_eq!("[1, 3]", eval("min(interval(1, 5), interval(2, 3))").to_string());
+}
+
+#[test]
+fn enclosures() {
+    assert_encloses("sin(x)", 0.0, 2.0);
+    assert_encloses("cos(x)", -1.0, 5.0);
+    assert_encloses("sin(x) * cos(x) + x^2", -0.5, 0.7);
+    assert_encloses("e^x", -1.0, 1.0);
+    assert_encloses("ln(x) / sqrt(x)", 0.5, 3.0);
+    assert_encloses("tan(x) + atan(x)", -1.0, 1.0);
+    assert_encloses("cosh(x) - sinh(x) * tanh(x)", -2.0, 1.0);
+    assert_encloses("asin(x) + acos(x) + atanh(x)", -0.5, 0.5);
+    assert_encloses("acosh(x) + asinh(x) + cbrt(x) + log(x)", 1.0, 4.0);
+    assert_encloses("floor(x) + fract(x) + abs(x) + sign(x)", -1.5, 1.5);
+    assert_encloses("x^x", 0.5, 2.0);
+}
+
+#[test]
+fn constants() {
+    let pi = eval("pi");
+    assert!(pi.lower().to_f64() < std::f64::consts::PI && pi.upper().to_f64() > std::f64::consts::PI);
+    assert!(eval("sin(pi)").contains(&Number::zero()));
+}
+
+#[test]
+fn errors() {
+    let error = Expr::parse("1 / interval(-1, 1)").unwrap().eval::<Interval>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::MathError, error.kind());
+    let error = Expr::parse("tan(interval(1, 2))").unwrap().eval::<Interval>().expect_err("Evaluation should fail");
+    assert_eq!("Function tan is not defined on all of [1, 2]", error.message());
+    let error = Expr::parse("sqrt(interval(-1, 1))").unwrap().eval::<Interval>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::MathError, error.kind());
+    let error = Expr::parse("interval(2, 1)").unwrap().eval::<Interval>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::ArgumentMismatch, error.kind());
+    let error = Expr::parse("interval(1, 2) < interval(1.5, 3) ? 1 : 0").unwrap()
+        .eval::<Interval>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::MathError, error.kind());
+}
+
+#[test]
+fn tolerances() {
+    assert_eq!("[99/10, 101/10]", eval("10 ± 0.1").to_string());
+    assert_eq!("[17/2, 23/2]", eval("interval(9, 11) +- 0.5").to_string());
+    let error = Expr::parse("1 ± -1").unwrap().eval::<Interval>().expect_err("Negative tolerance");
+    assert_eq!(EvalErrorKind::MathError, error.kind());
+}
@@ -0,0 +1,59 @@
+
+use std::str::FromStr;
+
+use matheval::{Interval, Number, Value};
+
+fn interval(lo: i64, hi: i64) -> Interval {
+    Interval::new(Number::from_i64(lo), Number::from_i64(hi)).expect("Invalid interval")
+}
+
+#[test]
+fn construction() {
+    assert!(Interval::new(Number::from_i64(2), Number::from_i64(1)).is_err());
+    assert_eq!(interval(3, 3), Interval::from_str("3").expect("Failed parsing number"));
+    assert!(interval(1, 1).is_point());
+    assert_eq!("[1, 2]", interval(1, 2).to_string());
+    assert_eq!("5", interval(5, 5).to_string());
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(interval(4, 6), (interval(1, 2) + interval(3, 4)).unwrap());
+    assert_eq!(interval(-3, -1), (interval(1, 2) - interval(3, 4)).unwrap());
+    assert_eq!(interval(-4, 6), (interval(-2, 3) * interval(-1, 2)).unwrap());
+    assert_eq!(interval(-3, 2), (-interval(-2, 3)).unwrap());
+    assert_eq!(
+        Interval::new(Number::from_i64s(1, 4), Number::from_i64s(3, 2)).unwrap(),
+        (interval(1, 3) / interval(2, 4)).unwrap()
+    );
+    assert!((interval(1, 3) / interval(-1, 4)).is_err());
+}
+
+#[test]
+fn power() {
+    assert_eq!(interval(0, 9), interval(-2, 3).pow(interval(2, 2)).unwrap());
+    assert_eq!(interval(-8, 27), interval(-2, 3).pow(interval(3, 3)).unwrap());
+    assert_eq!(interval(4, 9), interval(-3, -2).pow(interval(2, 2)).unwrap());
+    assert_eq!(interval(1, 27), interval(1, 3).pow(interval(0, 3)).unwrap());
+    assert!(interval(-1, 3).pow(Interval::point(Number::from_i64s(1, 2))).is_err());
+    assert!(interval(-1, 3).pow(interval(-1, -1)).is_err());
+}
+
+#[test]
+fn outward_rounding() {
+    let third = (interval(1, 1) / Interval::point(Number::Float(3.0))).unwrap();
+    assert!(third.lower().to_f64() < 1.0 / 3.0 && third.upper().to_f64() > 1.0 / 3.0);
+    let sum = (third.clone() + third.clone()).unwrap().add(third).unwrap();
+    assert!(sum.lower().to_f64() < 1.0 && sum.upper().to_f64() > 1.0);
+}
+
+#[test]
+fn comparison() {
+    assert_eq!(interval(1, 1), interval(1, 2).less(interval(3, 4)).unwrap());
+    assert_eq!(interval(0, 0), interval(3, 4).less(interval(1, 2)).unwrap());
+    assert_eq!(interval(1, 1), interval(2, 2).equal(interval(2, 2)).unwrap());
+    assert!(interval(1, 3).less(interval(2, 4)).is_err());
+    assert!(interval(1, 2).to_bool().unwrap());
+    assert!(!interval(0, 0).to_bool().unwrap());
+    assert!(interval(-1, 1).to_bool().is_err());
+}
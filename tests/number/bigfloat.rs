@@ -0,0 +1,118 @@
+
+use num::{BigRational, BigInt};
+
+use matheval::{BigFloat, Number, NumberContext, Context, Expr};
+
+const PI: &str = "3.14159265358979323846264338327950288419716939937510582097494459230781640628620899862803482534211706798214808651";
+const E: &str = "2.71828182845904523536028747135266249775724709369995957496696762772407663035354759457138217852516642742746";
+
+fn rational(num: i64, den: i64) -> BigRational {
+    BigRational::new(BigInt::from(num), BigInt::from(den))
+}
+
+fn eval(source: &str, precision: u32) -> Number {
+    Expr::parse(source).expect("Failed parsing expression")
+        .eval_in(&NumberContext::with_precision(precision)).expect("Evaluation failed")
+}
+
+fn assert_correctly_rounded(f: fn(&BigFloat) -> Option<BigFloat>, num: i64, den: i64) {
+    for precision in [24, 53, 113, 200] {
+        let x = BigFloat::from_rational(&rational(num, den), precision);
+        let low = f(&x).expect("Evaluation failed");
+        let high = f(&x.with_precision(precision + 128)).expect("Evaluation failed");
+        assert_eq!(BigFloat::from_rational(&high.to_rational(), precision), low, "at {}/{} with {} bits", num, den, precision);
+    }
+}
+
+#[test]
+fn rounding() {
+    assert_eq!("0.3335", BigFloat::from_rational(&rational(1, 3), 10).to_string());
+    assert_eq!(rational(21, 64), BigFloat::from_rational(&rational(1, 3), 5).to_rational());
+    assert_eq!(rational(2, 1), BigFloat::from_rational(&rational(5, 2), 2).to_rational());
+    assert_eq!(rational(4, 1), BigFloat::from_rational(&rational(7, 2), 2).to_rational());
+    let power = BigRational::from_integer(BigInt::from(2).pow(100));
+    assert_eq!("1.267650600228229401496703205376e30", BigFloat::from_rational(&power, 128).to_string());
+    assert_eq!("0.0009765625", BigFloat::from_rational(&rational(1, 1024), 64).to_string());
+    assert_eq!("-12.5", BigFloat::from_rational(&rational(-25, 2), 64).to_string());
+    assert_eq!(Some(BigFloat::from_rational(&rational(1, 4), 8)), BigFloat::from_f64(0.25, 8));
+}
+
+#[test]
+fn constants() {
+    let pi = BigFloat::pi(332).to_string();
+    assert_eq!(&PI[..95], &pi[..95]);
+    let e = BigFloat::e(332).to_string();
+    assert_eq!(&E[..95], &e[..95]);
+    assert_eq!(std::f64::consts::PI, BigFloat::pi(53).to_f64());
+    assert_eq!(std::f64::consts::E, BigFloat::e(53).to_f64());
+}
+
+#[test]
+fn correctly_rounded() {
+    for (num, den) in [(1, 3), (7, 5), (-22, 7), (1, 1000), (123456, 10)] {
+        assert_correctly_rounded(BigFloat::sin, num, den);
+        assert_correctly_rounded(BigFloat::cos, num, den);
+        assert_correctly_rounded(BigFloat::tan, num, den);
+        assert_correctly_rounded(BigFloat::atan, num, den);
+        assert_correctly_rounded(BigFloat::exp, num, den);
+        assert_correctly_rounded(BigFloat::sinh, num, den);
+        assert_correctly_rounded(BigFloat::cosh, num, den);
+        assert_correctly_rounded(BigFloat::tanh, num, den);
+        assert_correctly_rounded(BigFloat::asinh, num, den);
+        assert_correctly_rounded(BigFloat::cbrt, num, den);
+    }
+    for (num, den) in [(1, 3), (7, 5), (22, 7), (1, 1000), (123456, 10)] {
+        assert_correctly_rounded(BigFloat::sqrt, num, den);
+        assert_correctly_rounded(BigFloat::ln, num, den);
+        assert_correctly_rounded(BigFloat::log, num, den);
+        assert_correctly_rounded(BigFloat::acosh, num + den, den);
+    }
+    for (num, den) in [(1, 3), (-7, 10), (1, 1000), (999, 1000)] {
+        assert_correctly_rounded(BigFloat::asin, num, den);
+        assert_correctly_rounded(BigFloat::acos, num, den);
+        assert_correctly_rounded(BigFloat::atanh, num, den);
+    }
+}
+
+#[test]
+fn context() {
+    assert_eq!(&PI[..60], &eval("pi", 200).to_string()[..60]);
+    assert_eq!(&PI[..60], &eval("4 atan(1)", 200).to_string()[..60]);
+    assert_eq!("1.414213562373095048801688724209698078569", eval("sqrt(2)", 130).to_string());
+    assert_eq!("2", eval("sqrt(4)", 130).to_string());
+    assert_eq!("0", eval("sin(0)", 130).to_string());
+    assert_eq!("1.231144413344916", &eval("2^0.3", 130).to_string()[..17]);
+    assert!(matches!(eval("2^0.3", 130), Number::BigFloat(_)));
+    assert!(matches!(eval("2^(1/3)", 130), Number::BigFloat(_)));
+    assert_eq!("1.25992104989487316476721060727822835057", eval("2^(1/3)", 130).to_string());
+    assert_eq!("8", eval("4^(3/2)", 130).to_string());
+    assert_eq!(&E[..40], &eval("e^1", 130).to_string()[..40]);
+    assert_eq!(Some(130), NumberContext::with_precision(130).precision());
+    assert_eq!(None, NumberContext::new().precision());
+    assert!(Expr::parse("ln(-1)").unwrap().eval_in(&NumberContext::with_precision(64)).is_err());
+    let mut cnxt = NumberContext::with_precision(64);
+    cnxt.set_variable("x", Number::Float(0.5));
+    let value = Expr::parse("asin(x)").unwrap().eval_in(&cnxt).expect("Evaluation failed");
+    assert!(matches!(value, Number::BigFloat(_)));
+}
+
+#[test]
+fn arithmetic() {
+    let third = eval("pi / pi / 3", 64);
+    assert!(matches!(third, Number::BigFloat(_)));
+    assert_eq!(Number::BigFloat(BigFloat::from_rational(&rational(1, 3), 64)), third);
+    assert_eq!("1", eval("e - (e - 1)", 64).to_string());
+    assert!(eval("pi", 64) > Number::from_i64s(314, 100));
+    assert!(eval("pi", 64) < Number::from_i64s(22, 7));
+}
+
+#[test]
+fn domain() {
+    let x = BigFloat::from_rational(&rational(-2, 1), 64);
+    assert!(x.sqrt().is_none());
+    assert!(x.ln().is_none());
+    assert!(x.asin().is_none());
+    assert!(x.atanh().is_none());
+    assert!(x.acosh().is_none());
+    assert_eq!(rational(-2, 1), BigFloat::from_rational(&rational(-8, 1), 64).cbrt().unwrap().to_rational());
+}
@@ -7,14 +7,16 @@ use matheval::{NumberContext, Context, Number};
 fn has_pi() {
     let cnxt = NumberContext::new();
     let val = cnxt.get_variable("pi").expect("Context doesn't contain pi");
-    assert_eq!(Number::Float(3.1415926535897932384626433832795), val);
+    assert_eq!(Number::pi(), val);
+    assert_eq!(std::f64::consts::PI, val.to_f64());
 }
 
 #[test]
 fn has_e() {
     let cnxt = NumberContext::new();
     let val = cnxt.get_variable("e").expect("Context doesn't contain e");
-    assert_eq!(Number::Float(2.7182818284590452353602874713527), val);
+    assert_eq!(Number::e(), val);
+    assert_eq!(std::f64::consts::E, val.to_f64());
 }
 
 #[test]
@@ -85,11 +87,11 @@ fn set_function() {
     let mut cnxt = NumberContext::new();
     let num = Number::from_str("42").expect("Failed parsing number");
     let func_num = num.clone();
-    let func = move |_| Ok(func_num.clone());
+    let func = move |_: &[Number]| Ok(func_num.clone());
     cnxt.set_function("func1", Box::new(func));
     let val = cnxt.get_function("func1")
         .expect("Context doesn't contain func1");
-    assert_eq!(num, val(Vec::new()).expect("Function call failed"));
+    assert_eq!(num, val(&[]).expect("Function call failed"));
 }
 
 #[test]
@@ -97,15 +99,15 @@ fn overwrite_function() {
     let mut cnxt = NumberContext::new();
     let num = Number::from_str("12").expect("Failed parsing number");
     let func_num = num.clone();
-    let func = move |_| Ok(func_num.clone());
+    let func = move |_: &[Number]| Ok(func_num.clone());
     cnxt.set_function("func1", Box::new(func));
     let num = Number::from_str("42").expect("Failed parsing number");
     let func_num = num.clone();
-    let func = move |_| Ok(func_num.clone());
+    let func = move |_: &[Number]| Ok(func_num.clone());
     cnxt.set_function("func1", Box::new(func));
     let val = cnxt.get_function("func1")
         .expect("Context doesn't contain func1");
-    assert_eq!(num, val(Vec::new()).expect("Function call failed"));
+    assert_eq!(num, val(&[]).expect("Function call failed"));
 }
 
 #[test]
@@ -113,11 +115,11 @@ fn overwrite_buildin_function() {
     let mut cnxt = NumberContext::new();
     let num = Number::from_str("42").expect("Failed parsing number");
     let func_num = num.clone();
-    let func = move |_| Ok(func_num.clone());
+    let func = move |_: &[Number]| Ok(func_num.clone());
     cnxt.set_function("sin", Box::new(func));
     let val = cnxt.get_function("sin")
         .expect("Context doesn't contain sin");
-    assert_eq!(num, val(Vec::new()).expect("Function call failed"));
+    assert_eq!(num, val(&[]).expect("Function call failed"));
 }
 
 #[test]
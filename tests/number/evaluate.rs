@@ -3,6 +3,7 @@ use std::ops::Add;
 use std::str::FromStr;
 
 use matheval::Context;
+use matheval::EvalErrorKind;
 use matheval::Expr;
 use matheval::Number;
 use matheval::NumberContext;
@@ -103,6 +104,21 @@ fn mixed_add_sub() {
     );
 }
 
+#[test]
+fn adjacent_signs() {
+    let parsed = Expr::parse("1+-2")
+        .expect("Failed parsing adjacent signs");
+    assert_eq!(Number::from_i64(-1), parsed.eval().expect("Evaluation failed"));
+    let parsed = Expr::parse("2*+-3")
+        .expect("Failed parsing adjacent signs");
+    assert_eq!(Number::from_i64(-6), parsed.eval().expect("Evaluation failed"));
+    let mut context = NumberContext::new();
+    context.set_variable("x", Number::from_i64(2));
+    let parsed = Expr::parse("5+-x")
+        .expect("Failed parsing adjacent signs");
+    assert_eq!(Number::from_i64(3), parsed.eval_in(&context).expect("Evaluation failed"));
+}
+
 #[test]
 fn simple_mul() {
     let parsed = Expr::parse("5 * 6e-5 * 42.5")
@@ -212,7 +228,7 @@ fn functions_general() {
         Expr::parse("sign(20/7)").expect("Failed parsing sign function call")
             .eval::<Number>().expect("Evaluation failed").to_string()
     );
-    assert_eq!("1.6903085094570331",
+    assert_eq!("2 sqrt(35)/7",
         Expr::parse("sqrt(20/7)").expect("Failed parsing sqrt function call")
             .eval::<Number>().expect("Evaluation failed").to_string()
     );
@@ -228,7 +244,7 @@ fn functions_general() {
         Expr::parse("cbrt(-20/7)").expect("Failed parsing cbrt function call")
             .eval::<Number>().expect("Evaluation failed").to_string()
     );
-    assert_eq!("1.0498221244986776",
+    assert_eq!("ln(20/7)",
         Expr::parse("ln(20/7)").expect("Failed parsing ln function call")
             .eval::<Number>().expect("Evaluation failed").to_string()
     );
@@ -324,16 +340,55 @@ fn function_max() {
 fn constants() {
     let parsed = Expr::parse("e")
         .expect("Failed parsing constant 'e'");
-    assert_eq!("2.718281828459045", parsed.eval::<Number>().expect("Evaluation failed").to_string());
+    assert_eq!("e", parsed.eval::<Number>().expect("Evaluation failed").to_string());
     let parsed = Expr::parse("pi")
         .expect("Failed parsing constant 'pi'");
-    assert_eq!("3.141592653589793", parsed.eval::<Number>().expect("Evaluation failed").to_string());
+    assert_eq!("pi", parsed.eval::<Number>().expect("Evaluation failed").to_string());
     let parsed = Expr::parse("e ^ 2")
         .expect("Failed parsing constant 'e^2'");
-    assert_eq!("7.3890560989306495", parsed.eval::<Number>().expect("Evaluation failed").to_string());
+    assert_eq!("e^2", parsed.eval::<Number>().expect("Evaluation failed").to_string());
     let parsed = Expr::parse("pi ^ 2")
         .expect("Failed parsing constant 'pi^2'");
-    assert_eq!("9.869604401089358", parsed.eval::<Number>().expect("Evaluation failed").to_string());
+    assert_eq!("pi^2", parsed.eval::<Number>().expect("Evaluation failed").to_string());
+}
+
+#[test]
+fn comparison() {
+    let parsed = Expr::parse("(1 < 2) + (2 <= 2) + (3 == 3.0) + (1 != 1) + (2 >= 3) + (3 > 2)")
+        .expect("Failed parsing comparisons");
+    assert_eq!("4", parsed.eval::<Number>().expect("Evaluation failed").to_string());
+}
+
+#[test]
+fn boolean() {
+    let parsed = Expr::parse("1 < 2 && !(2 > 3) || 0")
+        .expect("Failed parsing boolean operators");
+    assert_eq!("1", parsed.eval::<Number>().expect("Evaluation failed").to_string());
+    let parsed = Expr::parse("0 && 1 / 0")
+        .expect("Failed parsing boolean operators");
+    assert_eq!("0", parsed.eval::<Number>().expect("Evaluation failed").to_string());
+    let parsed = Expr::parse("2 || 1 / 0")
+        .expect("Failed parsing boolean operators");
+    assert_eq!("1", parsed.eval::<Number>().expect("Evaluation failed").to_string());
+}
+
+#[test]
+fn conditional() {
+    let mut context = NumberContext::new();
+    context.set_variable("x", Number::zero());
+    let parsed = Expr::parse("if(x == 0, 0, 1/x)")
+        .expect("Failed parsing conditional");
+    assert_eq!("0", parsed.eval_in(&context).expect("Evaluation failed").to_string());
+    let parsed = Expr::parse("x != 0 ? 1/x : -1")
+        .expect("Failed parsing conditional");
+    assert_eq!("-1", parsed.eval_in(&context).expect("Evaluation failed").to_string());
+    context.set_variable("x", Number::from_i64(4));
+    let parsed = Expr::parse("if(x == 0, 1/0, 1/x)")
+        .expect("Failed parsing conditional");
+    assert_eq!("1/4", parsed.eval_in(&context).expect("Evaluation failed").to_string());
+    let parsed = Expr::parse("x == 0 ? 0 : 1/(x - 4)")
+        .expect("Failed parsing conditional");
+    assert!(parsed.eval_in(&context).is_err());
 }
 
 #[test]
@@ -356,3 +411,199 @@ fn context_function() {
     assert_eq!("54", parsed.eval_in(&context).expect("Evaluation failed").to_string());
 }
 
+
+#[test]
+fn assignment() {
+    let mut context = NumberContext::new();
+    let parsed = Expr::parse("x = 6 * 7")
+        .expect("Failed parsing assignment");
+    assert_eq!("42", parsed.execute_in(&mut context).expect("Evaluation failed").unwrap().to_string());
+    let parsed = Expr::parse("x / 2")
+        .expect("Failed parsing expression");
+    assert_eq!("21", parsed.eval_in(&context).expect("Evaluation failed").to_string());
+    let parsed = Expr::parse("y = 1")
+        .expect("Failed parsing assignment");
+    assert!(parsed.eval_in(&context).is_err());
+}
+
+#[test]
+fn function_definition() {
+    let mut context = NumberContext::new();
+    let parsed = Expr::parse("f(x, y) = x^2 + y")
+        .expect("Failed parsing function definition");
+    assert!(parsed.execute_in(&mut context).expect("Evaluation failed").is_none());
+    let parsed = Expr::parse("f(3, 1) + f(1, 0)")
+        .expect("Failed parsing function call");
+    assert_eq!("11", parsed.eval_in(&context).expect("Evaluation failed").to_string());
+    let parsed = Expr::parse("f(3)")
+        .expect("Failed parsing function call");
+    let error = parsed.eval_in(&context).expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::ArgumentMismatch, error.kind());
+}
+
+#[test]
+fn function_resolves_names_when_called() {
+    let mut context = NumberContext::new();
+    for source in ["f(x) = g(x) + a", "g(x) = a * x", "a = 2"] {
+        Expr::parse(source).unwrap().execute_in(&mut context).expect("Evaluation failed");
+    }
+    let parsed = Expr::parse("f(5) + a")
+        .expect("Failed parsing function call");
+    assert_eq!("14", parsed.eval_in(&context).expect("Evaluation failed").to_string());
+    Expr::parse("a = 100").unwrap().execute_in(&mut context).expect("Evaluation failed");
+    assert_eq!("700", parsed.eval_in(&context).expect("Evaluation failed").to_string());
+    Expr::parse("g(x) = -x").unwrap().execute_in(&mut context).expect("Evaluation failed");
+    assert_eq!("195", parsed.eval_in(&context).expect("Evaluation failed").to_string());
+    for source in ["h(x) = k(2)", "k(y) = x * y"] {
+        Expr::parse(source).unwrap().execute_in(&mut context).expect("Evaluation failed");
+    }
+    let error = Expr::parse("h(3)").unwrap().eval_in(&context).expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::UnknownVariable, error.kind());
+}
+
+#[test]
+fn mutual_recursion() {
+    let mut context = NumberContext::new();
+    context.set_recursion_limit(10);
+    for source in ["even(n) = n == 0 ? 1 : odd(n - 1)", "odd(n) = n == 0 ? 0 : even(n - 1)"] {
+        Expr::parse(source).unwrap().execute_in(&mut context).expect("Evaluation failed");
+    }
+    let parsed = Expr::parse("even(9) + odd(9)")
+        .expect("Failed parsing function call");
+    assert_eq!("1", parsed.eval_in(&context).expect("Evaluation failed").to_string());
+    let parsed = Expr::parse("even(10)")
+        .expect("Failed parsing function call");
+    let error = parsed.eval_in(&context).expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::RecursionLimit, error.kind());
+}
+
+#[test]
+fn recursive_function() {
+    let mut context = NumberContext::new();
+    Expr::parse("fact(n) = n <= 1 ? 1 : n * fact(n - 1)").unwrap()
+        .execute_in(&mut context).expect("Evaluation failed");
+    let parsed = Expr::parse("fact(20)")
+        .expect("Failed parsing function call");
+    assert_eq!("2432902008176640000", parsed.eval_in(&context).expect("Evaluation failed").to_string());
+    Expr::parse("loop(n) = loop(n + 1)").unwrap()
+        .execute_in(&mut context).expect("Evaluation failed");
+    let error = Expr::parse("loop(0)").unwrap().eval_in(&context).expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::RecursionLimit, error.kind());
+    assert_eq!(65, error.calls().len());
+}
+
+#[test]
+fn recursion_limit() {
+    let mut context = NumberContext::new();
+    context.set_recursion_limit(5);
+    Expr::parse("fact(n) = n <= 1 ? 1 : n * fact(n - 1)").unwrap()
+        .execute_in(&mut context).expect("Evaluation failed");
+    let parsed = Expr::parse("fact(5)")
+        .expect("Failed parsing function call");
+    assert_eq!("120", parsed.eval_in(&context).expect("Evaluation failed").to_string());
+    let parsed = Expr::parse("fact(6)")
+        .expect("Failed parsing function call");
+    let error = parsed.eval_in(&context).expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::RecursionLimit, error.kind());
+    assert_eq!("Maximum call depth of 5 exceeded", error.message());
+}
+
+#[test]
+fn program() {
+    let mut context = NumberContext::new();
+    let program = Expr::parse_program("f(x) = 2x\ny = f(3); y + 1")
+        .expect("Failed parsing program");
+    let result = Expr::execute_program_in(&program, &mut context).expect("Evaluation failed");
+    assert_eq!("7", result.unwrap().to_string());
+    assert_eq!("6", context.get_variable("y").unwrap().to_string());
+    let program = Expr::parse_program("z = 1; z / 0; w = 2")
+        .expect("Failed parsing program");
+    assert!(Expr::execute_program_in(&program, &mut context).is_err());
+    assert_eq!(Some(Number::one()), context.get_variable("z"));
+    assert_eq!(None, context.get_variable("w"));
+}
+
+#[test]
+fn sum_and_prod() {
+    let parsed = Expr::parse("sum(i, 1, 10, i^2)")
+        .expect("Failed parsing sum");
+    assert_eq!("385", parsed.eval::<Number>().expect("Evaluation failed").to_string());
+    let parsed = Expr::parse("prod(k, 1, 5, k) + sum(i, 1, 0, i)")
+        .expect("Failed parsing prod");
+    assert_eq!("120", parsed.eval::<Number>().expect("Evaluation failed").to_string());
+    let parsed = Expr::parse("sum(i, 1, 3, prod(j, 1, i, j))")
+        .expect("Failed parsing nested sum");
+    assert_eq!("9", parsed.eval::<Number>().expect("Evaluation failed").to_string());
+    let parsed = Expr::parse("sum(i, 1, 1e9, i)")
+        .expect("Failed parsing sum");
+    let error = parsed.eval::<Number>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::MathError, error.kind());
+    let parsed = Expr::parse("sum(i, 0, 1e17, 1)")
+        .expect("Failed parsing sum");
+    let error = parsed.eval::<f64>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::MathError, error.kind());
+    let parsed = Expr::parse("prod(i, 1e17, 1e17 + 100, 1)")
+        .expect("Failed parsing prod");
+    let error = parsed.eval::<f64>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::MathError, error.kind());
+}
+
+#[test]
+fn integrate() {
+    let parsed = Expr::parse("integrate(x, 0, 3, x^2)")
+        .expect("Failed parsing integrate");
+    assert_eq!("9", parsed.eval::<Number>().expect("Evaluation failed").to_string());
+    let parsed = Expr::parse("integrate(x, 0, pi, sin(x))")
+        .expect("Failed parsing integrate");
+    let result = parsed.eval::<Number>().expect("Evaluation failed").to_f64();
+    assert!((result - 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn solve() {
+    let parsed = Expr::parse("solve(x, 0, 2, x^2 - 2)")
+        .expect("Failed parsing solve");
+    let result = parsed.eval::<Number>().expect("Evaluation failed").to_f64();
+    assert!((result - 2f64.sqrt()).abs() < 1e-15);
+    let parsed = Expr::parse("solve(x, 0, 4, x - 1)")
+        .expect("Failed parsing solve");
+    assert_eq!("1", parsed.eval::<Number>().expect("Evaluation failed").to_string());
+    let parsed = Expr::parse("solve(x, 2, 4, x - 1)")
+        .expect("Failed parsing solve");
+    assert!(parsed.eval::<Number>().is_err());
+}
+
+#[test]
+fn forms_in_functions() {
+    let mut context = NumberContext::new();
+    Expr::parse("tri(n) = sum(i, 1, n, i)").unwrap()
+        .execute_in(&mut context).expect("Evaluation failed");
+    let parsed = Expr::parse("tri(100)")
+        .expect("Failed parsing function call");
+    assert_eq!("5050", parsed.eval_in(&context).expect("Evaluation failed").to_string());
+    let parsed = Expr::parse("sum(2, 1, 3, 1)")
+        .expect("Failed parsing sum");
+    let error = parsed.eval_in(&context).expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::ArgumentMismatch, error.kind());
+    assert_eq!(["sum"], error.calls());
+}
+
+#[test]
+fn integer_operators() {
+    for (source, expected) in [
+        ("7 % 3", "1"), ("-7 % 3", "2"), ("7 % -3", "-2"), ("7/2 % 1", "1/2"),
+        ("-7 // 2", "-4"), ("7 // 2 * 2 + 7 % 2", "7"),
+        ("12 & 10", "8"), ("12 | 3", "15"), ("6 xor 3", "5"), ("-6 & 7", "2"),
+        ("1 << 70", "1180591620717411303424"), ("-5 >> 1", "-3"), ("1 << -1", "0"),
+        ("~5", "-6"), ("~-1", "0"), ("1 + 2 << 3", "24"),
+    ] {
+        let parsed = Expr::parse(source).expect("Failed to parse integer operator");
+        assert_eq!(expected, parsed.eval::<Number>().expect("Evaluation failed").to_string(), "{}", source);
+    }
+    let parsed = Expr::parse("1.5 & 1").expect("Failed to parse bitwise and");
+    let error = parsed.eval::<Number>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::ArgumentMismatch, error.kind());
+    let parsed = Expr::parse("5 % 0").expect("Failed to parse modulo");
+    let error = parsed.eval::<Number>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::MathError, error.kind());
+}
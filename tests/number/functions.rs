@@ -117,7 +117,7 @@ fn sqrt_rational() {
 
 #[test]
 fn sqrt_float() {
-    assert_eq!("3.529872518944558", Number::from_str("12.46").unwrap().sqrt().to_string());
+    assert_eq!("sqrt(1246)/10", Number::from_str("12.46").unwrap().sqrt().to_string());
     assert_eq!("0", Number::from_str("0.0").unwrap().sqrt().to_string());
     assert_eq!("3.529872518944558", Number::Float(12.46).sqrt().to_string());
     assert_eq!("0", Number::Float(0.0).sqrt().to_string());
@@ -125,7 +125,7 @@ fn sqrt_float() {
 
 #[test]
 fn ln_float() {
-    assert_eq!("2.522523513359307", Number::from_str("12.46").unwrap().ln().to_string());
+    assert_eq!("ln(623/50)", Number::from_str("12.46").unwrap().ln().to_string());
     assert_eq!("0", Number::from_str("1.0").unwrap().ln().to_string());
     assert_eq!("2.522523513359307", Number::Float(12.46).ln().to_string());
     assert_eq!("0", Number::Float(1.0).ln().to_string());
@@ -190,7 +190,7 @@ fn asin_float() {
 #[test]
 fn acos_float() {
     assert_eq!("0.9967907614669048", Number::from_str("0.543").unwrap().acos().to_string());
-    assert_eq!("1.5707963267948966", Number::from_str("0.0").unwrap().acos().to_string());
+    assert_eq!("pi/2", Number::from_str("0.0").unwrap().acos().to_string());
     assert_eq!("0.9967907614669048", Number::Float(0.543).acos().to_string());
     assert_eq!("1.5707963267948966", Number::Float(0.0).acos().to_string());
 }
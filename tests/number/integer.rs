@@ -0,0 +1,97 @@
+use std::str::FromStr;
+
+use matheval::{Number, EvalErrorKind, Expr};
+
+fn eval(source: &str) -> Result<Number, EvalErrorKind> {
+    Expr::parse(source).expect("Failed parsing expression").eval().map_err(|e| e.kind())
+}
+
+fn assert_eval(expected: &str, source: &str) {
+    assert_eq!(expected, eval(source).expect("Evaluation failed").to_string(), "evaluating {}", source);
+}
+
+#[test]
+fn divisibility() {
+    assert_eval("6", "gcd(12, 18)");
+    assert_eval("4", "gcd(-12, 8, 20)");
+    assert_eval("7", "gcd(0, -7)");
+    assert_eval("36", "lcm(12, 18)");
+    assert_eval("60", "lcm(3, 4, 5, 6)");
+    assert_eval("0", "lcm(0, 5)");
+    assert_eval("2", "mod(17, 5)");
+    assert_eval("3", "mod(-17, 5)");
+    assert_eval("-3", "mod(17, -5)");
+    assert_eval("1/2", "mod(7/2, 3/2)");
+    assert_eval("123456789012345678901234567890", "mod(123456789012345678901234567890, 10^40)");
+    assert_eq!(Err(EvalErrorKind::MathError), eval("mod(3, 0)"));
+    assert_eq!(Err(EvalErrorKind::ArgumentMismatch), eval("gcd(1.5, 3)"));
+    assert_eq!(Err(EvalErrorKind::ArgumentMismatch), eval("lcm(pi, 3)"));
+}
+
+#[test]
+fn combinatorics() {
+    assert_eval("120", "factorial(5)");
+    assert_eval("1", "0!");
+    assert_eval("720", "3!!");
+    assert_eval("30414093201713378043612608166064768844377641568960512000000000000", "50!");
+    assert_eval("10", "binomial(5, 2)");
+    assert_eval("10", "nCr(5, 3)");
+    assert_eval("0", "nCr(5, 6)");
+    assert_eval("-4", "binomial(-2, 3)");
+    assert_eval("100891344545564193334812497256", "nCr(100, 50)");
+    assert_eval("60", "nPr(5, 3)");
+    assert_eval("1", "nPr(5, 0)");
+    assert_eval("0", "nPr(3, 4)");
+    assert_eq!(Err(EvalErrorKind::ArgumentMismatch), eval("(-1)!"));
+    assert_eq!(Err(EvalErrorKind::ArgumentMismatch), eval("2.5!"));
+    assert_eq!(Err(EvalErrorKind::ArgumentMismatch), eval("nCr(5, 1/2)"));
+    assert_eq!(Err(EvalErrorKind::MathError), eval("(10^9)!"));
+}
+
+#[test]
+fn primes() {
+    assert_eval("0", "isprime(1)");
+    assert_eval("1", "isprime(2)");
+    assert_eval("0", "isprime(561)");
+    assert_eval("1", "isprime(2^61 - 1)");
+    assert_eval("0", "isprime(2^64 + 1)");
+    assert_eval("2", "nextprime(-5)");
+    assert_eval("101", "nextprime(97)");
+    assert_eval("18446744073709551629", "nextprime(2^64)");
+    assert_eval("2^3 * 3^2 * 5", "factor(360)");
+    assert_eval("-7 * 11 * 13", "factor(-1001)");
+    assert_eval("1", "factor(1)");
+    assert_eval("1", "factor(360) == 360");
+    assert_eval("361", "factor(360) + 1");
+    assert_eval("129600", "factor(360)^2");
+    assert_eval("12", "gcd(factor(360), 84)");
+    assert_eq!(Err(EvalErrorKind::ArgumentMismatch), eval("factor(0)"));
+    assert_eval("2", "factor(360, 1)");
+    assert_eval("5", "factor(360, 6)");
+    assert_eq!(Err(EvalErrorKind::ArgumentMismatch), eval("factor(360, 7)"));
+    assert_eq!(Err(EvalErrorKind::ArgumentMismatch), eval("isprime(7.5)"));
+    let factors = Number::from_str("18446744073709551617").unwrap().factor().expect("Factorization failed");
+    assert_eq!(vec![Number::from_i64(274177), Number::from_i64(67280421310721)], factors);
+    let factors = Number::from_i64(-1001).factor().expect("Factorization failed");
+    assert_eq!(vec![Number::from_i64(7), Number::from_i64(11), Number::from_i64(13)], factors);
+    assert!(Number::from_i64(0).factor().is_err());
+    assert_eval("998244353", "factor(100000007 * 998244353 * 1000000007, 2)");
+    assert_eq!(Err(EvalErrorKind::MathError), eval("factor(2^128 + 1, 1)"));
+    assert_eq!(Err(EvalErrorKind::MathError), eval("totient(2^128 + 1)"));
+}
+
+#[test]
+fn modular() {
+    assert_eval("445", "modpow(4, 13, 497)");
+    assert_eval("1", "modpow(2, 10^20, 2^61 - 1) == modpow(2, mod(10^20, 61), 2^61 - 1)");
+    assert_eval("4", "modpow(3, -1, 11)");
+    assert_eval("0", "modpow(5, 3, 1)");
+    assert_eval("4", "modinv(3, 11)");
+    assert_eval("7", "modinv(-3, 11)");
+    assert_eq!(Err(EvalErrorKind::MathError), eval("modinv(6, 9)"));
+    assert_eq!(Err(EvalErrorKind::MathError), eval("modpow(2, 3, 0)"));
+    assert_eval("4", "totient(12)");
+    assert_eval("1", "totient(1)");
+    assert_eval("2305843009213693950", "totient(2^61 - 1)");
+    assert_eq!(Err(EvalErrorKind::ArgumentMismatch), eval("totient(0)"));
+}
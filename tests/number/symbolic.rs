@@ -0,0 +1,93 @@
+use matheval::{Number, NumberContext, Expr};
+
+fn eval(source: &str) -> Number {
+    Expr::parse(source).expect("Failed parsing expression").eval().expect("Evaluation failed")
+}
+
+fn assert_eval(expected: &str, source: &str) {
+    assert_eq!(expected, eval(source).to_string(), "evaluating {}", source);
+}
+
+#[test]
+fn trigonometry() {
+    assert_eval("1/2", "sin(pi/6)");
+    assert_eval("1/2", "cos(pi/3)");
+    assert_eval("sqrt(2)/2", "sin(pi/4)");
+    assert_eval("sqrt(3)/2", "cos(-pi/6)");
+    assert_eval("-1", "cos(pi)");
+    assert_eval("0", "sin(2 pi)");
+    assert_eval("sqrt(6)/4 - sqrt(2)/4", "sin(pi/12)");
+    assert_eval("-sqrt(6)/4 + sqrt(2)/4", "sin(13 pi/12)");
+    assert_eval("2 - sqrt(3)", "tan(pi/12)");
+    assert_eval("-sqrt(3)", "tan(2 pi/3)");
+    assert!(Expr::parse("tan(pi/2)").unwrap().eval::<Number>().is_err());
+    assert_eval("pi/6", "asin(1/2)");
+    assert_eval("3 pi/4", "acos(-sqrt(2)/2)");
+    assert_eval("-pi/3", "atan(-sqrt(3))");
+    assert_eval("1", "sin(pi/5)^2 + cos(pi/5)^2");
+}
+
+#[test]
+fn roots() {
+    assert_eval("2 sqrt(2)", "sqrt(8)");
+    assert_eval("2", "sqrt(2)^2");
+    assert_eval("sqrt(6)", "sqrt(2) sqrt(3)");
+    assert_eval("sqrt(2)/2", "1/sqrt(2)");
+    assert_eval("sqrt(6)/3", "sqrt(2/3)");
+    assert_eval("3/2", "sqrt(9/4)");
+    assert_eval("sqrt(2)", "2^0.5");
+    assert_eval("2 sqrt(2)", "2^(3/2)");
+    assert_eval("1 + sqrt(2)", "1/(sqrt(2) - 1)");
+    assert_eval("3 + 2 sqrt(2)", "(1 + sqrt(2))^2");
+    assert_eval("0", "sqrt(3) - sqrt(3)");
+    assert_eval("1/2 + sqrt(2)", "sqrt(2) + 0.5");
+}
+
+#[test]
+fn logarithms() {
+    assert_eval("5", "e^ln(5)");
+    assert_eval("2", "ln(e^2)");
+    assert_eval("ln(2)", "ln(2)");
+    assert_eval("3 ln(2)", "ln(8)");
+    assert_eval("-ln(3)", "ln(1/3)");
+    assert_eval("ln(6)", "ln(2) + ln(3)");
+    assert_eval("ln(3/2)", "ln(3) - ln(2)");
+    assert_eval("3", "log(1000)");
+    assert_eval("3", "ln(8) / ln(2)");
+    assert_eval("e^(1/2)", "sqrt(e)");
+    assert_eval("e^(-1)", "1/e");
+    assert_eval("2 pi", "pi + pi");
+}
+
+#[test]
+fn constant_powers() {
+    assert_eval("e^2", "e^2");
+    assert_eval("pi^2", "pi^2");
+    assert_eval("e^3", "e * e^2");
+    assert_eval("pi^3", "pi * pi^2");
+    assert_eval("e^(-2)", "1/e^2");
+    assert_eval("pi^(-2)", "1/pi^2");
+    assert_eval("3 e^2/4", "3/4 e^2");
+    assert_eval("pi^2/4", "(pi/2)^2");
+    assert_eval("2 pi^2", "pi^2 + pi^2");
+    assert_eval("e", "sqrt(e^2)");
+    assert_eval("pi", "sqrt(pi^2)");
+    assert_eval("pi/2", "pi^3 / (2 pi^2)");
+    assert_eq!(std::f64::consts::PI.powi(2), eval("pi^2").to_f64());
+    assert!(matches!(eval("e^2 + pi^2"), Number::Float(_)));
+}
+
+#[test]
+fn approximation() {
+    assert_eq!(0.5f64.sqrt(), eval("sqrt(2)/2").to_f64());
+    assert_eq!(std::f64::consts::PI / 4.0, eval("pi/4").to_f64());
+    assert!(matches!(eval("pi + 1"), Number::Float(_)));
+    assert_eq!(Number::from_i64(3), eval("floor(pi)"));
+    assert_eq!(Number::from_i64(-2), eval("round(-sqrt(2)^3/2 - 0.5)"));
+    assert!(eval("sqrt(2)") < eval("3/2"));
+    assert!(eval("pi/4") > eval("sqrt(2)/2"));
+    assert!(eval("-pi").is_negative());
+    let value = Expr::parse("sin(pi/4)").unwrap().eval_in(&NumberContext::with_precision(100)).unwrap();
+    assert!(matches!(value, Number::BigFloat(_)));
+    assert!(value.to_string().starts_with("0.707106781186547524400844362104"));
+}
@@ -1,19 +1,47 @@
 
+mod complex {
+    mod complex;
+    mod evaluate;
+}
+
 mod expr {
+    mod compile;
+    mod derivative;
     mod format;
     mod parser;
+    mod simplify;
+}
+
+mod float {
+    mod context;
+    mod evaluate;
+}
+
+mod interval {
+    mod evaluate;
+    mod interval;
 }
 
 mod number {
+    mod bigfloat;
     mod context;
     mod evaluate;
     mod functions;
+    mod integer;
     mod number;
+    mod symbolic;
+}
+
+mod uncertain {
+    mod evaluate;
+    mod uncertain;
 }
 
 mod units {
     mod context;
     mod evaluate;
+    mod format;
+    mod loader;
     mod quantity;
     mod unit;
 }
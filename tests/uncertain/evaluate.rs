@@ -0,0 +1,49 @@
+
+use matheval::{Expr, Uncertain, UncertainContext, EvalErrorKind};
+
+fn eval(source: &str) -> Uncertain {
+    Expr::parse(source).expect("Failed parsing expression")
+        .eval::<Uncertain>().expect("Evaluation failed")
+}
+
+fn assert_close(expected: f64, actual: f64) {
+    assert!((expected - actual).abs() < 1e-12, "expected {} but got {}", expected, actual);
+}
+
+#[test]
+fn literals() {
+    assert_eq!("9.81 ± 0.02", eval("9.81 ± 0.02").to_string());
+    assert_eq!("9.81 ± 0.02", eval("9.81 +- 0.02").to_string());
+    assert_eq!("1.5", eval("3 / 2").to_string());
+    let error = Expr::parse("1 ± -1").unwrap().eval::<Uncertain>().expect_err("Negative uncertainty");
+    assert_eq!(EvalErrorKind::MathError, error.kind());
+}
+
+#[test]
+fn functions() {
+    let value = eval("sin(0 ± 0.1)");
+    assert_close(0.0, value.mean());
+    assert_close(0.1, value.std_dev());
+    assert_close(0.05, eval("sqrt(4 ± 0.2)").std_dev());
+    assert_close(0.1, eval("ln(2 ± 0.2)").std_dev());
+    assert_close(0.0, eval("floor(2.5 ± 0.1)").std_dev());
+    assert_close(0.2, eval("max(1 ± 0.1, 3 ± 0.2)").std_dev());
+    assert_close(0.5, eval("mean(0.5 ± 1)").mean());
+    assert_close(1.0, eval("sd(0.5 ± 1)").mean());
+    assert!(Expr::parse("sqrt(-1 ± 0.1)").unwrap().eval::<Uncertain>().is_err());
+}
+
+#[test]
+fn correlated_inputs() {
+    let mut context = UncertainContext::new();
+    context.set_input("x", 5.0, 0.1);
+    context.set_input("y", 2.0, 0.2);
+    let eval = |source: &str| Expr::parse(source).unwrap()
+        .eval_in::<Uncertain, _>(&context).expect("Evaluation failed");
+    assert!(eval("x - x").is_exact());
+    assert_close(0.0, eval("x / x - 1").std_dev());
+    assert_close(0.2, eval("2x").std_dev());
+    assert_close(0.05f64.sqrt(), eval("x + y").std_dev());
+    assert_close(1.0, eval("corr(x, 3x + 1)").mean());
+    assert_close(0.0, eval("corr(x, y)").mean());
+}
@@ -0,0 +1,57 @@
+
+use std::str::FromStr;
+
+use matheval::{Uncertain, Value};
+
+fn assert_close(expected: f64, actual: f64) {
+    assert!((expected - actual).abs() < 1e-12, "expected {} but got {}", expected, actual);
+}
+
+#[test]
+fn construction() {
+    let value = Uncertain::independent(9.81, 0.02);
+    assert_eq!(9.81, value.mean());
+    assert_eq!(0.02, value.std_dev());
+    assert_eq!("9.81 ± 0.02", value.to_string());
+    assert!(Uncertain::exact(2.0).is_exact());
+    assert_eq!("2", Uncertain::exact(2.0).to_string());
+    assert_eq!(Uncertain::exact(0.5), Uncertain::from_str("0.5").expect("Failed parsing number"));
+}
+
+#[test]
+fn propagation() {
+    let a = Uncertain::independent(3.0, 0.3);
+    let b = Uncertain::independent(4.0, 0.4);
+    let sum = (a.clone() + b.clone()).unwrap();
+    assert_close(7.0, sum.mean());
+    assert_close(0.5, sum.std_dev());
+    let product = (a.clone() * b.clone()).unwrap();
+    assert_close(12.0, product.mean());
+    assert_close(2.0f64.sqrt() * 1.2, product.std_dev());
+    let quotient = (a.clone() / b).unwrap();
+    assert_close(0.75, quotient.mean());
+    assert_close(2.0f64.sqrt() * 0.075, quotient.std_dev());
+    let square = Value::pow(a.clone(), Uncertain::exact(2.0)).unwrap();
+    assert_close(9.0, square.mean());
+    assert_close(1.8, square.std_dev());
+    assert!((a / Uncertain::independent(0.0, 1.0)).is_err());
+}
+
+#[test]
+fn correlation() {
+    let x = Uncertain::named("x", 2.0, 0.1);
+    let y = Uncertain::named("x", 2.0, 0.1);
+    assert!((x.clone() - y.clone()).unwrap().is_exact());
+    assert_close(0.2, (x.clone() + y).unwrap().std_dev());
+    let z = Uncertain::independent(2.0, 0.1);
+    assert_close(0.0, x.covariance(&z));
+    assert_close(1.0, x.correlation(&(x.clone() * Uncertain::exact(3.0)).unwrap()));
+}
+
+#[test]
+fn plus_minus() {
+    let value = Uncertain::exact(10.0).plus_minus(Uncertain::exact(0.5)).unwrap();
+    assert_eq!("10 ± 0.5", value.to_string());
+    assert!(Uncertain::exact(10.0).plus_minus(Uncertain::exact(-0.5)).is_err());
+    assert!(Uncertain::exact(10.0).plus_minus(value).is_err());
+}
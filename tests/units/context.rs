@@ -1,8 +1,9 @@
 
 use std::ops::*;
+use std::str::FromStr;
 use num::traits::Pow;
 
-use matheval::{Quantity, Number, QuantityContext, Context, Unit, BaseUnit};
+use matheval::{Quantity, Number, QuantityContext, Context, Unit, BaseUnit, UnitSystem, Expr, EvalErrorKind};
 
 #[test]
 fn has_base_si_units() {
@@ -16,7 +17,7 @@ fn has_base_si_units() {
         cnxt.get_variable("m").expect("'m' is not in the context")
     );
     assert_eq!(
-        Quantity::new(Number::from_i64(1000), Unit::base(BaseUnit::Gram)),
+        Quantity::new(Number::one(), Unit::base(BaseUnit::Kilogram)),
         cnxt.get_variable("kg").expect("'kg' is not in the context")
     );
     assert_eq!(
@@ -41,7 +42,7 @@ fn has_base_si_units() {
 fn has_derived_si_units() {
     let cnxt = QuantityContext::new();
     assert_eq!(
-        Quantity::new(Number::from_i64(1_000_000), Unit::base(BaseUnit::Gram)),
+        Quantity::new(Number::from_i64(1_000), Unit::base(BaseUnit::Kilogram)),
         cnxt.get_variable("t").expect("'t' is not in the context")
     );
     assert_eq!(
@@ -54,15 +55,36 @@ fn has_derived_si_units() {
     );
     assert_eq!(
         Quantity::new(
-            Number::from_i64(1000),
+            Number::one(),
             Unit::base(BaseUnit::Meter).pow(Number::from_i64(2))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-3)))
-                .mul(Unit::base(BaseUnit::Gram))
+                .mul(Unit::base(BaseUnit::Kilogram))
         ),
         cnxt.get_variable("W").expect("'W' is not in the context")
     );
 }
 
+#[test]
+fn derived_si_units_are_coherent() {
+    let cnxt = QuantityContext::new();
+    for name in ["N", "Pa", "J", "W", "C", "V", "F", "ohm", "S", "Wb", "T", "H", "Gy", "Sv"] {
+        assert_eq!(
+            &Number::one(),
+            cnxt.get_variable(name).expect("Unit is not in the context").coefficient(),
+        );
+    }
+    assert_eq!("1 kg m s^-2", cnxt.get_variable("N").unwrap().to_string());
+    assert_eq!("1 m^2 s^-2", cnxt.get_variable("Gy").unwrap().to_string());
+    assert_eq!(
+        Quantity::new(Number::from_i64s(1, 1_000_000), Unit::base(BaseUnit::Kilogram)),
+        cnxt.get_variable("mg").expect("'mg' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i64(1000), Unit::base(BaseUnit::Kilogram)),
+        cnxt.get_variable("Mg").expect("'Mg' is not in the context")
+    );
+}
+
 #[test]
 fn has_prefixed_units() {
     let cnxt = QuantityContext::new();
@@ -71,7 +93,7 @@ fn has_prefixed_units() {
         cnxt.get_variable("ms").expect("'ms' is not in the context")
     );
     assert_eq!(
-        Quantity::new(Number::from_i64(1_000_000_000_000), Unit::base(BaseUnit::Gram)),
+        Quantity::new(Number::from_i64(1_000_000_000), Unit::base(BaseUnit::Kilogram)),
         cnxt.get_variable("Mt").expect("'Mt' is not in the context")
     );
     assert_eq!(
@@ -80,16 +102,66 @@ fn has_prefixed_units() {
     );
     assert_eq!(
         Quantity::new(
-            Number::from_i64s(1, 1_000_000_000),
+            Number::from_i64s(1, 1_000_000),
             Unit::base(BaseUnit::Ampere).pow(Number::from_i64(2))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(4)))
                 .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(-2)))
-                .mul(Unit::base(BaseUnit::Gram).pow(Number::from_i64(-1)))
+                .mul(Unit::base(BaseUnit::Kilogram).pow(Number::from_i64(-1)))
         ),
         cnxt.get_variable("uF").expect("'uF' is not in the context")
     );
 }
 
+#[test]
+fn has_extreme_prefixes() {
+    let cnxt = QuantityContext::new();
+    assert_eq!(
+        Quantity::new(Number::from_str("1e30").unwrap(), Unit::base(BaseUnit::Meter)),
+        cnxt.get_variable("Qm").expect("'Qm' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_str("1e27").unwrap(), Unit::base(BaseUnit::Second)),
+        cnxt.get_variable("ronnasecond").expect("'ronnasecond' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_str("1e-27").unwrap(), Unit::base(BaseUnit::Meter)),
+        cnxt.get_variable("rm").expect("'rm' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_str("1e-33").unwrap(), Unit::base(BaseUnit::Kilogram)),
+        cnxt.get_variable("quectogram").expect("'quectogram' is not in the context")
+    );
+}
+
+#[test]
+fn has_information_units() {
+    let cnxt = QuantityContext::new();
+    assert_eq!(
+        Quantity::new(Number::one(), Unit::base(BaseUnit::Bit)),
+        cnxt.get_variable("bit").expect("'bit' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i64(8), Unit::base(BaseUnit::Bit)),
+        cnxt.get_variable("B").expect("'B' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i64(8_000), Unit::base(BaseUnit::Bit)),
+        cnxt.get_variable("kB").expect("'kB' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i64(8 * 1024), Unit::base(BaseUnit::Bit)),
+        cnxt.get_variable("KiB").expect("'KiB' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i64(1 << 20), Unit::base(BaseUnit::Bit)),
+        cnxt.get_variable("mebibit").expect("'mebibit' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i128(8 << 80), Unit::base(BaseUnit::Bit)),
+        cnxt.get_variable("YiB").expect("'YiB' is not in the context")
+    );
+}
+
 #[test]
 fn has_non_si_units() {
     let cnxt = QuantityContext::new();
@@ -98,7 +170,7 @@ fn has_non_si_units() {
         cnxt.get_variable("h").expect("'h' is not in the context")
     );
     assert_eq!(
-        Quantity::new(Number::from_i64s(453_59237, 100000), Unit::base(BaseUnit::Gram)),
+        Quantity::new(Number::from_i64s(453_59237, 100000000), Unit::base(BaseUnit::Kilogram)),
         cnxt.get_variable("lb").expect("'lb' is not in the context")
     );
     assert_eq!(
@@ -123,7 +195,7 @@ fn has_named_units() {
         cnxt.get_variable("milliseconds").expect("'milliseconds' is not in the context")
     );
     assert_eq!(
-        Quantity::new(Number::from_i64(1_000_000_000_000), Unit::base(BaseUnit::Gram)),
+        Quantity::new(Number::from_i64(1_000_000_000), Unit::base(BaseUnit::Kilogram)),
         cnxt.get_variable("megatonne").expect("'megatonne' is not in the context")
     );
     assert_eq!(
@@ -132,16 +204,16 @@ fn has_named_units() {
     );
     assert_eq!(
         Quantity::new(
-            Number::from_i64s(1, 1_000_000_000),
+            Number::from_i64s(1, 1_000_000),
             Unit::base(BaseUnit::Ampere).pow(Number::from_i64(2))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(4)))
                 .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(-2)))
-                .mul(Unit::base(BaseUnit::Gram).pow(Number::from_i64(-1)))
+                .mul(Unit::base(BaseUnit::Kilogram).pow(Number::from_i64(-1)))
         ),
         cnxt.get_variable("microfarad").expect("'microfarad' is not in the context")
     );
     assert_eq!(
-        Quantity::new(Number::from_i64(1_000_000), Unit::base(BaseUnit::Gram)),
+        Quantity::new(Number::from_i64(1_000), Unit::base(BaseUnit::Kilogram)),
         cnxt.get_variable("tonnes").expect("'tonnes' is not in the context")
     );
     assert_eq!(
@@ -154,10 +226,10 @@ fn has_named_units() {
     );
     assert_eq!(
         Quantity::new(
-            Number::from_i64(1000),
+            Number::one(),
             Unit::base(BaseUnit::Meter).pow(Number::from_i64(2))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-3)))
-                .mul(Unit::base(BaseUnit::Gram))
+                .mul(Unit::base(BaseUnit::Kilogram))
         ),
         cnxt.get_variable("watt").expect("'watt' is not in the context")
     );
@@ -170,7 +242,7 @@ fn has_named_units() {
         cnxt.get_variable("metre").expect("'metre' is not in the context")
     );
     assert_eq!(
-        Quantity::new(Number::from_i64(1000), Unit::base(BaseUnit::Gram)),
+        Quantity::new(Number::one(), Unit::base(BaseUnit::Kilogram)),
         cnxt.get_variable("kilogram").expect("'kilogram' is not in the context")
     );
     assert_eq!(
@@ -194,7 +266,7 @@ fn has_named_units() {
         cnxt.get_variable("hours").expect("'hours' is not in the context")
     );
     assert_eq!(
-        Quantity::new(Number::from_i64s(453_59237, 100000), Unit::base(BaseUnit::Gram)),
+        Quantity::new(Number::from_i64s(453_59237, 100000000), Unit::base(BaseUnit::Kilogram)),
         cnxt.get_variable("pound").expect("'pound' is not in the context")
     );
     assert_eq!(
@@ -211,6 +283,141 @@ fn has_named_units() {
     );
 }
 
+#[test]
+fn has_us_customary_units() {
+    let cnxt = QuantityContext::with_system(UnitSystem::UsCustomary);
+    assert_eq!(UnitSystem::UsCustomary, cnxt.system());
+    let volume = Unit::base(BaseUnit::Meter).pow(Number::from_i64(3));
+    assert_eq!(
+        Quantity::new(Number::from_i128s(3_785411784, 1_000_000_000_000), volume.clone()),
+        cnxt.get_variable("gal").expect("'gal' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i128s(946_352946, 1_000_000_000_000), volume.clone()),
+        cnxt.get_variable("quart").expect("'quart' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i128s(473_176473, 1_000_000_000_000), volume.clone()),
+        cnxt.get_variable("pint").expect("'pint' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i128s(118_29411825, 100_000_000_000_000), volume.clone()),
+        cnxt.get_variable("gill").expect("'gill' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i128s(29_5735295625, 10_000_000_000_000_000), volume.clone()),
+        cnxt.get_variable("floz").expect("'floz' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i128s(236_5882365, 10_000_000_000_000), volume.clone()),
+        cnxt.get_variable("cup").expect("'cup' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i128s(14_78676478125, 100_000_000_000_000_000), volume.clone()),
+        cnxt.get_variable("tbsp").expect("'tbsp' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i128s(4_92892159375, 100_000_000_000_000_000), volume.clone()),
+        cnxt.get_variable("tsp").expect("'tsp' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i64s(1200, 3937), Unit::base(BaseUnit::Meter)),
+        cnxt.get_variable("ftUS").expect("'ftUS' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i64s(907184_74, 100000), Unit::base(BaseUnit::Kilogram)),
+        cnxt.get_variable("ton").expect("'ton' is not in the context")
+    );
+}
+
+#[test]
+fn us_customary_relations() {
+    let cnxt = QuantityContext::with_system(UnitSystem::UsCustomary);
+    let convert = |from: &str, to: &str| {
+        cnxt.get_variable(from).expect("Unit is not in the context").convert_to_in(to, &cnxt)
+    };
+    assert_eq!(Some(Number::from_i64(231)), convert("gal", "in^3"));
+    assert_eq!(Some(Number::from_i64(4)), convert("gal", "quart"));
+    assert_eq!(Some(Number::from_i64(2)), convert("quart", "pint"));
+    assert_eq!(Some(Number::from_i64(2)), convert("pint", "cup"));
+    assert_eq!(Some(Number::from_i64(8)), convert("cup", "floz"));
+    assert_eq!(Some(Number::from_i64(16)), convert("cup", "tbsp"));
+    assert_eq!(Some(Number::from_i64(3)), convert("tbsp", "tsp"));
+    assert_eq!(Some(Number::from_i64(2000)), convert("ton", "lb"));
+    assert_eq!(Some(Number::from_i64(5280)), convert("miUS", "ftUS"));
+}
+
+#[test]
+fn namespaced_volume_units() {
+    for system in [UnitSystem::Imperial, UnitSystem::UsCustomary] {
+        let cnxt = QuantityContext::with_system(system);
+        let volume = Unit::base(BaseUnit::Meter).pow(Number::from_i64(3));
+        assert_eq!(
+            Quantity::new(Number::from_i128s(3_785411784, 1_000_000_000_000), volume.clone()),
+            cnxt.get_variable("usgal").expect("'usgal' is not in the context")
+        );
+        assert_eq!(
+            Quantity::new(Number::from_i64s(4546_09, 100000000), volume.clone()),
+            cnxt.get_variable("ukgal").expect("'ukgal' is not in the context")
+        );
+    }
+    assert_eq!(
+        QuantityContext::new().get_variable("ukton"),
+        QuantityContext::new().get_variable("ton")
+    );
+}
+
+#[test]
+fn add_base_dimension() {
+    let mut cnxt = QuantityContext::new();
+    cnxt.add_base_dimension("dollar", "USD").expect("Failed adding dimension");
+    assert_eq!(
+        Quantity::new(Number::one(), Unit::dimension("USD")),
+        cnxt.get_variable("dollar").expect("'dollar' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::one(), Unit::dimension("USD")),
+        cnxt.get_variable("USD").expect("'USD' is not in the context")
+    );
+    let price = Expr::parse("3 USD/GB + 5 USD/TB").unwrap().eval_in::<Quantity, _>(&cnxt)
+        .expect("Evaluation failed");
+    assert_eq!(Some(Number::from_i64s(3005, 1000)), price.convert_to_in("USD/GB", &cnxt));
+    assert_eq!("601/1600000000000 bit^-1 USD", price.to_string());
+    assert!(Expr::parse("3 USD/GB + 5 USD/h").unwrap().eval_in::<Quantity, _>(&cnxt).is_err());
+    assert!(Expr::parse("3 USD + 5").unwrap().eval_in::<Quantity, _>(&cnxt).is_err());
+    assert_eq!(
+        Quantity::new(Number::from_i64(1000), Unit::dimension("USD")),
+        cnxt.get_variable("kUSD").expect("'kUSD' is not in the context")
+    );
+    for (name, symbol) in [("bit", "b"), ("money", "m"), ("euro", "USD"), ("inch", "EUR")] {
+        let error = cnxt.add_base_dimension(name, symbol).expect_err("Adding dimension should fail");
+        assert_eq!(EvalErrorKind::UnitError, error.kind());
+    }
+}
+
+#[test]
+fn add_base_dimension_over_prefixed_unit() {
+    let mut cnxt = QuantityContext::new();
+    assert!(cnxt.get_variable("ct").is_some());
+    cnxt.add_base_dimension("count", "ct").expect("Failed adding dimension");
+    assert_eq!(
+        Quantity::new(Number::one(), Unit::dimension("ct")),
+        cnxt.get_variable("ct").expect("'ct' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i64(1000), Unit::dimension("ct")),
+        cnxt.get_variable("kct").expect("'kct' is not in the context")
+    );
+    assert_eq!(
+        Some(Number::from_i64(720)),
+        Expr::parse("12 ct/s * 1 min").unwrap().eval_in::<Quantity, _>(&cnxt).unwrap().convert_to_in("ct", &cnxt)
+    );
+    for (name, symbol) in [("dollar", "$"), ("euro", "€"), ("", "E1"), ("percount", "1ct")] {
+        let error = cnxt.add_base_dimension(name, symbol).expect_err("Adding dimension should fail");
+        assert_eq!(EvalErrorKind::UnitError, error.kind());
+    }
+}
+
 #[test]
 fn set_variable() {
     let mut cnxt = QuantityContext::new();
@@ -245,11 +452,11 @@ fn set_function() {
     let mut cnxt = QuantityContext::new();
     let num = Quantity::unitless(Number::from_i64(42));
     let func_num = num.clone();
-    let func = move |_| Ok(func_num.clone());
+    let func = move |_: &[Quantity]| Ok(func_num.clone());
     cnxt.set_function("func1", Box::new(func));
     let val = cnxt.get_function("func1")
         .expect("Context doesn't contain func1");
-    assert_eq!(num, val(Vec::new()).expect("Function call failed"));
+    assert_eq!(num, val(&[]).expect("Function call failed"));
 }
 
 #[test]
@@ -257,15 +464,15 @@ fn overwrite_function() {
     let mut cnxt = QuantityContext::new();
     let num = Quantity::unitless(Number::from_i64(12));
     let func_num = num.clone();
-    let func = move |_| Ok(func_num.clone());
+    let func = move |_: &[Quantity]| Ok(func_num.clone());
     cnxt.set_function("func1", Box::new(func));
     let num = Quantity::unitless(Number::from_i64(42));
     let func_num = num.clone();
-    let func = move |_| Ok(func_num.clone());
+    let func = move |_: &[Quantity]| Ok(func_num.clone());
     cnxt.set_function("func1", Box::new(func));
     let val = cnxt.get_function("func1")
         .expect("Context doesn't contain func1");
-    assert_eq!(num, val(Vec::new()).expect("Function call failed"));
+    assert_eq!(num, val(&[]).expect("Function call failed"));
 }
 
 #[test]
@@ -273,11 +480,11 @@ fn overwrite_buildin_function() {
     let mut cnxt = QuantityContext::new();
     let num = Quantity::unitless(Number::from_i64(42));
     let func_num = num.clone();
-    let func = move |_| Ok(func_num.clone());
+    let func = move |_: &[Quantity]| Ok(func_num.clone());
     cnxt.set_function("sin", Box::new(func));
     let val = cnxt.get_function("sin")
         .expect("Context doesn't contain sin");
-    assert_eq!(num, val(Vec::new()).expect("Function call failed"));
+    assert_eq!(num, val(&[]).expect("Function call failed"));
 }
 
 #[test]
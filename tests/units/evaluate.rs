@@ -2,7 +2,7 @@
 use std::ops::*;
 use num::traits::Pow;
 
-use matheval::{Quantity, Number, Unit, BaseUnit, Expr};
+use matheval::{Quantity, QuantityContext, Number, Unit, BaseUnit, Expr, EvalErrorKind};
 
 #[test]
 fn simple_integer_literal() {
@@ -86,10 +86,10 @@ fn derived_unit() {
         .expect("Failed parsing simple add");
     assert_eq!(
         Quantity::new(
-            Number::from_i64(5500),
+            Number::from_i64s(55, 10),
             Unit::base(BaseUnit::Meter).pow(Number::from_i64(2))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-3)))
-                .mul(Unit::base(BaseUnit::Gram))
+                .mul(Unit::base(BaseUnit::Kilogram))
         ),
         parsed.eval().expect("Evaluation failed")
     );
@@ -101,10 +101,10 @@ fn prefixed_unit() {
         .expect("Failed parsing simple add");
     assert_eq!(
         Quantity::new(
-            Number::from_i64s(55, 10),
+            Number::from_i64s(55, 10000),
             Unit::base(BaseUnit::Meter).pow(Number::from_i64(2))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-3)))
-                .mul(Unit::base(BaseUnit::Gram))
+                .mul(Unit::base(BaseUnit::Kilogram))
         ),
         parsed.eval().expect("Evaluation failed")
     );
@@ -146,8 +146,8 @@ fn simple_mul() {
         .expect("Failed parsing simple mul");
     assert_eq!(
         Quantity::new(
-            Number::from_i64s(0_01275, 100),
-            Unit::base(BaseUnit::Gram)
+            Number::from_i64s(0_01275, 100000),
+            Unit::base(BaseUnit::Kilogram)
                 .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(2)))
         ),
         parsed.eval().expect("Evaluation failed")
@@ -195,3 +195,245 @@ fn mixed_parens() {
     assert_eq!("390625/1679616", parsed.eval::<Quantity>().expect("Evaluation failed").to_string());
 }
 
+
+#[test]
+fn absolute_temperature() {
+    let parsed = Expr::parse("25 degC")
+        .expect("Failed parsing absolute temperature");
+    assert_eq!(
+        Quantity::absolute(Number::from_i64s(29815, 100), Unit::base(BaseUnit::Kelvin)),
+        parsed.eval().expect("Evaluation failed")
+    );
+    let parsed = Expr::parse("-40 degF")
+        .expect("Failed parsing absolute temperature");
+    assert_eq!(
+        Quantity::absolute(Number::from_i64s(23315, 100), Unit::base(BaseUnit::Kelvin)),
+        parsed.eval().expect("Evaluation failed")
+    );
+}
+
+#[test]
+fn temperature_difference() {
+    let parsed = Expr::parse("25 degC + 9 deltaF")
+        .expect("Failed parsing temperature difference");
+    assert_eq!(
+        Quantity::absolute(Number::from_i64s(30315, 100), Unit::base(BaseUnit::Kelvin)),
+        parsed.eval().expect("Evaluation failed")
+    );
+    let parsed = Expr::parse("25 degC - 50 degF")
+        .expect("Failed parsing temperature difference");
+    assert_eq!(
+        Quantity::difference(Number::from_i64(15), Unit::base(BaseUnit::Kelvin)),
+        parsed.eval().expect("Evaluation failed")
+    );
+    let parsed = Expr::parse("(30 degC - 20 degC) / 2 + 10 degC to degC")
+        .expect("Failed parsing temperature difference");
+    assert_eq!(Quantity::unitless(Number::from_i64(15)), parsed.eval().expect("Evaluation failed"));
+    let parsed = Expr::parse("4 J / degC")
+        .expect("Failed parsing temperature difference");
+    assert_eq!(
+        Quantity::new(
+            Number::from_i64(4),
+            Unit::base(BaseUnit::Kilogram)
+                .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(2)))
+                .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-2)))
+                .mul(Unit::base(BaseUnit::Kelvin).pow(Number::neg_one()))
+        ),
+        parsed.eval().expect("Evaluation failed")
+    );
+}
+
+#[test]
+fn kelvin_temperature() {
+    let parsed = Expr::parse("300 K to degC")
+        .expect("Failed parsing conversion");
+    assert_eq!(Quantity::unitless(Number::from_i64s(2685, 100)), parsed.eval().expect("Evaluation failed"));
+    let parsed = Expr::parse("26.85 degC to K")
+        .expect("Failed parsing conversion");
+    assert_eq!(Quantity::unitless(Number::from_i64(300)), parsed.eval().expect("Evaluation failed"));
+    let parsed = Expr::parse("0 K to degC")
+        .expect("Failed parsing conversion");
+    assert_eq!(Quantity::unitless(Number::from_i64s(-27315, 100)), parsed.eval().expect("Evaluation failed"));
+    let parsed = Expr::parse("(-273.15) degC to K")
+        .expect("Failed parsing conversion");
+    assert_eq!(Quantity::unitless(Number::zero()), parsed.eval().expect("Evaluation failed"));
+    let parsed = Expr::parse("300 K == 26.85 degC")
+        .expect("Failed parsing comparison");
+    assert_eq!(Quantity::unitless(Number::one()), parsed.eval().expect("Evaluation failed"));
+    let parsed = Expr::parse("25 degC + 300 K to degC")
+        .expect("Failed parsing temperature sum");
+    assert_eq!(Quantity::unitless(Number::from_i64(325)), parsed.eval().expect("Evaluation failed"));
+    let parsed = Expr::parse("300 K - 25 degC")
+        .expect("Failed parsing temperature difference");
+    assert_eq!(
+        Quantity::difference(Number::from_i64s(185, 100), Unit::base(BaseUnit::Kelvin)),
+        parsed.eval().expect("Evaluation failed")
+    );
+    let parsed = Expr::parse("25 degC < 300 K")
+        .expect("Failed parsing comparison");
+    assert_eq!(Quantity::unitless(Number::one()), parsed.eval().expect("Evaluation failed"));
+}
+
+#[test]
+fn absolute_temperature_error() {
+    assert!(Expr::parse("25 degC + 30 degC").unwrap().eval::<Quantity>().is_err());
+    assert!(Expr::parse("5 deltaC - 30 degC").unwrap().eval::<Quantity>().is_err());
+    for source in ["2 * (25 degC)", "-(25 degC)", "(25 degC)^2", "10 deltaC to degC", "25 degC < 3 deltaC"] {
+        let error = Expr::parse(source).unwrap().eval::<Quantity>().expect_err(source);
+        assert_eq!(EvalErrorKind::UnitError, error.kind(), "{}", source);
+    }
+}
+
+#[test]
+fn comparison() {
+    let parsed = Expr::parse("2 km > 1500 m && 1 h == 3600 s")
+        .expect("Failed parsing comparison");
+    assert_eq!(Quantity::unitless(Number::one()), parsed.eval().expect("Evaluation failed"));
+    let parsed = Expr::parse("20 degC < 60 degF")
+        .expect("Failed parsing comparison");
+    assert_eq!(Quantity::unitless(Number::zero()), parsed.eval().expect("Evaluation failed"));
+    let parsed = Expr::parse("degF < degC")
+        .expect("Failed parsing comparison");
+    assert_eq!(Quantity::unitless(Number::one()), parsed.eval().expect("Evaluation failed"));
+}
+
+#[test]
+fn comparison_error() {
+    let error = Expr::parse("2 m < 3 s").unwrap().eval::<Quantity>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::UnitError, error.kind());
+    assert_eq!("Cannot compare m to s", error.message());
+    let error = Expr::parse("!(2 m)").unwrap().eval::<Quantity>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::UnitError, error.kind());
+}
+
+#[test]
+fn definitions() {
+    let mut context = QuantityContext::new();
+    let parsed = Expr::parse("v = 3 m/s")
+        .expect("Failed parsing assignment");
+    parsed.execute_in(&mut context).expect("Evaluation failed");
+    let parsed = Expr::parse("dist(t) = v t")
+        .expect("Failed parsing function definition");
+    parsed.execute_in(&mut context).expect("Evaluation failed");
+    let parsed = Expr::parse("dist(1 min)")
+        .expect("Failed parsing function call");
+    assert_eq!(
+        Quantity::new(Number::from_i64(180), Unit::base(BaseUnit::Meter)),
+        parsed.eval_in(&context).expect("Evaluation failed")
+    );
+}
+
+#[test]
+fn conversion() {
+    let parsed = Expr::parse("25 degC to degF")
+        .expect("Failed parsing conversion");
+    assert_eq!(Quantity::unitless(Number::from_i64(77)), parsed.eval().expect("Evaluation failed"));
+    let parsed = Expr::parse("2 m to s")
+        .expect("Failed parsing conversion");
+    let error = parsed.eval::<Quantity>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::UnitError, error.kind());
+}
+
+#[test]
+fn program() {
+    let mut context = QuantityContext::new();
+    let program = Expr::parse_program("r = 2 ft; A = 3 r^2; A to ft^2")
+        .expect("Failed parsing program");
+    assert_eq!(
+        Some(Quantity::unitless(Number::from_i64(12))),
+        Expr::execute_program_in(&program, &mut context).expect("Evaluation failed")
+    );
+}
+
+#[test]
+fn integrate_with_units() {
+    let parsed = Expr::parse("integrate(t, 0 s, 10 s, 2 m/s^2 * t)")
+        .expect("Failed parsing integrate");
+    assert_eq!(
+        Quantity::new(Number::from_i64(100), Unit::base(BaseUnit::Meter)),
+        parsed.eval().expect("Evaluation failed")
+    );
+    let parsed = Expr::parse("sum(i, 1, 4, i m)")
+        .expect("Failed parsing sum");
+    assert_eq!(
+        Quantity::new(Number::from_i64(10), Unit::base(BaseUnit::Meter)),
+        parsed.eval().expect("Evaluation failed")
+    );
+}
+
+#[test]
+fn solve_with_units() {
+    let parsed = Expr::parse("solve(x, 0 m, 10 m, x^2 - 4 m^2)")
+        .expect("Failed parsing solve");
+    let result = parsed.eval::<Quantity>().expect("Evaluation failed");
+    assert_eq!(&Unit::base(BaseUnit::Meter), result.unit());
+    assert!((result.coefficient().to_f64() - 2.0).abs() < 1e-15);
+    let parsed = Expr::parse("solve(x, 0 m, 10 m, x - 4 s)")
+        .expect("Failed parsing solve");
+    let error = parsed.eval::<Quantity>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::UnitError, error.kind());
+}
+
+#[test]
+fn error_location() {
+    let parsed = Expr::parse("2 m + sqrt(-1 m)")
+        .expect("Failed parsing expression");
+    let error = parsed.eval::<Quantity>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::MathError, error.kind());
+    assert_eq!(Some(&Expr::parse("sqrt(-1 m)").unwrap()), error.expr());
+    assert_eq!(["sqrt"], error.calls());
+    assert_eq!(None, error.identifier());
+    let parsed = Expr::parse("5 m + 2 s")
+        .expect("Failed parsing expression");
+    let error = parsed.eval::<Quantity>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::UnitError, error.kind());
+    assert_eq!(Some(&parsed), error.expr());
+    assert!(error.calls().is_empty());
+    assert_eq!("Unit error: Cannot add m to s in '5 * m + 2 * s'", error.to_string());
+}
+
+#[test]
+fn error_unknown_identifier() {
+    let parsed = Expr::parse("3 * (2 furlongs + 1 parsec)")
+        .expect("Failed parsing expression");
+    let error = parsed.eval::<Quantity>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::UnknownVariable, error.kind());
+    assert_eq!(Some("parsec"), error.identifier());
+    assert_eq!(Some(&Expr::Variable("parsec".to_owned())), error.expr());
+    assert_eq!("Unknown variable: parsec in 'parsec'", error.to_string());
+    let parsed = Expr::parse("foo(2 m)")
+        .expect("Failed parsing expression");
+    let error = parsed.eval::<Quantity>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::UnknownFunction, error.kind());
+    assert_eq!(Some("foo"), error.identifier());
+    let error: Box<dyn std::error::Error> = Box::new(error);
+    assert_eq!("Unknown function: foo in 'foo(2 * m)'", error.to_string());
+}
+
+#[test]
+fn integer_operators() {
+    let parsed = Expr::parse("100 min % 1 h")
+        .expect("Failed parsing modulo");
+    assert_eq!(
+        Quantity::new(Number::from_i64(2400), Unit::base(BaseUnit::Second)),
+        parsed.eval().expect("Evaluation failed")
+    );
+    let parsed = Expr::parse("100 min // 1 h")
+        .expect("Failed parsing floor division");
+    assert_eq!(Quantity::unitless(Number::from_i64(1)), parsed.eval().expect("Evaluation failed"));
+    let parsed = Expr::parse("12 | 3")
+        .expect("Failed parsing bitwise or");
+    assert_eq!(Quantity::unitless(Number::from_i64(15)), parsed.eval().expect("Evaluation failed"));
+    let parsed = Expr::parse("1 m % 1 s")
+        .expect("Failed parsing modulo");
+    let error = parsed.eval::<Quantity>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::UnitError, error.kind());
+    let parsed = Expr::parse("5 m // 2 s")
+        .expect("Failed parsing floor division");
+    let error = parsed.eval::<Quantity>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::UnitError, error.kind());
+    let parsed = Expr::parse("~(1 m)")
+        .expect("Failed parsing complement");
+    let error = parsed.eval::<Quantity>().expect_err("Evaluation should fail");
+    assert_eq!(EvalErrorKind::UnitError, error.kind());
+}
@@ -0,0 +1,60 @@
+
+use matheval::{Quantity, Number, Expr, Unit, BaseUnit};
+
+fn best_string(source: &str) -> String {
+    Expr::parse(source).expect("Failed parsing expression")
+        .eval::<Quantity>().expect("Evaluation failed")
+        .to_best_string()
+}
+
+#[test]
+fn derived_units() {
+    assert_eq!("1 J", best_string("1000 g m^2 s^-2"));
+    assert_eq!("10 W", best_string("5 kV * 2 mA"));
+    assert_eq!("3 N", best_string("3 kg m / s^2"));
+    assert_eq!("2 Pa", best_string("2 N / m^2"));
+    assert_eq!("5 ohm", best_string("10 V / (2 A)"));
+    assert_eq!("4 C", best_string("2 A * 2 s"));
+    assert_eq!("50 Hz", best_string("3000 / min"));
+    assert_eq!("5/2 GHz", best_string("5 / (2 ns)"));
+}
+
+#[test]
+fn prefixed_units() {
+    assert_eq!("5 kg", best_string("5000 g"));
+    assert_eq!("500 um", best_string("0.5 mm"));
+    assert_eq!("12 MJ", best_string("12e6 J"));
+    assert_eq!("3/2 kW", best_string("1500 W"));
+    assert_eq!("1 ms", best_string("1/1000 s"));
+    assert_eq!("8 Gbit", best_string("1 GB"));
+}
+
+#[test]
+fn time_units() {
+    assert_eq!("40 min", best_string("100 min % 1 h"));
+    assert_eq!("3/2 h", best_string("5400 s"));
+    assert_eq!("2 d", best_string("48 h"));
+    assert_eq!("30 s", best_string("30 s"));
+    assert_eq!("-5 min", best_string("-300 s"));
+}
+
+#[test]
+fn derived_with_rest() {
+    assert_eq!("12 J K^-1", best_string("12 J/K"));
+    assert_eq!("7 V m^-1", best_string("7 V/m"));
+    assert_eq!("3 m s^-1", best_string("3 m/s"));
+}
+
+#[test]
+fn base_units() {
+    assert_eq!("5/6 m s^-1", best_string("3 km/h"));
+    assert_eq!("4 m^2", best_string("2m * 2m"));
+    assert_eq!("42", best_string("42"));
+}
+
+#[test]
+fn best_unit() {
+    let (coefficient, unit) = Quantity::new(Number::from_i64(2000), Unit::base(BaseUnit::Meter)).best_unit();
+    assert_eq!(Number::from_i64(2), coefficient);
+    assert_eq!("km", unit);
+}
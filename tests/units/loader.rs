@@ -0,0 +1,109 @@
+
+use std::ops::*;
+use num::traits::Pow;
+
+use matheval::{Quantity, Number, QuantityContext, Context, Unit, BaseUnit};
+
+#[test]
+fn load_simple_units() {
+    let mut cnxt = QuantityContext::new();
+    cnxt.load_units("
+        # Typographic units
+        point, pt = 1/72 in
+        pica = 12 point
+    ".as_bytes()).expect("Failed loading units");
+    assert_eq!(
+        Quantity::new(Number::from_i64s(254, 72_0000), Unit::base(BaseUnit::Meter)),
+        cnxt.get_variable("point").expect("'point' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i64s(254, 72_0000), Unit::base(BaseUnit::Meter)),
+        cnxt.get_variable("pt").expect("'pt' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i64s(12 * 254, 72_0000), Unit::base(BaseUnit::Meter)),
+        cnxt.get_variable("pica").expect("'pica' is not in the context")
+    );
+}
+
+#[test]
+fn load_derived_units() {
+    let mut cnxt = QuantityContext::new();
+    cnxt.load_units("knot, kn = nmi / h # nautical miles per hour".as_bytes()).expect("Failed loading units");
+    assert_eq!(
+        Quantity::new(
+            Number::from_i64s(1852, 3600),
+            Unit::base(BaseUnit::Meter).mul(Unit::base(BaseUnit::Second).pow(Number::neg_one()))
+        ),
+        cnxt.get_variable("kn").expect("'kn' is not in the context")
+    );
+}
+
+#[test]
+fn load_base_dimensions() {
+    let mut cnxt = QuantityContext::new();
+    cnxt.load_units("
+        px, pixel, pixels = !
+        megapixel, MP = 1000000 px
+    ".as_bytes()).expect("Failed loading units");
+    assert_eq!(
+        Quantity::new(Number::one(), Unit::dimension("px")),
+        cnxt.get_variable("pixel").expect("'pixel' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i64(1_000_000), Unit::dimension("px")),
+        cnxt.get_variable("MP").expect("'MP' is not in the context")
+    );
+    assert_eq!(
+        Quantity::new(Number::from_i64(1000), Unit::dimension("px")),
+        cnxt.get_variable("kpx").expect("'kpx' is not in the context")
+    );
+    let error = cnxt.load_units("word, bit = !".as_bytes()).expect_err("Loading should fail");
+    assert_eq!(1, error.line());
+}
+
+#[test]
+fn load_overrides_buildin() {
+    let mut cnxt = QuantityContext::new();
+    cnxt.load_units("gallon, gal = 231 in^3".as_bytes()).expect("Failed loading units");
+    assert_eq!(
+        Some(Number::from_i64(231)),
+        cnxt.get_variable("gal").expect("'gal' is not in the context").convert_to_in("in^3", &cnxt)
+    );
+    cnxt.set_variable("gal", Quantity::unitless(Number::one()));
+    assert_eq!(Quantity::unitless(Number::one()), cnxt.get_variable("gal").unwrap());
+}
+
+#[test]
+fn load_prefixed() {
+    let mut cnxt = QuantityContext::new();
+    cnxt.load_units("
+        furlong = 660 ft
+        smoot = 67 in
+    ".as_bytes()).expect("Failed loading units");
+    assert_eq!(
+        Some(Number::from_i64(660_000)),
+        cnxt.get_variable("kfurlong").expect("'kfurlong' is not in the context").convert_to_in("ft", &cnxt)
+    );
+    assert_eq!(
+        Some(Number::from_i64s(67, 1000)),
+        cnxt.get_variable("msmoot").expect("'msmoot' is not in the context").convert_to_in("in", &cnxt)
+    );
+}
+
+#[test]
+fn load_errors() {
+    let error = QuantityContext::new().load_units("a = 1 m\n\nb 2 m\n".as_bytes())
+        .expect_err("Missing '=' is an error");
+    assert_eq!(3, error.line());
+    let error = QuantityContext::new().load_units("a = 1 m\nb = (2 m\n".as_bytes())
+        .expect_err("Invalid expression is an error");
+    assert_eq!(2, error.line());
+    let error = QuantityContext::new().load_units("a = 1 m\nb = 1 m + 1 s\n".as_bytes())
+        .expect_err("Evaluation failure is an error");
+    assert_eq!(2, error.line());
+    let error = QuantityContext::new().load_units("a, 2b = 1 m".as_bytes())
+        .expect_err("Invalid name is an error");
+    assert_eq!(1, error.line());
+    assert_eq!("Error on line 1: Invalid unit name '2b'", error.to_string());
+}
@@ -2,7 +2,7 @@
 use std::{ops::*, str::FromStr, cmp::Ordering};
 use num::traits::Pow;
 
-use matheval::{Quantity, Number, Unit, BaseUnit, QuantityContext};
+use matheval::{Quantity, Number, Unit, BaseUnit, QuantityContext, Expr};
 
 #[test]
 fn from_str_integer() {
@@ -163,20 +163,20 @@ fn to_string_float() {
 fn to_string_with_unit() {
     let num = Quantity::new(
         Number::from_i64(4200),
-        Unit::base(BaseUnit::Gram).mul(Unit::base(BaseUnit::Meter)).mul(Unit::base(BaseUnit::Second))
+        Unit::base(BaseUnit::Kilogram).mul(Unit::base(BaseUnit::Meter)).mul(Unit::base(BaseUnit::Second))
     );
-    assert_eq!("4200 g m s", num.to_string());
+    assert_eq!("4200 kg m s", num.to_string());
 }
 
 #[test]
 fn to_string_with_unit2() {
     let num = Quantity::new(
         Number::from_i64(4200),
-        Unit::base(BaseUnit::Gram).pow(Number::from_i64(2))
+        Unit::base(BaseUnit::Kilogram).pow(Number::from_i64(2))
             .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(-10)))
             .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64s(-3, 4)))
     );
-    assert_eq!("4200 g^2 m^-10 s^(-3/4)", num.to_string());
+    assert_eq!("4200 kg^2 m^-10 s^(-3/4)", num.to_string());
 }
 
 #[test]
@@ -188,32 +188,32 @@ fn is_unitless() {
 
 #[test]
 fn is_not_unitless() {
-    assert!(!Quantity::new(Number::Float(12.3456789), Unit::base(BaseUnit::Gram)).is_unitless());
-    assert!(!Quantity::new(Number::from_i64(123), Unit::base(BaseUnit::Gram)).is_unitless());
-    assert!(!Quantity::new(Number::from_i64s(123, 7), Unit::base(BaseUnit::Gram)).is_unitless());
+    assert!(!Quantity::new(Number::Float(12.3456789), Unit::base(BaseUnit::Kilogram)).is_unitless());
+    assert!(!Quantity::new(Number::from_i64(123), Unit::base(BaseUnit::Kilogram)).is_unitless());
+    assert!(!Quantity::new(Number::from_i64s(123, 7), Unit::base(BaseUnit::Kilogram)).is_unitless());
 }
 
 #[test]
 fn coefficient() {
     assert_eq!(
         &Number::Float(12.3456789),
-        Quantity::new(Number::Float(12.3456789), Unit::base(BaseUnit::Gram)).coefficient()
+        Quantity::new(Number::Float(12.3456789), Unit::base(BaseUnit::Kilogram)).coefficient()
     );
     assert_eq!(
         &Number::from_i64(123),
-        Quantity::new(Number::from_i64(123), Unit::base(BaseUnit::Gram)).coefficient()
+        Quantity::new(Number::from_i64(123), Unit::base(BaseUnit::Kilogram)).coefficient()
     );
     assert_eq!(
         &Number::from_i64s(123, 7),
-        Quantity::new(Number::from_i64s(123, 7), Unit::base(BaseUnit::Gram)).coefficient()
+        Quantity::new(Number::from_i64s(123, 7), Unit::base(BaseUnit::Kilogram)).coefficient()
     );
 }
 
 #[test]
 fn unit() {
     assert_eq!(
-        &Unit::base(BaseUnit::Gram),
-        Quantity::new(Number::Float(12.3456789), Unit::base(BaseUnit::Gram)).unit()
+        &Unit::base(BaseUnit::Kilogram),
+        Quantity::new(Number::Float(12.3456789), Unit::base(BaseUnit::Kilogram)).unit()
     );
     assert_eq!(
         &Unit::base(BaseUnit::Second).mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(10))),
@@ -229,8 +229,8 @@ fn unit() {
 #[test]
 fn abs() {
     assert_eq!(
-        Quantity::new(Number::Float(12.3456789), Unit::base(BaseUnit::Gram)),
-        Quantity::new(Number::Float(12.3456789), Unit::base(BaseUnit::Gram)).abs()
+        Quantity::new(Number::Float(12.3456789), Unit::base(BaseUnit::Kilogram)),
+        Quantity::new(Number::Float(12.3456789), Unit::base(BaseUnit::Kilogram)).abs()
     );
     assert_eq!(
         Quantity::new(Number::Float(12.3456789), Unit::base(BaseUnit::Second)),
@@ -258,7 +258,7 @@ fn abs() {
 fn sign() {
     assert_eq!(
         Quantity::unitless(Number::one()),
-        Quantity::new(Number::Float(12.3456789), Unit::base(BaseUnit::Gram)).sign()
+        Quantity::new(Number::Float(12.3456789), Unit::base(BaseUnit::Kilogram)).sign()
     );
     assert_eq!(
         Quantity::unitless(Number::neg_one()),
@@ -281,8 +281,8 @@ fn sign() {
 #[test]
 fn sqrt() {
     assert_eq!(
-        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram).pow(Number::from_i64s(1, 2))),
-        Quantity::new(Number::from_i64s(64, 9), Unit::base(BaseUnit::Gram)).sqrt()
+        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram).pow(Number::from_i64s(1, 2))),
+        Quantity::new(Number::from_i64s(64, 9), Unit::base(BaseUnit::Kilogram)).sqrt()
     );
     assert_eq!(
         Quantity::new(Number::from_i64s(3, 8), Unit::base(BaseUnit::Second)),
@@ -293,8 +293,8 @@ fn sqrt() {
 #[test]
 fn cbrt() {
     assert_eq!(
-        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram).pow(Number::from_i64s(1, 3))),
-        Quantity::new(Number::from_i64s(512, 27), Unit::base(BaseUnit::Gram)).cbrt()
+        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram).pow(Number::from_i64s(1, 3))),
+        Quantity::new(Number::from_i64s(512, 27), Unit::base(BaseUnit::Kilogram)).cbrt()
     );
     assert_eq!(
         Quantity::new(Number::from_i64s(3, 8), Unit::base(BaseUnit::Second)),
@@ -306,21 +306,50 @@ fn cbrt() {
 fn convert_to() {
     let context = QuantityContext::new();
     assert_eq!(
-        Some(Number::from_i64(1000)), Quantity::new(Number::one(), Unit::base(BaseUnit::Gram)).convert_to_in("mg", &context)
+        Some(Number::from_i64(1_000_000)), Quantity::new(Number::one(), Unit::base(BaseUnit::Kilogram)).convert_to_in("mg", &context)
     );
     assert_eq!(
-        Some(Number::from_i64s(1, 1000)), Quantity::new(Number::one(), Unit::base(BaseUnit::Gram)).convert_to_in("kg", &context)
+        Some(Number::from_i64(1000)), Quantity::new(Number::one(), Unit::base(BaseUnit::Kilogram)).convert_to_in("g", &context)
     );
     assert_eq!(
         Some(Number::from_i128s(5000000000000000000000000000, 801088317)),
-        Quantity::new(Number::from_i64(1000),
-            Unit::base(BaseUnit::Gram)
+        Quantity::new(Number::one(),
+            Unit::base(BaseUnit::Kilogram)
                 .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64(2)))
                 .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(-2)))
         ).convert_to_in("eV", &context)
     );
 }
 
+#[test]
+fn convert_to_information() {
+    let context = QuantityContext::new();
+    let transfer = Expr::parse("4 GiB / (100 Mbit/s)").unwrap().eval_in::<Quantity, _>(&context).unwrap();
+    assert_eq!(Some(Number::from_i64s(34359738368, 100000000)), transfer.convert_to_in("s", &context));
+    let transfer = Expr::parse("4 GiB / (100 Mbit/s) to s").unwrap().eval_in::<Quantity, _>(&context).unwrap();
+    assert_eq!(Quantity::unitless(Number::from_i64s(34359738368, 100000000)), transfer);
+    let size = Quantity::new(Number::from_i64(8 << 30), Unit::base(BaseUnit::Bit));
+    assert_eq!(Some(Number::from_i64s(1 << 30, 1_000_000_000)), size.convert_to_in("GB", &context));
+    assert_eq!(Some(Number::from_i64(1 << 20)), size.convert_to_in("KiB", &context));
+}
+
+#[test]
+fn convert_to_temperature() {
+    let context = QuantityContext::new();
+    let celsius = Quantity::absolute(Number::from_i64s(29815, 100), Unit::base(BaseUnit::Kelvin));
+    assert_eq!(Some(Number::from_i64(25)), celsius.convert_to_in("degC", &context));
+    assert_eq!(Some(Number::from_i64(77)), celsius.convert_to_in("degF", &context));
+    assert_eq!(Some(Number::from_i64s(29815, 100)), celsius.convert_to_in("K", &context));
+    assert_eq!(Some(Number::from_i64s(107334, 200)), celsius.convert_to_in("degR", &context));
+    let cold = Quantity::absolute(Number::from_i64(10), Unit::base(BaseUnit::Kelvin));
+    assert_eq!(Some(Number::from_i64s(-52630, 200)), cold.convert_to_in("degC", &context));
+    let difference = Quantity::difference(Number::from_i64(10), Unit::base(BaseUnit::Kelvin));
+    assert_eq!(Some(Number::from_i64(18)), difference.convert_to_in("deltaF", &context));
+    assert_eq!(None, difference.convert_to_in("degC", &context));
+    let kelvin = Quantity::new(Number::from_i64(10), Unit::base(BaseUnit::Kelvin));
+    assert_eq!(Some(Number::from_i64s(-52630, 200)), kelvin.convert_to_in("degC", &context));
+}
+
 #[test]
 fn ord() {
     assert_eq!(None,
@@ -344,8 +373,8 @@ fn ord() {
 #[test]
 fn neg() {
     assert_eq!(
-        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram)),
-        Quantity::new(Number::from_i64s(-8, 3), Unit::base(BaseUnit::Gram)).neg().expect("neg failed")
+        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram)),
+        Quantity::new(Number::from_i64s(-8, 3), Unit::base(BaseUnit::Kilogram)).neg().expect("neg failed")
     );
     assert_eq!(
         Quantity::new(Number::from_i64s(-3, 8), Unit::base(BaseUnit::Second).pow(Number::from_i64(2))),
@@ -356,9 +385,9 @@ fn neg() {
 #[test]
 fn add() {
     assert_eq!(
-        Quantity::new(Number::from_i64s(16, 3), Unit::base(BaseUnit::Gram)),
-        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram)).add(
-            Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram)),
+        Quantity::new(Number::from_i64s(16, 3), Unit::base(BaseUnit::Kilogram)),
+        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram)).add(
+            Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram)),
         ).expect("neg failed")
     );
     assert_eq!(
@@ -372,13 +401,13 @@ fn add() {
 #[test]
 fn add_error() {
     assert!(
-        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram)).add(
+        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram)).add(
             Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Second)),
         ).is_err()
     );
     assert!(
-        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram)).add(
-            Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram).pow(Number::from_i64(2))),
+        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram)).add(
+            Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram).pow(Number::from_i64(2))),
         ).is_err()
     );
     assert!(
@@ -391,9 +420,9 @@ fn add_error() {
 #[test]
 fn sub() {
     assert_eq!(
-        Quantity::new(Number::from_i64s(16, 3), Unit::base(BaseUnit::Gram)),
-        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram)).sub(
-            Quantity::new(Number::from_i64s(-8, 3), Unit::base(BaseUnit::Gram)),
+        Quantity::new(Number::from_i64s(16, 3), Unit::base(BaseUnit::Kilogram)),
+        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram)).sub(
+            Quantity::new(Number::from_i64s(-8, 3), Unit::base(BaseUnit::Kilogram)),
         ).expect("neg failed")
     );
     assert_eq!(
@@ -407,13 +436,13 @@ fn sub() {
 #[test]
 fn sub_error() {
     assert!(
-        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram)).sub(
+        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram)).sub(
             Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Second)),
         ).is_err()
     );
     assert!(
-        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram)).sub(
-            Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram).pow(Number::from_i64(2))),
+        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram)).sub(
+            Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram).pow(Number::from_i64(2))),
         ).is_err()
     );
     assert!(
@@ -426,9 +455,9 @@ fn sub_error() {
 #[test]
 fn mul() {
     assert_eq!(
-        Quantity::new(Number::from_i64s(-64, 9), Unit::base(BaseUnit::Gram).pow(Number::from_i64(2))),
-        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram)).mul(
-            Quantity::new(Number::from_i64s(-8, 3), Unit::base(BaseUnit::Gram)),
+        Quantity::new(Number::from_i64s(-64, 9), Unit::base(BaseUnit::Kilogram).pow(Number::from_i64(2))),
+        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram)).mul(
+            Quantity::new(Number::from_i64s(-8, 3), Unit::base(BaseUnit::Kilogram)),
         ).expect("neg failed")
     );
     assert_eq!(
@@ -444,9 +473,9 @@ fn mul() {
         ).expect("neg failed")
     );
     assert_eq!(
-        Quantity::new(Number::from_i64s(16, 21), Unit::base(BaseUnit::Second).mul(Unit::base(BaseUnit::Gram))),
+        Quantity::new(Number::from_i64s(16, 21), Unit::base(BaseUnit::Second).mul(Unit::base(BaseUnit::Kilogram))),
         Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Second)).mul(
-            Quantity::new(Number::from_i64s(2, 7), Unit::base(BaseUnit::Gram)),
+            Quantity::new(Number::from_i64s(2, 7), Unit::base(BaseUnit::Kilogram)),
         ).expect("neg failed")
     );
 }
@@ -455,8 +484,8 @@ fn mul() {
 fn div() {
     assert_eq!(
         Quantity::unitless(Number::neg_one()),
-        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram)).div(
-            Quantity::new(Number::from_i64s(-8, 3), Unit::base(BaseUnit::Gram)),
+        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram)).div(
+            Quantity::new(Number::from_i64s(-8, 3), Unit::base(BaseUnit::Kilogram)),
         ).expect("neg failed")
     );
     assert_eq!(
@@ -466,9 +495,9 @@ fn div() {
         ).expect("neg failed")
     );
     assert_eq!(
-        Quantity::new(Number::from_i64s(56, 6), Unit::base(BaseUnit::Second).mul(Unit::base(BaseUnit::Gram).pow(Number::neg_one()))),
+        Quantity::new(Number::from_i64s(56, 6), Unit::base(BaseUnit::Second).mul(Unit::base(BaseUnit::Kilogram).pow(Number::neg_one()))),
         Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Second)).div(
-            Quantity::new(Number::from_i64s(2, 7), Unit::base(BaseUnit::Gram)),
+            Quantity::new(Number::from_i64s(2, 7), Unit::base(BaseUnit::Kilogram)),
         ).expect("neg failed")
     );
 }
@@ -476,20 +505,20 @@ fn div() {
 #[test]
 fn pow() {
     assert_eq!(
-        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram)),
-        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram)).pow(
+        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram)),
+        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram)).pow(
             Quantity::unitless(Number::one())
         ).expect("neg failed")
     );
     assert_eq!(
-        Quantity::new(Number::from_i64s(64, 9), Unit::base(BaseUnit::Gram).pow(Number::from_i64(2))),
-        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram)).pow(
+        Quantity::new(Number::from_i64s(64, 9), Unit::base(BaseUnit::Kilogram).pow(Number::from_i64(2))),
+        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram)).pow(
             Quantity::unitless(Number::from_i64(2))
         ).expect("neg failed")
     );
     assert_eq!(
-        Quantity::new(Number::from_i64s(9, 64), Unit::base(BaseUnit::Gram).pow(Number::from_i64(-2))),
-        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Gram)).pow(
+        Quantity::new(Number::from_i64s(9, 64), Unit::base(BaseUnit::Kilogram).pow(Number::from_i64(-2))),
+        Quantity::new(Number::from_i64s(8, 3), Unit::base(BaseUnit::Kilogram)).pow(
             Quantity::unitless(Number::from_i64(-2))
         ).expect("neg failed")
     );
@@ -503,12 +532,12 @@ fn pow_error() {
         ).is_err()
     );
     assert!(
-        Quantity::new(Number::zero(), Unit::base(BaseUnit::Gram)).pow(
+        Quantity::new(Number::zero(), Unit::base(BaseUnit::Kilogram)).pow(
             Quantity::unitless(Number::from_i64(0))
         ).is_err()
     );
     assert!(
-        Quantity::new(Number::zero(), Unit::base(BaseUnit::Gram)).pow(
+        Quantity::new(Number::zero(), Unit::base(BaseUnit::Kilogram)).pow(
             Quantity::unitless(Number::from_i64(-2))
         ).is_err()
     );
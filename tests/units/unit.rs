@@ -8,11 +8,12 @@ use matheval::{Unit, BaseUnit, Number};
 fn base_unit_symbols() {
     assert_eq!("s", BaseUnit::Second.symbol());
     assert_eq!("m", BaseUnit::Meter.symbol());
-    assert_eq!("g", BaseUnit::Gram.symbol());
+    assert_eq!("kg", BaseUnit::Kilogram.symbol());
     assert_eq!("K", BaseUnit::Kelvin.symbol());
     assert_eq!("mol", BaseUnit::Mole.symbol());
     assert_eq!("cd", BaseUnit::Candela.symbol());
     assert_eq!("A", BaseUnit::Ampere.symbol());
+    assert_eq!("bit", BaseUnit::Bit.symbol());
 }
 
 #[test]
@@ -24,6 +25,7 @@ fn base_unit_try_from() {
     assert!(BaseUnit::try_from(4).is_ok());
     assert!(BaseUnit::try_from(5).is_ok());
     assert!(BaseUnit::try_from(6).is_ok());
+    assert!(BaseUnit::try_from(7).is_ok());
 }
 
 #[test]
@@ -31,7 +33,7 @@ fn is_empty() {
     assert!(Unit::empty().is_empty());
     assert!(!Unit::base(BaseUnit::Second).is_empty());
     assert!(!Unit::base(BaseUnit::Meter).is_empty());
-    assert!(!Unit::base(BaseUnit::Gram).is_empty());
+    assert!(!Unit::base(BaseUnit::Kilogram).is_empty());
     assert!(!Unit::base(BaseUnit::Candela).is_empty());
 }
 
@@ -40,7 +42,7 @@ fn equal() {
     assert_eq!(Unit::empty(), Unit::empty());
     assert_eq!(Unit::base(BaseUnit::Second), Unit::base(BaseUnit::Second));
     assert_eq!(Unit::base(BaseUnit::Meter), Unit::base(BaseUnit::Meter));
-    assert_eq!(Unit::base(BaseUnit::Gram), Unit::base(BaseUnit::Gram));
+    assert_eq!(Unit::base(BaseUnit::Kilogram), Unit::base(BaseUnit::Kilogram));
     assert_eq!(Unit::base(BaseUnit::Kelvin), Unit::base(BaseUnit::Kelvin));
     assert_eq!(Unit::base(BaseUnit::Mole), Unit::base(BaseUnit::Mole));
     assert_eq!(Unit::base(BaseUnit::Candela), Unit::base(BaseUnit::Candela));
@@ -55,7 +57,7 @@ fn equal() {
 fn not_equal() {
     assert_ne!(Unit::empty(), Unit::base(BaseUnit::Second));
     assert_ne!(Unit::empty(), Unit::base(BaseUnit::Meter));
-    assert_ne!(Unit::empty(), Unit::base(BaseUnit::Gram));
+    assert_ne!(Unit::empty(), Unit::base(BaseUnit::Kilogram));
     assert_ne!(Unit::empty(), Unit::base(BaseUnit::Kelvin));
     assert_ne!(Unit::empty(), Unit::base(BaseUnit::Mole));
     assert_ne!(Unit::empty(), Unit::base(BaseUnit::Candela));
@@ -124,7 +126,7 @@ fn to_string_empty() {
 fn to_string_base() {
     assert_eq!("s", Unit::base(BaseUnit::Second).to_string());
     assert_eq!("m", Unit::base(BaseUnit::Meter).to_string());
-    assert_eq!("g", Unit::base(BaseUnit::Gram).to_string());
+    assert_eq!("kg", Unit::base(BaseUnit::Kilogram).to_string());
     assert_eq!("K", Unit::base(BaseUnit::Kelvin).to_string());
     assert_eq!("mol", Unit::base(BaseUnit::Mole).to_string());
     assert_eq!("cd", Unit::base(BaseUnit::Candela).to_string());
@@ -176,12 +178,36 @@ fn to_string_negative_float_power() {
 #[test]
 fn to_string_combined() {
     assert_eq!("m s", Unit::base(BaseUnit::Second).mul(Unit::base(BaseUnit::Meter)).to_string());
-    assert_eq!("g m s", Unit::base(BaseUnit::Gram).mul(Unit::base(BaseUnit::Second)).mul(Unit::base(BaseUnit::Meter)).to_string());
-    assert_eq!("g^-1 m^(3/4) s^12 mol^-1",
-        Unit::base(BaseUnit::Gram).pow(Number::neg_one())
+    assert_eq!("kg m s", Unit::base(BaseUnit::Kilogram).mul(Unit::base(BaseUnit::Second)).mul(Unit::base(BaseUnit::Meter)).to_string());
+    assert_eq!("kg^-1 m^(3/4) s^12 mol^-1",
+        Unit::base(BaseUnit::Kilogram).pow(Number::neg_one())
             .mul(Unit::base(BaseUnit::Second).pow(Number::from_i64(12)))
             .mul(Unit::base(BaseUnit::Meter).pow(Number::from_i64s(3, 4)))
             .div(Unit::base(BaseUnit::Mole)).to_string()
     );
 }
 
+
+#[test]
+fn custom_dimension() {
+    assert!(!Unit::dimension("USD").is_empty());
+    assert_eq!(Unit::dimension("USD"), Unit::dimension("USD"));
+    assert_ne!(Unit::dimension("USD"), Unit::dimension("px"));
+    assert_ne!(Unit::dimension("USD"), Unit::empty());
+    assert_eq!(Unit::empty(), Unit::dimension("USD").div(Unit::dimension("USD")));
+    assert_eq!(Unit::empty(), Unit::dimension("USD").pow(Number::zero()));
+    assert_eq!(
+        Unit::dimension("USD").pow(Number::from_i64(2)),
+        Unit::dimension("USD").mul(Unit::dimension("USD"))
+    );
+}
+
+#[test]
+fn to_string_custom_dimension() {
+    assert_eq!("USD", Unit::dimension("USD").to_string());
+    assert_eq!("s^-1 USD", Unit::dimension("USD").div(Unit::base(BaseUnit::Second)).to_string());
+    assert_eq!("USD^-2 px^(1/2)",
+        Unit::dimension("USD").pow(Number::from_i64(-2))
+            .mul(Unit::dimension("px").pow(Number::from_i64s(1, 2))).to_string()
+    );
+}